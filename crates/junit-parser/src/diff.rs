@@ -0,0 +1,201 @@
+use crate::{TestCase, TestStatus, TestSuite, TestSuites};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A test slows down "noticeably" when it takes at least this many times
+/// as long as before...
+pub const REGRESSION_RATIO: f64 = 1.2;
+/// ...and at least this many seconds longer, so millisecond jitter on
+/// fast tests is ignored.
+pub const REGRESSION_MIN_SECS: f64 = 0.1;
+
+/// Identifies a test across two runs: its suite, classname and name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct TestKey {
+    pub suite: String,
+    pub classname: Option<String>,
+    pub name: String,
+}
+
+impl TestKey {
+    pub fn new(suite: &TestSuite, tc: &TestCase) -> Self {
+        TestKey {
+            suite: suite.name.clone(),
+            classname: tc.classname.clone(),
+            name: tc.name.clone(),
+        }
+    }
+
+    /// `classname.name`, or `suite.name` when there is no classname.
+    pub fn label(&self) -> String {
+        format!(
+            "{}.{}",
+            self.classname.as_deref().unwrap_or(&self.suite),
+            self.name
+        )
+    }
+}
+
+/// A test whose outcome changed between runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusChange {
+    pub test: TestKey,
+    /// `None` when the test did not exist in the base run.
+    pub before: Option<TestStatus>,
+    pub after: TestStatus,
+    /// The failure or error message in the head run, if any.
+    pub message: Option<String>,
+}
+
+/// A test that got noticeably slower between runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DurationChange {
+    pub test: TestKey,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl DurationChange {
+    pub fn delta(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// What changed going from a base run to a head run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReportDiff {
+    /// Tests failing or erroring in head that weren't in base, including
+    /// new tests that fail.
+    pub new_failures: Vec<StatusChange>,
+    /// Tests that failed or errored in base and pass in head.
+    pub fixed: Vec<StatusChange>,
+    /// Tests failing in both runs.
+    pub still_failing: usize,
+    /// Slowest regressions first; see [`REGRESSION_RATIO`].
+    pub slower: Vec<DurationChange>,
+    /// Tests only in head.
+    pub added: usize,
+    /// Tests only in base.
+    pub removed: usize,
+}
+
+impl ReportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.new_failures.is_empty() && self.fixed.is_empty() && self.slower.is_empty()
+    }
+}
+
+/// Compares two runs of the same test suite. Tests are matched by suite,
+/// classname and name; duplicates keep their last occurrence.
+pub fn diff(base: &TestSuites, head: &TestSuites) -> ReportDiff {
+    let before: HashMap<TestKey, &TestCase> = keyed(base).collect();
+    let mut diff = ReportDiff::default();
+    let mut seen = 0;
+
+    for (key, tc) in keyed(head) {
+        let after = tc.status();
+        let old = before.get(&key);
+        if old.is_some() {
+            seen += 1;
+        } else {
+            diff.added += 1;
+        }
+        let old_status = old.map(|o| o.status());
+
+        match (old_status.is_some_and(is_failing), is_failing(after)) {
+            (false, true) => diff.new_failures.push(StatusChange {
+                message: failure_message(tc),
+                test: key.clone(),
+                before: old_status,
+                after,
+            }),
+            (true, true) => diff.still_failing += 1,
+            (true, false) if after == TestStatus::Passed => diff.fixed.push(StatusChange {
+                test: key.clone(),
+                before: old_status,
+                after,
+                message: None,
+            }),
+            _ => {}
+        }
+
+        if let (Some(before), Some(after)) = (old.and_then(|o| o.time), tc.time) {
+            if after >= before * REGRESSION_RATIO && after - before >= REGRESSION_MIN_SECS {
+                diff.slower.push(DurationChange {
+                    test: key,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+
+    diff.removed = before.len().saturating_sub(seen);
+    diff.slower.sort_by(|a, b| b.delta().total_cmp(&a.delta()));
+    diff
+}
+
+fn keyed(report: &TestSuites) -> impl Iterator<Item = (TestKey, &TestCase)> {
+    report
+        .all_cases()
+        .map(|(suite, tc)| (TestKey::new(suite, tc), tc))
+}
+
+fn is_failing(status: TestStatus) -> bool {
+    matches!(status, TestStatus::Failed | TestStatus::Errored)
+}
+
+fn failure_message(tc: &TestCase) -> Option<String> {
+    let failure = tc.failure.as_ref().and_then(|f| f.message.clone());
+    failure.or_else(|| tc.error.as_ref().and_then(|e| e.message.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_str;
+
+    const BASE: &str = r#"<testsuite name="s">
+        <testcase classname="C" name="stays_green" time="1.0"/>
+        <testcase classname="C" name="breaks" time="1.0"/>
+        <testcase classname="C" name="gets_fixed" time="1.0"><failure message="boom"/></testcase>
+        <testcase classname="C" name="stays_red" time="1.0"><error message="io"/></testcase>
+        <testcase classname="C" name="goes_away" time="1.0"/>
+    </testsuite>"#;
+
+    const HEAD: &str = r#"<testsuite name="s">
+        <testcase classname="C" name="stays_green" time="3.0"/>
+        <testcase classname="C" name="breaks" time="1.05"><failure message="expected 1"/></testcase>
+        <testcase classname="C" name="gets_fixed" time="1.0"/>
+        <testcase classname="C" name="stays_red" time="1.0"><error message="io"/></testcase>
+        <testcase classname="C" name="brand_new" time="0.1"><error message="npe"/></testcase>
+    </testsuite>"#;
+
+    #[test]
+    fn diff_classifies_changes() {
+        let diff = diff(&parse_str(BASE).unwrap(), &parse_str(HEAD).unwrap());
+
+        let new: Vec<&str> = diff
+            .new_failures
+            .iter()
+            .map(|c| c.test.name.as_str())
+            .collect();
+        assert_eq!(new, ["breaks", "brand_new"]);
+        assert_eq!(diff.new_failures[0].message.as_deref(), Some("expected 1"));
+        assert_eq!(diff.new_failures[1].before, None);
+
+        assert_eq!(diff.fixed.len(), 1);
+        assert_eq!(diff.fixed[0].test.label(), "C.gets_fixed");
+        assert_eq!(diff.still_failing, 1);
+        assert_eq!(diff.added, 1);
+        assert_eq!(diff.removed, 1);
+    }
+
+    #[test]
+    fn diff_ignores_small_slowdowns() {
+        let diff = diff(&parse_str(BASE).unwrap(), &parse_str(HEAD).unwrap());
+        assert_eq!(diff.slower.len(), 1);
+        assert_eq!(diff.slower[0].test.name, "stays_green");
+        assert_eq!(diff.slower[0].delta(), 2.0);
+    }
+}
@@ -0,0 +1,468 @@
+use crate::{TestStatus, TestSuite, TestSuites};
+use std::collections::HashMap;
+
+/// Signature of failures that carry neither a message nor a body.
+pub const NO_MESSAGE: &str = "(no message)";
+
+/// Failed and errored tests sharing the same normalised message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailureGroup {
+    /// The message with numbers and quoted values masked; see
+    /// [`normalize_message`].
+    pub signature: String,
+    /// The first member's message as written, for display.
+    pub example: String,
+    /// `(suite, case)` indices of the members, in report order.
+    pub cases: Vec<(usize, usize)>,
+}
+
+/// Groups every failed or errored test by its normalised message, largest
+/// group first. Groups of equal size keep the order of their first member.
+pub fn group_failures(report: &TestSuites) -> Vec<FailureGroup> {
+    let mut groups: Vec<FailureGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (si, suite) in report.suites.iter().enumerate() {
+        for (ti, tc) in suite.test_cases.iter().enumerate() {
+            let Some(text) = tc.failure_text() else {
+                continue;
+            };
+            let example = text
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .unwrap_or(NO_MESSAGE)
+                .to_string();
+            let signature = normalize_message(&example);
+            let i = *index.entry(signature.clone()).or_insert_with(|| {
+                groups.push(FailureGroup {
+                    signature,
+                    example,
+                    cases: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            groups[i].cases.push((si, ti));
+        }
+    }
+
+    groups.sort_by_key(|g| std::cmp::Reverse(g.cases.len()));
+    groups
+}
+
+/// Signature of failures whose text names no exception class.
+pub const NO_EXCEPTION: &str = "(no exception type)";
+
+/// Groups every failed or errored test by the exception class its text
+/// leads with (see [`exception_type`]), largest group first. Unlike
+/// [`group_failures`] this keeps together failures whose messages are all
+/// different, such as `NullPointerException`s from unrelated code.
+pub fn group_exceptions(report: &TestSuites) -> Vec<FailureGroup> {
+    let mut groups: Vec<FailureGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (si, suite) in report.suites.iter().enumerate() {
+        for (ti, tc) in suite.test_cases.iter().enumerate() {
+            let Some(text) = tc.failure_text() else {
+                continue;
+            };
+            let signature = exception_type(&text).unwrap_or(NO_EXCEPTION).to_string();
+            let i = *index.entry(signature.clone()).or_insert_with(|| {
+                let example = text
+                    .lines()
+                    .map(str::trim)
+                    .find(|l| !l.is_empty())
+                    .unwrap_or(NO_MESSAGE)
+                    .to_string();
+                groups.push(FailureGroup {
+                    signature,
+                    example,
+                    cases: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            groups[i].cases.push((si, ti));
+        }
+    }
+
+    groups.sort_by_key(|g| std::cmp::Reverse(g.cases.len()));
+    groups
+}
+
+/// The exception class a failure's text reports: the first line that
+/// starts with one in a Java trace (`java.lang.IllegalStateException: …`),
+/// or the unindented last one after a Python `Traceback` (`KeyError: 'x'`).
+/// pytest's `E   ` prefix is ignored. Only names ending in `Error`,
+/// `Exception`, `Failure` or `Throwable` count, so ordinary messages that
+/// happen to contain a colon don't.
+pub fn exception_type(text: &str) -> Option<&str> {
+    fn leading_class(line: &str) -> Option<&str> {
+        let line = match line.strip_prefix("E ") {
+            Some(rest) => rest.trim_start(),
+            None => line,
+        };
+        let end = line.find([':', ' ', '(']).unwrap_or(line.len());
+        let name = &line[..end];
+        let simple = name.rsplit(['.', '$']).next()?;
+        let valid = !name.is_empty()
+            && name.split('.').all(|part| {
+                part.chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_')
+                    && part
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+            })
+            && simple.starts_with(|c: char| c.is_uppercase())
+            && ["Error", "Exception", "Failure", "Throwable"]
+                .iter()
+                .any(|suffix| simple.ends_with(suffix));
+        valid.then_some(name)
+    }
+
+    if let Some(start) = text.find("Traceback (most recent call last):") {
+        let found = text[start..]
+            .lines()
+            .rev()
+            .filter(|l| !l.starts_with(char::is_whitespace))
+            .find_map(leading_class);
+        if found.is_some() {
+            return found;
+        }
+    }
+    text.lines().map(str::trim).find_map(leading_class)
+}
+
+/// Reason of skipped tests that give none.
+pub const NO_REASON: &str = "(no reason given)";
+
+/// Skipped tests sharing the same reason.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkipGroup {
+    /// The reason as written, with whitespace runs collapsed.
+    pub reason: String,
+    /// `(suite, case)` indices of the members, in report order.
+    pub cases: Vec<(usize, usize)>,
+}
+
+/// Groups every skipped test by its reason, largest group first. Unlike
+/// failures, reasons are compared as written: `requires JDK 17` and
+/// `requires JDK 21` are different reasons.
+pub fn group_skipped(report: &TestSuites) -> Vec<SkipGroup> {
+    let mut groups: Vec<SkipGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (si, suite) in report.suites.iter().enumerate() {
+        for (ti, tc) in suite.test_cases.iter().enumerate() {
+            let Some(skipped) = tc
+                .skipped
+                .as_ref()
+                .filter(|_| tc.status() == TestStatus::Skipped)
+            else {
+                continue;
+            };
+            let reason = match skipped.reason() {
+                Some(reason) => reason.split_whitespace().collect::<Vec<_>>().join(" "),
+                None => NO_REASON.to_string(),
+            };
+            let i = *index.entry(reason.clone()).or_insert_with(|| {
+                groups.push(SkipGroup {
+                    reason,
+                    cases: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            groups[i].cases.push((si, ti));
+        }
+    }
+
+    groups.sort_by_key(|g| std::cmp::Reverse(g.cases.len()));
+    groups
+}
+
+/// Masks the parts of a message that differ between otherwise identical
+/// failures: numbers (including hex) become `#` and quoted values become
+/// `'…'`. Whitespace runs collapse to a single space.
+///
+/// `Expected 401 but got "500"` and `Expected 404 but got "503"` both
+/// become `Expected # but got '…'`.
+pub fn normalize_message(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut chars = message.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '0'..='9' => {
+                // Swallow the rest of the number, including decimals and
+                // the tail of a `0x` hex literal.
+                let hex = c == '0' && chars.next_if_eq(&'x').is_some();
+                while chars
+                    .next_if(|&n| {
+                        n.is_ascii_digit() || n == '.' || n == '_' || hex && n.is_ascii_hexdigit()
+                    })
+                    .is_some()
+                {}
+                if !out.ends_with('#') {
+                    out.push('#');
+                }
+            }
+            // An apostrophe inside a word (`don't`) is not a quote.
+            '"' | '\'' | '`'
+                if !out.ends_with(char::is_alphanumeric) && chars.clone().any(|n| n == c) =>
+            {
+                for n in chars.by_ref() {
+                    if n == c {
+                        break;
+                    }
+                }
+                out.push_str("'…'");
+            }
+            c if c.is_whitespace() => {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ');
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    let trimmed = out.trim_end().to_string();
+    if trimmed.is_empty() {
+        NO_MESSAGE.to_string()
+    } else {
+        trimmed
+    }
+}
+
+/// Runs of one parameterized test within a suite.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterizedGroup {
+    /// The test's name without its parameters: `test_sum` for
+    /// `test_sum[1-2-3]`.
+    pub name: String,
+    /// Indices into the suite's test cases, in the order given.
+    pub cases: Vec<usize>,
+}
+
+/// Splits a parameterized test's name into its base and its bracketed
+/// parameters, as pytest (`test_sum[1-2-3]`), JUnit 4 (`sum[0]`) and
+/// JUnit 5 (`sum(int, int)[2]`) write them. `None` for other names.
+pub fn split_parameters(name: &str) -> Option<(&str, &str)> {
+    if !name.ends_with(']') {
+        return None;
+    }
+    // Find the `[` matching the final `]`, so nested brackets in the
+    // parameters stay with them.
+    let mut depth = 0;
+    for (i, c) in name.char_indices().rev() {
+        match c {
+            ']' => depth += 1,
+            '[' => {
+                depth -= 1;
+                if depth == 0 {
+                    let base = name[..i].trim_end();
+                    return (!base.is_empty()).then(|| (base, &name[i..]));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Groups the cases at `indices` of `suite` by class and base name, in the
+/// order each test first appears. Tests without parameters form groups of
+/// their own.
+pub fn group_parameterized(suite: &TestSuite, indices: &[usize]) -> Vec<ParameterizedGroup> {
+    let mut groups: Vec<ParameterizedGroup> = Vec::new();
+    let mut index: HashMap<(Option<&str>, &str), usize> = HashMap::new();
+
+    for &ti in indices {
+        let tc = &suite.test_cases[ti];
+        let base = split_parameters(&tc.name).map_or(tc.name.as_str(), |(base, _)| base);
+        let i = *index
+            .entry((tc.classname.as_deref(), base))
+            .or_insert_with(|| {
+                groups.push(ParameterizedGroup {
+                    name: base.to_string(),
+                    cases: Vec::new(),
+                });
+                groups.len() - 1
+            });
+        groups[i].cases.push(ti);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_str;
+
+    #[test]
+    fn normalize_masks_numbers_and_quotes() {
+        assert_eq!(
+            normalize_message("Expected 401 but got \"500\""),
+            "Expected # but got '…'"
+        );
+        assert_eq!(
+            normalize_message("Test KO; 852.0; over ; 10000.0; iterations."),
+            "Test KO; #; over ; #; iterations."
+        );
+        assert_eq!(
+            normalize_message("at 0x7ffd1234  in   frame"),
+            "at # in frame"
+        );
+        assert_eq!(normalize_message("it's 'fine'"), "it's '…'");
+        assert_eq!(normalize_message("step2failed"), "step#failed");
+        assert_eq!(normalize_message("   "), NO_MESSAGE);
+    }
+
+    #[test]
+    fn groups_largest_first() {
+        let report = parse_str(
+            r#"<testsuite name="s">
+                <testcase name="a"><error message="Connection refused to port 5432"/></testcase>
+                <testcase name="b"><failure message="expected 1"/></testcase>
+                <testcase name="c"/>
+                <testcase name="d"><failure message="Connection refused to port 6379"/></testcase>
+                <testcase name="e"><failure>boom&#10;  at Foo.bar</failure></testcase>
+            </testsuite>"#,
+        )
+        .unwrap();
+
+        let groups = group_failures(&report);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].signature, "Connection refused to port #");
+        assert_eq!(groups[0].example, "Connection refused to port 5432");
+        assert_eq!(groups[0].cases, [(0, 0), (0, 3)]);
+        assert_eq!(groups[1].signature, "expected #");
+        assert_eq!(groups[2].signature, "boom");
+    }
+
+    #[test]
+    fn exception_type_from_java_and_python_traces() {
+        assert_eq!(
+            exception_type(
+                "boom\n\njava.lang.IllegalStateException: boom\n\tat Foo.bar(Foo.java:3)"
+            ),
+            Some("java.lang.IllegalStateException")
+        );
+        assert_eq!(
+            exception_type("org.opentest4j.AssertionFailedError"),
+            Some("org.opentest4j.AssertionFailedError")
+        );
+        assert_eq!(
+            exception_type(
+                "Traceback (most recent call last):\n  File \"t.py\", line 3, in f\n    raise KeyError('x')\nKeyError: 'x'"
+            ),
+            Some("KeyError")
+        );
+        assert_eq!(
+            exception_type(
+                "def test_sum():\n>       assert 1 == 2\nE       AssertionError: assert 1 == 2"
+            ),
+            Some("AssertionError")
+        );
+        assert_eq!(exception_type("Expected: 3 but was: 4"), None);
+        assert_eq!(exception_type("Connection refused"), None);
+    }
+
+    #[test]
+    fn groups_by_exception_type() {
+        let report = parse_str(
+            r#"<testsuite name="s">
+                <testcase name="a"><error message="x was null">java.lang.NullPointerException: x was null</error></testcase>
+                <testcase name="b"><failure message="timed out"/></testcase>
+                <testcase name="c"><error>java.lang.NullPointerException&#10;  at Foo.bar</error></testcase>
+            </testsuite>"#,
+        )
+        .unwrap();
+
+        let groups = group_exceptions(&report);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].signature, "java.lang.NullPointerException");
+        assert_eq!(groups[0].example, "x was null");
+        assert_eq!(groups[0].cases, [(0, 0), (0, 2)]);
+        assert_eq!(groups[1].signature, NO_EXCEPTION);
+    }
+
+    #[test]
+    fn groups_skips_by_reason() {
+        let report = parse_str(
+            r#"<testsuite name="s">
+                <testcase name="a"><skipped message="requires docker"/></testcase>
+                <testcase name="b"><skipped/></testcase>
+                <testcase name="c"><skipped>
+                    requires   docker
+                </skipped></testcase>
+                <testcase name="d"/>
+                <testcase name="e"><skipped message="flaky on CI"/></testcase>
+            </testsuite>"#,
+        )
+        .unwrap();
+
+        let groups = group_skipped(&report);
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| (g.reason.as_str(), g.cases.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("requires docker", vec![(0, 0), (0, 2)]),
+                (NO_REASON, vec![(0, 1)]),
+                ("flaky on CI", vec![(0, 4)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_parameters_finds_bracketed_suffix() {
+        assert_eq!(
+            split_parameters("test_sum[1-2-3]"),
+            Some(("test_sum", "[1-2-3]"))
+        );
+        assert_eq!(
+            split_parameters("sum(int, int)[2]"),
+            Some(("sum(int, int)", "[2]"))
+        );
+        assert_eq!(
+            split_parameters("test_list[[1, 2]-x]"),
+            Some(("test_list", "[[1, 2]-x]"))
+        );
+        assert_eq!(split_parameters("test_plain"), None);
+        assert_eq!(split_parameters("[1]"), None);
+        assert_eq!(split_parameters("odd]"), None);
+    }
+
+    #[test]
+    fn groups_parameterized_cases_by_base_name() {
+        let report = parse_str(
+            r#"<testsuite name="s">
+                <testcase classname="A" name="test_sum[1-2-3]"/>
+                <testcase classname="A" name="test_other"/>
+                <testcase classname="A" name="test_sum[4-5-9]"><failure/></testcase>
+                <testcase classname="B" name="test_sum[1-2-3]"/>
+            </testsuite>"#,
+        )
+        .unwrap();
+        let suite = &report.suites[0];
+
+        let groups = group_parameterized(suite, &[0, 1, 2, 3]);
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| (g.name.as_str(), g.cases.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("test_sum", vec![0, 2]),
+                ("test_other", vec![1]),
+                ("test_sum", vec![3]),
+            ]
+        );
+
+        let groups = group_parameterized(suite, &[2, 1]);
+        assert_eq!(groups[0].cases, [2]);
+        assert_eq!(groups[1].cases, [1]);
+    }
+}
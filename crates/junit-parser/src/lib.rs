@@ -1,8 +1,28 @@
+pub mod diff;
+mod group;
+mod package;
+mod write;
+
+pub use diff::{diff, ReportDiff};
+pub use group::{
+    exception_type, group_exceptions, group_failures, group_parameterized, group_skipped,
+    normalize_message, split_parameters, FailureGroup, ParameterizedGroup, SkipGroup,
+};
+pub use package::{split_classname, PackageNode};
+pub use write::write_xml;
+
 use anyhow::{Context, Result};
-use serde::Deserialize;
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::path::Path;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Upper bounds, in seconds, of the buckets [`TestSuite::duration_histogram`]
+/// counts into; one more bucket holds everything slower than the last.
+pub const DURATION_BUCKETS: [f64; 5] = [0.001, 0.01, 0.1, 1.0, 10.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TestStatus {
     Passed,
     Failed,
@@ -10,17 +30,73 @@ pub enum TestStatus {
     Skipped,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl TestStatus {
+    pub const ALL: [TestStatus; 4] = [
+        TestStatus::Passed,
+        TestStatus::Failed,
+        TestStatus::Errored,
+        TestStatus::Skipped,
+    ];
+
+    /// Ordering used when grouping by status: problems first, passes last.
+    fn severity_rank(self) -> u8 {
+        match self {
+            TestStatus::Failed => 0,
+            TestStatus::Errored => 1,
+            TestStatus::Skipped => 2,
+            TestStatus::Passed => 3,
+        }
+    }
+}
+
+/// Overall outcome of a suite, derived from its counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuiteStatus {
+    /// No failures or errors; skipped tests may be present alongside passes.
+    AllPassed,
+    /// At least one failure or error.
+    HasFailures,
+    /// Every test in the suite was skipped.
+    AllSkipped,
+    /// The suite reports no tests at all.
+    Empty,
+}
+
+/// Orderings available for a suite's test cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaseOrder {
+    /// Slowest first; cases without a time go last.
+    Time,
+    Name,
+    /// By classname, then name; cases without a classname go last.
+    Class,
+    /// Failed, errored, skipped, then passed.
+    Status,
+}
+
+/// Orderings available for a report's suites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuiteOrder {
+    Name,
+    /// Most failures plus errors first.
+    Failures,
+    /// Slowest first; suites without a time go last.
+    Time,
+    /// Earliest first; suites without a timestamp go last.
+    Timestamp,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TestSuites {
-    #[serde(rename = "@tests", default)]
+    #[serde(rename(deserialize = "@tests"), default)]
     pub tests: Option<u64>,
-    #[serde(rename = "@failures", default)]
+    #[serde(rename(deserialize = "@failures"), default)]
     pub failures: Option<u64>,
-    #[serde(rename = "@errors", default)]
+    #[serde(rename(deserialize = "@errors"), default)]
     pub errors: Option<u64>,
-    #[serde(rename = "@skipped", default)]
+    #[serde(rename(deserialize = "@skipped"), default)]
     pub skipped: Option<u64>,
-    #[serde(rename = "testsuite", default)]
+    #[serde(rename(deserialize = "testsuite"), default)]
     pub suites: Vec<TestSuite>,
 }
 
@@ -46,53 +122,317 @@ impl TestSuites {
         let non_pass = self.total_failures() + self.total_errors() + self.total_skipped();
         total.saturating_sub(non_pass)
     }
+
+    /// Keeps only test cases whose status is in `statuses`, dropping suites
+    /// left empty and recomputing all counts from the remaining cases.
+    pub fn retain_status(&mut self, statuses: &[TestStatus]) {
+        self.retain_cases(|tc| statuses.contains(&tc.status()));
+    }
+
+    /// Keeps only test cases for which `keep` returns true, dropping suites
+    /// left empty and recomputing all counts from the remaining cases.
+    pub fn retain_cases(&mut self, mut keep: impl FnMut(&TestCase) -> bool) {
+        for suite in &mut self.suites {
+            suite.retain_cases(&mut keep);
+        }
+        self.suites.retain(|s| !s.test_cases.is_empty());
+        self.recount();
+    }
+
+    /// The earliest suite timestamp, as written in the report. ISO 8601
+    /// timestamps in the same format order correctly as strings.
+    pub fn timestamp(&self) -> Option<&str> {
+        self.suites
+            .iter()
+            .filter_map(|s| s.timestamp.as_deref())
+            .min()
+    }
+
+    /// The `limit` slowest test cases across all suites as `(suite, case)`
+    /// index pairs, slowest first. Cases without a time are left out.
+    pub fn slowest(&self, limit: usize) -> Vec<(usize, usize)> {
+        let mut cases: Vec<(usize, usize)> = self
+            .suites
+            .iter()
+            .enumerate()
+            .flat_map(|(si, s)| {
+                s.test_cases
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, tc)| tc.time.is_some())
+                    .map(move |(ti, _)| (si, ti))
+            })
+            .collect();
+        let time = |&(si, ti): &(usize, usize)| self.suites[si].test_cases[ti].time;
+        cases.sort_by(|a, b| cmp_time_desc(time(a), time(b)));
+        cases.truncate(limit);
+        cases
+    }
+
+    /// [`TestSuite::duration_histogram`] over every suite.
+    pub fn duration_histogram(&self) -> [usize; DURATION_BUCKETS.len() + 1] {
+        let mut counts = [0; DURATION_BUCKETS.len() + 1];
+        for suite in &self.suites {
+            for (total, count) in counts.iter_mut().zip(suite.duration_histogram()) {
+                *total += count;
+            }
+        }
+        counts
+    }
+
+    /// Sum of every test case's time.
+    pub fn total_case_time(&self) -> f64 {
+        self.all_cases().filter_map(|(_, tc)| tc.time).sum()
+    }
+
+    /// Concatenates the suites of several reports into one, in order, with
+    /// counts recomputed from the merged suites.
+    pub fn merge<'a>(reports: impl IntoIterator<Item = &'a TestSuites>) -> TestSuites {
+        let mut merged = TestSuites {
+            tests: None,
+            failures: None,
+            errors: None,
+            skipped: None,
+            suites: reports
+                .into_iter()
+                .flat_map(|r| r.suites.iter().cloned())
+                .collect(),
+        };
+        merged.recount();
+        merged
+    }
+
+    /// Iterates over every test case in every suite, paired with its suite.
+    pub fn all_cases(&self) -> impl Iterator<Item = (&TestSuite, &TestCase)> {
+        self.suites
+            .iter()
+            .flat_map(|s| s.test_cases.iter().map(move |tc| (s, tc)))
+    }
+
+    /// Indices into `suites` arranged by `order`. Sorting is stable, so
+    /// ties keep their report order.
+    pub fn order_suite_indices(&self, order: SuiteOrder) -> Vec<usize> {
+        let suites = &self.suites;
+        let mut indices: Vec<usize> = (0..suites.len()).collect();
+        match order {
+            SuiteOrder::Name => indices.sort_by(|&a, &b| suites[a].name.cmp(&suites[b].name)),
+            SuiteOrder::Failures => {
+                indices.sort_by_key(|&i| std::cmp::Reverse(suites[i].failures + suites[i].errors))
+            }
+            SuiteOrder::Time => {
+                indices.sort_by(|&a, &b| cmp_time_desc(suites[a].time, suites[b].time))
+            }
+            SuiteOrder::Timestamp => {
+                indices.sort_by(
+                    |&a, &b| match (&suites[a].timestamp, &suites[b].timestamp) {
+                        (Some(x), Some(y)) => x.cmp(y),
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => std::cmp::Ordering::Equal,
+                    },
+                )
+            }
+        }
+        indices
+    }
+
+    pub fn sort_suites_by_name(&mut self) {
+        self.suites.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Sorts suites slowest first; suites without a time go last.
+    pub fn sort_suites_by_time(&mut self) {
+        self.suites.sort_by(|a, b| cmp_time_desc(a.time, b.time));
+    }
+
+    fn recount(&mut self) {
+        self.tests = Some(self.total_tests());
+        self.failures = Some(self.total_failures());
+        self.errors = Some(self.total_errors());
+        self.skipped = Some(self.total_skipped());
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TestSuite {
-    #[serde(rename = "@name", default)]
+    #[serde(rename(deserialize = "@name"), default)]
     pub name: String,
-    #[serde(rename = "@timestamp", default)]
+    #[serde(rename(deserialize = "@timestamp"), default)]
     pub timestamp: Option<String>,
-    #[serde(rename = "@time", default)]
+    #[serde(rename(deserialize = "@hostname"), default)]
+    pub hostname: Option<String>,
+    #[serde(rename(deserialize = "@time"), default)]
     pub time: Option<f64>,
-    #[serde(rename = "@tests", default)]
+    #[serde(rename(deserialize = "@tests"), default)]
     pub tests: u64,
-    #[serde(rename = "@failures", default)]
+    #[serde(rename(deserialize = "@failures"), default)]
     pub failures: u64,
-    #[serde(rename = "@errors", default)]
+    #[serde(rename(deserialize = "@errors"), default)]
     pub errors: u64,
-    #[serde(rename = "@skipped", default)]
+    #[serde(rename(deserialize = "@skipped"), default)]
     pub skipped: Option<u64>,
     #[serde(default)]
     pub properties: Option<Properties>,
-    #[serde(rename = "testcase", default)]
+    #[serde(rename(deserialize = "testcase"), default)]
     pub test_cases: Vec<TestCase>,
+    /// Output captured outside any test, such as fixture setup and
+    /// teardown.
+    #[serde(default, rename(deserialize = "system-out"))]
+    pub system_out: Option<String>,
+    #[serde(default, rename(deserialize = "system-err"))]
+    pub system_err: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl TestSuite {
+    pub fn passed(&self) -> u64 {
+        self.tests
+            .saturating_sub(self.failures + self.errors + self.skipped.unwrap_or(0))
+    }
+
+    pub fn status(&self) -> SuiteStatus {
+        let skipped = self.skipped.unwrap_or(0);
+        if self.failures > 0 || self.errors > 0 {
+            SuiteStatus::HasFailures
+        } else if self.tests == 0 {
+            SuiteStatus::Empty
+        } else if skipped >= self.tests {
+            SuiteStatus::AllSkipped
+        } else {
+            SuiteStatus::AllPassed
+        }
+    }
+
+    pub fn cases_with_status<'a>(
+        &'a self,
+        statuses: &'a [TestStatus],
+    ) -> impl Iterator<Item = &'a TestCase> + 'a {
+        self.test_cases
+            .iter()
+            .filter(move |tc| statuses.contains(&tc.status()))
+    }
+
+    /// Indices into `test_cases` arranged by `order`. Sorting is stable, so
+    /// ties keep their report order.
+    pub fn order_indices(&self, order: CaseOrder) -> Vec<usize> {
+        let cases = &self.test_cases;
+        let mut indices: Vec<usize> = (0..cases.len()).collect();
+        match order {
+            CaseOrder::Time => {
+                indices.sort_by(|&a, &b| cmp_time_desc(cases[a].time, cases[b].time))
+            }
+            CaseOrder::Name => indices.sort_by(|&a, &b| cases[a].name.cmp(&cases[b].name)),
+            CaseOrder::Class => indices.sort_by(|&a, &b| {
+                let class = |i: usize| (cases[i].classname.is_none(), &cases[i].classname);
+                class(a)
+                    .cmp(&class(b))
+                    .then_with(|| cases[a].name.cmp(&cases[b].name))
+            }),
+            CaseOrder::Status => indices.sort_by_key(|&i| cases[i].status().severity_rank()),
+        }
+        indices
+    }
+
+    pub fn sorted(&self, order: CaseOrder) -> Vec<&TestCase> {
+        self.order_indices(order)
+            .into_iter()
+            .map(|i| &self.test_cases[i])
+            .collect()
+    }
+
+    /// Test cases ordered slowest first; cases without a time go last.
+    pub fn sorted_by_time(&self) -> Vec<&TestCase> {
+        self.sorted(CaseOrder::Time)
+    }
+
+    pub fn sorted_by_name(&self) -> Vec<&TestCase> {
+        self.sorted(CaseOrder::Name)
+    }
+
+    /// Test cases grouped failed, errored, skipped, passed; original order
+    /// is kept within each group.
+    pub fn sorted_by_status(&self) -> Vec<&TestCase> {
+        self.sorted(CaseOrder::Status)
+    }
+
+    /// How many timed test cases fall in each of the [`DURATION_BUCKETS`],
+    /// fastest first, with the last count for cases slower than them all.
+    pub fn duration_histogram(&self) -> [usize; DURATION_BUCKETS.len() + 1] {
+        let mut counts = [0; DURATION_BUCKETS.len() + 1];
+        for time in self.test_cases.iter().filter_map(|tc| tc.time) {
+            let bucket = DURATION_BUCKETS
+                .iter()
+                .position(|&bound| time < bound)
+                .unwrap_or(DURATION_BUCKETS.len());
+            counts[bucket] += 1;
+        }
+        counts
+    }
+
+    /// Keeps only test cases whose status is in `statuses` and recomputes
+    /// the suite's counts from what is left.
+    pub fn retain_status(&mut self, statuses: &[TestStatus]) {
+        self.retain_cases(|tc| statuses.contains(&tc.status()));
+    }
+
+    /// Keeps only test cases for which `keep` returns true and recomputes
+    /// the suite's counts from what is left.
+    pub fn retain_cases(&mut self, mut keep: impl FnMut(&TestCase) -> bool) {
+        self.test_cases.retain(|tc| keep(tc));
+        self.recount();
+    }
+
+    fn recount(&mut self) {
+        let count = |status| {
+            self.test_cases
+                .iter()
+                .filter(|tc| tc.status() == status)
+                .count() as u64
+        };
+        let failures = count(TestStatus::Failed);
+        let errors = count(TestStatus::Errored);
+        let skipped = count(TestStatus::Skipped);
+        self.tests = self.test_cases.len() as u64;
+        self.failures = failures;
+        self.errors = errors;
+        self.skipped = Some(skipped);
+    }
+}
+
+fn cmp_time_desc(a: Option<f64>, b: Option<f64>) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    match (a, b) {
+        (Some(a), Some(b)) => b.partial_cmp(&a).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Properties {
-    #[serde(rename = "property", default)]
+    #[serde(rename(deserialize = "property"), default)]
     pub properties: Vec<Property>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Property {
-    #[serde(rename = "@name")]
+    #[serde(rename(deserialize = "@name"))]
     pub name: String,
-    #[serde(rename = "@value")]
+    #[serde(rename(deserialize = "@value"))]
     pub value: String,
 }
 
+/// Serializes with its computed [`TestStatus`] as a `status` field, so JSON
+/// consumers needn't work it out from `failure`, `error` and `skipped`.
 #[derive(Debug, Clone, Deserialize)]
 pub struct TestCase {
-    #[serde(rename = "@classname", default)]
+    #[serde(rename(deserialize = "@classname"), default)]
     pub classname: Option<String>,
-    #[serde(rename = "@name", default)]
+    #[serde(rename(deserialize = "@name"), default)]
     pub name: String,
-    #[serde(rename = "@time", default)]
+    #[serde(rename(deserialize = "@time"), default)]
     pub time: Option<f64>,
-    #[serde(rename = "@file", default)]
+    #[serde(rename(deserialize = "@file"), default)]
     pub file: Option<String>,
     #[serde(default)]
     pub failure: Option<Failure>,
@@ -100,10 +440,44 @@ pub struct TestCase {
     pub error: Option<TestError>,
     #[serde(default)]
     pub skipped: Option<Skipped>,
-    #[serde(default, rename = "system-out")]
+    #[serde(default, rename(deserialize = "system-out"))]
     pub system_out: Option<String>,
-    #[serde(default, rename = "system-err")]
+    #[serde(default, rename(deserialize = "system-err"))]
     pub system_err: Option<String>,
+    #[serde(default, rename(deserialize = "flakyFailure"))]
+    pub flaky_failures: Vec<Rerun>,
+    #[serde(default, rename(deserialize = "flakyError"))]
+    pub flaky_errors: Vec<Rerun>,
+    #[serde(default, rename(deserialize = "rerunFailure"))]
+    pub rerun_failures: Vec<Rerun>,
+    #[serde(default, rename(deserialize = "rerunError"))]
+    pub rerun_errors: Vec<Rerun>,
+    /// Byte range of the `<testcase>` element in the source XML. Only set
+    /// by [`parse_str_with_spans`].
+    #[serde(skip)]
+    pub span: Option<Range<usize>>,
+}
+
+impl Serialize for TestCase {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut case = serializer.serialize_struct("TestCase", 14)?;
+        case.serialize_field("classname", &self.classname)?;
+        case.serialize_field("name", &self.name)?;
+        case.serialize_field("status", &self.status())?;
+        case.serialize_field("time", &self.time)?;
+        case.serialize_field("file", &self.file)?;
+        case.serialize_field("failure", &self.failure)?;
+        case.serialize_field("error", &self.error)?;
+        case.serialize_field("skipped", &self.skipped)?;
+        case.serialize_field("system_out", &self.system_out)?;
+        case.serialize_field("system_err", &self.system_err)?;
+        case.serialize_field("flaky_failures", &self.flaky_failures)?;
+        case.serialize_field("flaky_errors", &self.flaky_errors)?;
+        case.serialize_field("rerun_failures", &self.rerun_failures)?;
+        case.serialize_field("rerun_errors", &self.rerun_errors)?;
+        case.end()
+    }
 }
 
 impl TestCase {
@@ -118,28 +492,114 @@ impl TestCase {
             TestStatus::Passed
         }
     }
+
+    /// The failure (or error) message followed by its body, separated by a
+    /// blank line. `None` for tests that neither failed nor errored.
+    pub fn failure_text(&self) -> Option<String> {
+        let (message, body) = match (&self.failure, &self.error) {
+            (Some(f), _) => (f.message.as_deref(), f.body.as_deref()),
+            (None, Some(e)) => (e.message.as_deref(), e.body.as_deref()),
+            (None, None) => return None,
+        };
+        let parts: Vec<&str> = [message, body]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+        Some(parts.join("\n\n"))
+    }
+
+    /// The first non-blank line of [`failure_text`](Self::failure_text),
+    /// as a one-line summary for lists and notifications.
+    pub fn failure_line(&self) -> Option<&str> {
+        let (message, body) = match (&self.failure, &self.error) {
+            (Some(f), _) => (f.message.as_deref(), f.body.as_deref()),
+            (None, Some(e)) => (e.message.as_deref(), e.body.as_deref()),
+            (None, None) => return None,
+        };
+        [message, body]
+            .into_iter()
+            .flatten()
+            .flat_map(str::lines)
+            .map(str::trim)
+            .find(|l| !l.is_empty())
+    }
+
+    /// Passed, but only after one or more failed attempts.
+    pub fn is_flaky(&self) -> bool {
+        self.status() == TestStatus::Passed
+            && !(self.flaky_failures.is_empty() && self.flaky_errors.is_empty())
+    }
+
+    /// Every recorded retry: flaky attempts before a pass, then reruns of a
+    /// test that never passed. Failures come before errors in each.
+    pub fn reruns(&self) -> impl Iterator<Item = &Rerun> {
+        self.flaky_failures
+            .iter()
+            .chain(&self.flaky_errors)
+            .chain(&self.rerun_failures)
+            .chain(&self.rerun_errors)
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// One failed attempt of a retried test, as written by Maven Surefire's
+/// `rerunFailingTestsCount`: `<flakyFailure>` / `<flakyError>` when a later
+/// attempt passed, `<rerunFailure>` / `<rerunError>` when none did.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Rerun {
+    #[serde(rename(deserialize = "@message"), default)]
+    pub message: Option<String>,
+    #[serde(rename(deserialize = "@type"), default)]
+    pub kind: Option<String>,
+    #[serde(rename(deserialize = "stackTrace"), default)]
+    pub stack_trace: Option<String>,
+    #[serde(default, rename(deserialize = "system-out"))]
+    pub system_out: Option<String>,
+    #[serde(default, rename(deserialize = "system-err"))]
+    pub system_err: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Failure {
-    #[serde(rename = "@message", default)]
+    #[serde(rename(deserialize = "@message"), default)]
     pub message: Option<String>,
-    #[serde(rename = "$text", default)]
+    #[serde(rename(deserialize = "$text"), default)]
     pub body: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TestError {
-    #[serde(rename = "@message", default)]
+    #[serde(rename(deserialize = "@message"), default)]
     pub message: Option<String>,
-    #[serde(rename = "$text", default)]
+    #[serde(rename(deserialize = "$text"), default)]
     pub body: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Skipped {
-    #[serde(rename = "$text", default)]
+    #[serde(rename(deserialize = "@message"), default)]
     pub message: Option<String>,
+    #[serde(rename(deserialize = "$text"), default)]
+    pub body: Option<String>,
+}
+
+impl Skipped {
+    /// Why the test was skipped: the `message` attribute, or else the
+    /// first non-blank line of the element's text.
+    pub fn reason(&self) -> Option<&str> {
+        self.message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .or_else(|| {
+                self.body
+                    .as_deref()?
+                    .lines()
+                    .map(str::trim)
+                    .find(|l| !l.is_empty())
+            })
+    }
 }
 
 pub fn parse_str(xml: &str) -> Result<TestSuites> {
@@ -170,13 +630,70 @@ pub fn parse_str(xml: &str) -> Result<TestSuites> {
     }
 }
 
+/// Like [`parse_str`], but also records where each test case's element
+/// sits in `xml`, so its original markup can be shown.
+pub fn parse_str_with_spans(xml: &str) -> Result<TestSuites> {
+    let mut suites = parse_str(xml)?;
+    let mut spans = testcase_spans(xml)?.into_iter();
+    for tc in suites.suites.iter_mut().flat_map(|s| &mut s.test_cases) {
+        tc.span = spans.next();
+    }
+    Ok(suites)
+}
+
+/// Byte ranges of every `<testcase>` element in `xml`, in document order,
+/// which is the order [`parse_str`] lists them in.
+pub fn testcase_spans(xml: &str) -> Result<Vec<Range<usize>>> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut spans = Vec::new();
+    loop {
+        let start = reader.buffer_position() as usize;
+        match reader.read_event().context("Failed to scan JUnit XML")? {
+            Event::Start(e) if e.name().as_ref() == b"testcase" => {
+                reader
+                    .read_to_end(e.name())
+                    .context("Unclosed <testcase> element")?;
+                spans.push(start..reader.buffer_position() as usize);
+            }
+            Event::Empty(e) if e.name().as_ref() == b"testcase" => {
+                spans.push(start..reader.buffer_position() as usize);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(spans)
+}
+
 pub fn parse_file(path: &Path) -> Result<TestSuites> {
+    read_with(path, parse_str)
+}
+
+/// Like [`parse_file`], recording spans as [`parse_str_with_spans`] does.
+pub fn parse_file_with_spans(path: &Path) -> Result<TestSuites> {
+    read_with(path, parse_str_with_spans)
+}
+
+fn read_with(path: &Path, parse: fn(&str) -> Result<TestSuites>) -> Result<TestSuites> {
     let content = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read file: {}", path.display()))?;
-    parse_str(&content)
+    parse(&content)
 }
 
 pub fn parse_directory(path: &Path) -> Result<Vec<(String, TestSuites)>> {
+    read_directory_with(path, parse_str)
+}
+
+/// Like [`parse_directory`], recording spans as [`parse_str_with_spans`]
+/// does.
+pub fn parse_directory_with_spans(path: &Path) -> Result<Vec<(String, TestSuites)>> {
+    read_directory_with(path, parse_str_with_spans)
+}
+
+fn read_directory_with(
+    path: &Path,
+    parse: fn(&str) -> Result<TestSuites>,
+) -> Result<Vec<(String, TestSuites)>> {
     let mut results = Vec::new();
 
     let entries = std::fs::read_dir(path)
@@ -187,7 +704,7 @@ pub fn parse_directory(path: &Path) -> Result<Vec<(String, TestSuites)>> {
         let file_path = entry.path();
         if file_path.extension().is_some_and(|ext| ext == "xml") {
             let filename = entry.file_name().to_string_lossy().into_owned();
-            let suites = parse_file(&file_path)
+            let suites = read_with(&file_path, parse)
                 .with_context(|| format!("Failed to parse: {}", file_path.display()))?;
             results.push((filename, suites));
         }
@@ -221,6 +738,109 @@ mod tests {
         assert_eq!(auth_suite.test_cases.len(), 8);
     }
 
+    #[test]
+    fn parse_suite_hostname() {
+        let suites = parse_str(
+            r#"<testsuite name="s" hostname="ci-runner-7" timestamp="2024-01-15T10:30:00"/>"#,
+        )
+        .unwrap();
+        assert_eq!(suites.suites[0].hostname.as_deref(), Some("ci-runner-7"));
+        assert_eq!(
+            suites.suites[0].timestamp.as_deref(),
+            Some("2024-01-15T10:30:00")
+        );
+    }
+
+    #[test]
+    fn parse_str_with_spans_records_testcase_markup() {
+        let xml = r#"<testsuites>
+            <testsuite name="a">
+                <testcase name="one" custom="x"/>
+                <testcase name="two"><failure message="m">boom</failure></testcase>
+            </testsuite>
+            <testsuite name="b"><testcase name="three"></testcase></testsuite>
+        </testsuites>"#;
+        let suites = parse_str_with_spans(xml).unwrap();
+        let source = |si: usize, ti: usize| {
+            let span = suites.suites[si].test_cases[ti].span.clone().unwrap();
+            &xml[span]
+        };
+        assert_eq!(source(0, 0), r#"<testcase name="one" custom="x"/>"#);
+        assert_eq!(
+            source(0, 1),
+            r#"<testcase name="two"><failure message="m">boom</failure></testcase>"#
+        );
+        assert_eq!(source(1, 0), r#"<testcase name="three"></testcase>"#);
+
+        assert!(parse_str(xml).unwrap().suites[0].test_cases[0]
+            .span
+            .is_none());
+    }
+
+    #[test]
+    fn parse_suite_system_out_and_err() {
+        let suites = parse_str(
+            r#"<testsuites>
+                <testsuite name="s">
+                    <testcase name="t"><system-out>case out</system-out></testcase>
+                    <system-out><![CDATA[setUpClass: connecting to db]]></system-out>
+                    <system-err>WARN pool exhausted</system-err>
+                </testsuite>
+            </testsuites>"#,
+        )
+        .unwrap();
+        let suite = &suites.suites[0];
+        assert_eq!(suite.test_cases.len(), 1);
+        assert_eq!(
+            suite.system_out.as_deref(),
+            Some("setUpClass: connecting to db")
+        );
+        assert_eq!(suite.system_err.as_deref(), Some("WARN pool exhausted"));
+        assert_eq!(suite.test_cases[0].system_out.as_deref(), Some("case out"));
+    }
+
+    #[test]
+    fn parse_flaky_and_rerun_attempts() {
+        let suites = parse_str(
+            r#"<testsuite name="s">
+                <testcase name="flaky">
+                    <flakyFailure message="timed out" type="java.util.concurrent.TimeoutException">
+                        <stackTrace>at Foo.bar(Foo.java:3)</stackTrace>
+                        <system-out>attempt 1</system-out>
+                    </flakyFailure>
+                    <flakyError message="connection reset"/>
+                </testcase>
+                <testcase name="broken">
+                    <failure message="boom"/>
+                    <rerunFailure message="boom again"/>
+                </testcase>
+                <testcase name="stable"/>
+            </testsuite>"#,
+        )
+        .unwrap();
+        let cases = &suites.suites[0].test_cases;
+
+        assert!(cases[0].is_flaky());
+        let attempts: Vec<_> = cases[0].reruns().collect();
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].message.as_deref(), Some("timed out"));
+        assert_eq!(
+            attempts[0].kind.as_deref(),
+            Some("java.util.concurrent.TimeoutException")
+        );
+        assert_eq!(
+            attempts[0].stack_trace.as_deref(),
+            Some("at Foo.bar(Foo.java:3)")
+        );
+        assert_eq!(attempts[0].system_out.as_deref(), Some("attempt 1"));
+        assert_eq!(attempts[1].message.as_deref(), Some("connection reset"));
+
+        assert!(!cases[1].is_flaky());
+        assert_eq!(cases[1].status(), TestStatus::Failed);
+        assert_eq!(cases[1].reruns().count(), 1);
+        assert!(!cases[2].is_flaky());
+    }
+
     #[test]
     fn parse_failure_with_cdata() {
         let path = test_reports_dir().join("sample-mixed-results.xml");
@@ -330,4 +950,229 @@ mod tests {
         let suites = parse_file(&path).unwrap();
         assert_eq!(suites.total_passed(), 16);
     }
+
+    #[test]
+    fn sorted_by_time_slowest_first() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let sorted = suites.suites[0].sorted_by_time();
+        assert_eq!(sorted.len(), 8);
+        assert_eq!(sorted[0].name, "testLoginWithExpiredToken");
+        assert_eq!(sorted[7].name, "testLoginWithSAML");
+    }
+
+    #[test]
+    fn sorted_by_status_puts_failures_first() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let sorted = suites.suites[1].sorted_by_status();
+        assert_eq!(sorted[0].status(), TestStatus::Failed);
+        assert_eq!(sorted[1].status(), TestStatus::Failed);
+        assert_eq!(sorted[2].status(), TestStatus::Errored);
+        assert_eq!(sorted.last().unwrap().status(), TestStatus::Passed);
+    }
+
+    #[test]
+    fn retain_status_recounts() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let mut suites = parse_file(&path).unwrap();
+        suites.retain_status(&[TestStatus::Failed, TestStatus::Errored]);
+        assert_eq!(suites.total_tests(), 4);
+        assert_eq!(suites.total_failures(), 3);
+        assert_eq!(suites.total_errors(), 1);
+        assert_eq!(suites.total_skipped(), 0);
+        assert_eq!(suites.tests, Some(4));
+        assert!(suites.suites.iter().all(|s| !s.test_cases.is_empty()));
+    }
+
+    #[test]
+    fn retain_cases_drops_empty_suites() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let mut suites = parse_file(&path).unwrap();
+        let first = suites.suites[0].test_cases[0].classname.clone();
+        let count = suites.suites[0]
+            .test_cases
+            .iter()
+            .filter(|tc| tc.classname == first)
+            .count() as u64;
+        suites.retain_cases(|tc| tc.classname == first);
+        assert_eq!(suites.suites.len(), 1);
+        assert_eq!(suites.total_tests(), count);
+        assert_eq!(suites.tests, Some(count));
+    }
+
+    #[test]
+    fn suite_status_and_passed() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let mut suites = parse_file(&path).unwrap();
+        let auth = &suites.suites[0];
+        assert_eq!(auth.status(), SuiteStatus::HasFailures);
+        assert_eq!(auth.passed(), 6);
+
+        suites.retain_status(&[TestStatus::Skipped]);
+        assert_eq!(suites.suites[0].status(), SuiteStatus::AllSkipped);
+
+        suites.suites[0].retain_status(&[]);
+        assert_eq!(suites.suites[0].status(), SuiteStatus::Empty);
+    }
+
+    #[test]
+    fn order_indices_by_name() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let suite = &suites.suites[0];
+        let order = suite.order_indices(CaseOrder::Name);
+        assert_eq!(order.len(), suite.test_cases.len());
+        assert_eq!(suite.test_cases[order[0]].name, "testConcurrentLogin");
+        assert_eq!(suite.test_cases[order[7]].name, "testLogout");
+    }
+
+    #[test]
+    fn order_indices_by_class() {
+        let reports = parse_directory(&test_reports_dir()).unwrap();
+        let merged = TestSuites::merge(reports.iter().map(|(_, r)| r));
+        for suite in &merged.suites {
+            let order = suite.order_indices(CaseOrder::Class);
+            assert_eq!(order.len(), suite.test_cases.len());
+            let key = |i: usize| {
+                let tc = &suite.test_cases[i];
+                (
+                    tc.classname.is_none(),
+                    tc.classname.clone(),
+                    tc.name.clone(),
+                )
+            };
+            assert!(order.windows(2).all(|w| key(w[0]) <= key(w[1])));
+        }
+    }
+
+    #[test]
+    fn order_suite_indices_by_failures_and_timestamp() {
+        let reports = parse_directory(&test_reports_dir()).unwrap();
+        let merged = TestSuites::merge(reports.iter().map(|(_, r)| r));
+
+        let by_failures = merged.order_suite_indices(SuiteOrder::Failures);
+        let failing = |i: usize| merged.suites[i].failures + merged.suites[i].errors;
+        assert!(by_failures
+            .windows(2)
+            .all(|w| failing(w[0]) >= failing(w[1])));
+
+        let by_timestamp = merged.order_suite_indices(SuiteOrder::Timestamp);
+        assert_eq!(by_timestamp.len(), merged.suites.len());
+        let first = &merged.suites[by_timestamp[0]];
+        assert_eq!(first.timestamp.as_deref(), merged.timestamp());
+        let last = &merged.suites[*by_timestamp.last().unwrap()];
+        assert!(last.timestamp.is_none());
+    }
+
+    #[test]
+    fn timestamp_is_earliest_suite() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        assert_eq!(suites.timestamp(), Some("2026-01-15T09:30:00"));
+
+        let path = test_reports_dir().join("sample-cpp-checks.xml");
+        assert_eq!(parse_file(&path).unwrap().timestamp(), None);
+    }
+
+    #[test]
+    fn slowest_across_suites() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let slowest = suites.slowest(3);
+        assert_eq!(slowest.len(), 3);
+        let times: Vec<f64> = slowest
+            .iter()
+            .map(|&(si, ti)| suites.suites[si].test_cases[ti].time.unwrap())
+            .collect();
+        assert!(times.windows(2).all(|w| w[0] >= w[1]));
+        let max = suites
+            .all_cases()
+            .filter_map(|(_, tc)| tc.time)
+            .fold(0.0, f64::max);
+        assert_eq!(times[0], max);
+        assert!(suites.total_case_time() >= times.iter().sum::<f64>());
+    }
+
+    #[test]
+    fn duration_histogram_buckets_by_decade() {
+        let suites = parse_str(
+            r#"<testsuite name="s">
+                <testcase name="a" time="0.0005"/>
+                <testcase name="b" time="0.01"/>
+                <testcase name="c" time="0.05"/>
+                <testcase name="d" time="2.5"/>
+                <testcase name="e" time="30"/>
+                <testcase name="f"/>
+            </testsuite>"#,
+        )
+        .unwrap();
+        assert_eq!(suites.suites[0].duration_histogram(), [1, 0, 2, 0, 1, 1]);
+        assert_eq!(suites.duration_histogram(), [1, 0, 2, 0, 1, 1]);
+    }
+
+    #[test]
+    fn merge_concatenates_suites() {
+        let reports = parse_directory(&test_reports_dir()).unwrap();
+        let merged = TestSuites::merge(reports.iter().map(|(_, r)| r));
+        let suite_count: usize = reports.iter().map(|(_, r)| r.suites.len()).sum();
+        let test_count: u64 = reports.iter().map(|(_, r)| r.total_tests()).sum();
+        assert_eq!(merged.suites.len(), suite_count);
+        assert_eq!(merged.tests, Some(test_count));
+        assert_eq!(merged.suites[0].name, reports[0].1.suites[0].name);
+    }
+
+    #[test]
+    fn failure_text_joins_message_and_body() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let text = suites.suites[0].test_cases[2].failure_text().unwrap();
+        assert!(text.starts_with("Expected status 401 but got 500\n\njava.lang.AssertionError"));
+        assert!(suites.suites[0].test_cases[0].failure_text().is_none());
+    }
+
+    #[test]
+    fn failure_line_is_first_non_blank_line() {
+        let suites = parse_str(
+            r#"<testsuite name="s">
+                <testcase name="a"><failure message="  ">
+                    first
+                    second</failure></testcase>
+                <testcase name="b"><error message="boom&#10;detail"/></testcase>
+                <testcase name="c"/>
+            </testsuite>"#,
+        )
+        .unwrap();
+        let cases = &suites.suites[0].test_cases;
+        assert_eq!(cases[0].failure_line(), Some("first"));
+        assert_eq!(cases[1].failure_line(), Some("boom"));
+        assert_eq!(cases[2].failure_line(), None);
+    }
+
+    #[test]
+    fn serializes_with_field_names() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let json = serde_json::to_value(&suites).unwrap();
+        let case = &json["suites"][0]["test_cases"][2];
+        assert_eq!(case["name"], suites.suites[0].test_cases[2].name.as_str());
+        assert!(case["classname"].is_string());
+        assert_eq!(
+            case["failure"]["message"],
+            "Expected status 401 but got 500"
+        );
+        assert!(case.get("span").is_none());
+    }
+
+    #[test]
+    fn serializes_computed_status() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let json = serde_json::to_value(&suites).unwrap();
+        for (i, tc) in suites.suites[0].test_cases.iter().enumerate() {
+            let status = &json["suites"][0]["test_cases"][i]["status"];
+            assert_eq!(*status, serde_json::to_value(tc.status()).unwrap());
+        }
+        assert_eq!(json["suites"][0]["test_cases"][2]["status"], "failed");
+    }
 }
@@ -0,0 +1,180 @@
+use crate::{TestCase, TestStatus, TestSuites};
+use std::collections::BTreeMap;
+
+/// One level of the package hierarchy built from test classnames.
+///
+/// `com.example.auth.LoginServiceTest` becomes the chain
+/// `com` → `example` → `auth` → `LoginServiceTest`, and every node carries
+/// counts rolled up from all test cases underneath it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageNode {
+    /// The last segment, e.g. `auth`. Empty for the root.
+    pub name: String,
+    /// The full dotted path, e.g. `com.example.auth`. Empty for the root.
+    pub path: String,
+    pub tests: u64,
+    pub passed: u64,
+    pub failures: u64,
+    pub errors: u64,
+    pub skipped: u64,
+    pub time: f64,
+    /// Child nodes, sorted by name.
+    pub children: Vec<PackageNode>,
+}
+
+impl PackageNode {
+    pub fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Looks up a node by its full dotted path. The empty path is the root.
+    pub fn find(&self, path: &str) -> Option<&PackageNode> {
+        if path.is_empty() {
+            return Some(self);
+        }
+        let mut node = self;
+        for segment in split_classname(path) {
+            node = node.children.iter().find(|c| c.name == segment)?;
+        }
+        Some(node)
+    }
+
+    /// Depth-first walk yielding `(depth, node)` for every descendant,
+    /// excluding the node itself. Direct children are at depth 0.
+    pub fn descendants(&self) -> Vec<(usize, &PackageNode)> {
+        let mut out = Vec::new();
+        collect_descendants(self, 0, &mut out);
+        out
+    }
+
+    fn add(&mut self, tc: &TestCase) {
+        self.tests += 1;
+        self.time += tc.time.unwrap_or(0.0);
+        match tc.status() {
+            TestStatus::Passed => self.passed += 1,
+            TestStatus::Failed => self.failures += 1,
+            TestStatus::Errored => self.errors += 1,
+            TestStatus::Skipped => self.skipped += 1,
+        }
+    }
+}
+
+fn collect_descendants<'a>(
+    node: &'a PackageNode,
+    depth: usize,
+    out: &mut Vec<(usize, &'a PackageNode)>,
+) {
+    for child in &node.children {
+        out.push((depth, child));
+        collect_descendants(child, depth + 1, out);
+    }
+}
+
+/// Splits a classname on `.` (Java, Python) and `::` (C++, Rust), skipping
+/// empty segments.
+pub fn split_classname(classname: &str) -> impl Iterator<Item = &str> {
+    classname
+        .split("::")
+        .flat_map(|part| part.split('.'))
+        .filter(|s| !s.is_empty())
+}
+
+#[derive(Default)]
+struct Builder {
+    node: PackageNode,
+    children: BTreeMap<String, Builder>,
+}
+
+impl Builder {
+    fn finish(self) -> PackageNode {
+        let mut node = self.node;
+        node.children = self.children.into_values().map(Builder::finish).collect();
+        node
+    }
+}
+
+impl TestSuites {
+    /// Builds the package hierarchy for every test case in the report.
+    ///
+    /// A case's classname decides where it lands; cases without one fall
+    /// back to their suite's name, and cases with neither are counted at
+    /// the root only.
+    pub fn package_tree(&self) -> PackageNode {
+        let mut root = Builder::default();
+
+        for (suite, tc) in self.all_cases() {
+            let classname = tc.classname.as_deref().unwrap_or(&suite.name);
+            root.node.add(tc);
+
+            let mut current = &mut root;
+            let mut path = String::new();
+            for segment in split_classname(classname) {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(segment);
+                current = current
+                    .children
+                    .entry(segment.to_string())
+                    .or_insert_with(|| Builder {
+                        node: PackageNode {
+                            name: segment.to_string(),
+                            path: path.clone(),
+                            ..PackageNode::default()
+                        },
+                        children: BTreeMap::new(),
+                    });
+                current.node.add(tc);
+            }
+        }
+
+        root.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_file;
+    use std::path::Path;
+
+    fn test_reports_dir() -> std::path::PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../test-reports")
+    }
+
+    #[test]
+    fn package_tree_rolls_up_counts() {
+        let path = test_reports_dir().join("sample-mixed-results.xml");
+        let suites = parse_file(&path).unwrap();
+        let root = suites.package_tree();
+
+        assert_eq!(root.tests, suites.total_tests());
+        assert_eq!(root.failures, suites.total_failures());
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].name, "com");
+
+        let auth = root.find("com.example.auth").unwrap();
+        assert_eq!(auth.path, "com.example.auth");
+        assert_eq!(auth.tests, 8);
+        assert_eq!(auth.failures, 1);
+        assert_eq!(auth.skipped, 1);
+        assert_eq!(auth.passed, 6);
+
+        let class = root.find("com.example.auth.LoginServiceTest").unwrap();
+        assert!(class.is_leaf());
+    }
+
+    #[test]
+    fn package_tree_falls_back_to_suite_name() {
+        let path = test_reports_dir().join("sample-cpp-checks.xml");
+        let suites = parse_file(&path).unwrap();
+        let root = suites.package_tree();
+        let names: Vec<&str> = root.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["factorial", "failing_checks"]);
+    }
+
+    #[test]
+    fn split_classname_handles_cpp_separators() {
+        let parts: Vec<&str> = super::split_classname("ns::inner.Class").collect();
+        assert_eq!(parts, ["ns", "inner", "Class"]);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{Rerun, TestCase, TestSuite, TestSuites};
+use quick_xml::escape::escape;
+
+/// Writes `report` as JUnit XML under a `<testsuites>` root, with the
+/// counts as they stand in the model. Round-trips through [`parse_str`]:
+/// everything the parser reads is written back.
+///
+/// [`parse_str`]: crate::parse_str
+pub fn write_xml(report: &TestSuites) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites");
+    push_attr(&mut xml, "tests", report.tests);
+    push_attr(&mut xml, "failures", report.failures);
+    push_attr(&mut xml, "errors", report.errors);
+    push_attr(&mut xml, "skipped", report.skipped);
+    xml.push_str(">\n");
+    for suite in &report.suites {
+        write_suite(&mut xml, suite);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn write_suite(xml: &mut String, suite: &TestSuite) {
+    xml.push_str("  <testsuite");
+    push_attr(xml, "name", Some(&suite.name));
+    push_attr(xml, "tests", Some(suite.tests));
+    push_attr(xml, "failures", Some(suite.failures));
+    push_attr(xml, "errors", Some(suite.errors));
+    push_attr(xml, "skipped", suite.skipped);
+    push_attr(xml, "time", suite.time);
+    push_attr(xml, "timestamp", suite.timestamp.as_ref());
+    push_attr(xml, "hostname", suite.hostname.as_ref());
+    xml.push_str(">\n");
+    if let Some(properties) = &suite.properties {
+        xml.push_str("    <properties>\n");
+        for property in &properties.properties {
+            xml.push_str("      <property");
+            push_attr(xml, "name", Some(&property.name));
+            push_attr(xml, "value", Some(&property.value));
+            xml.push_str("/>\n");
+        }
+        xml.push_str("    </properties>\n");
+    }
+    for tc in &suite.test_cases {
+        write_case(xml, tc);
+    }
+    push_text(xml, "    ", "system-out", suite.system_out.as_deref());
+    push_text(xml, "    ", "system-err", suite.system_err.as_deref());
+    xml.push_str("  </testsuite>\n");
+}
+
+fn write_case(xml: &mut String, tc: &TestCase) {
+    xml.push_str("    <testcase");
+    push_attr(xml, "name", Some(&tc.name));
+    push_attr(xml, "classname", tc.classname.as_ref());
+    push_attr(xml, "time", tc.time);
+    push_attr(xml, "file", tc.file.as_ref());
+    let children = [
+        tc.failure
+            .as_ref()
+            .map(|f| ("failure", &f.message, &f.body)),
+        tc.error.as_ref().map(|e| ("error", &e.message, &e.body)),
+        tc.skipped
+            .as_ref()
+            .map(|s| ("skipped", &s.message, &s.body)),
+    ];
+    let has_reruns = tc.reruns().next().is_some();
+    if children.iter().all(Option::is_none)
+        && !has_reruns
+        && tc.system_out.is_none()
+        && tc.system_err.is_none()
+    {
+        xml.push_str("/>\n");
+        return;
+    }
+    xml.push_str(">\n");
+    for (tag, message, body) in children.into_iter().flatten() {
+        xml.push_str("      <");
+        xml.push_str(tag);
+        push_attr(xml, "message", message.as_ref());
+        match body {
+            Some(body) => {
+                xml.push('>');
+                xml.push_str(&escape(body.as_str()));
+                xml.push_str(&format!("</{}>\n", tag));
+            }
+            None => xml.push_str("/>\n"),
+        }
+    }
+    for (tag, reruns) in [
+        ("flakyFailure", &tc.flaky_failures),
+        ("flakyError", &tc.flaky_errors),
+        ("rerunFailure", &tc.rerun_failures),
+        ("rerunError", &tc.rerun_errors),
+    ] {
+        for rerun in reruns {
+            write_rerun(xml, tag, rerun);
+        }
+    }
+    push_text(xml, "      ", "system-out", tc.system_out.as_deref());
+    push_text(xml, "      ", "system-err", tc.system_err.as_deref());
+    xml.push_str("    </testcase>\n");
+}
+
+fn write_rerun(xml: &mut String, tag: &str, rerun: &Rerun) {
+    xml.push_str("      <");
+    xml.push_str(tag);
+    push_attr(xml, "message", rerun.message.as_ref());
+    push_attr(xml, "type", rerun.kind.as_ref());
+    xml.push_str(">\n");
+    push_text(xml, "        ", "stackTrace", rerun.stack_trace.as_deref());
+    push_text(xml, "        ", "system-out", rerun.system_out.as_deref());
+    push_text(xml, "        ", "system-err", rerun.system_err.as_deref());
+    xml.push_str(&format!("      </{}>\n", tag));
+}
+
+/// ` name="value"`, escaped, when there is a value.
+fn push_attr(xml: &mut String, name: &str, value: Option<impl ToString>) {
+    if let Some(value) = value {
+        xml.push_str(&format!(" {}=\"{}\"", name, escape(value.to_string())));
+    }
+}
+
+/// `<tag>text</tag>` on its own line, when there is text.
+fn push_text(xml: &mut String, indent: &str, tag: &str, text: Option<&str>) {
+    if let Some(text) = text {
+        xml.push_str(&format!("{}<{}>{}</{}>\n", indent, tag, escape(text), tag));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_file, parse_str, TestStatus};
+    use std::path::Path;
+
+    #[test]
+    fn round_trips_through_the_parser() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../test-reports");
+        let report = parse_file(&dir.join("sample-mixed-results.xml")).unwrap();
+        let reparsed = parse_str(&write_xml(&report)).unwrap();
+        assert_eq!(reparsed.suites.len(), report.suites.len());
+        assert_eq!(reparsed.total_tests(), report.total_tests());
+        assert_eq!(reparsed.total_failures(), report.total_failures());
+        for (a, b) in reparsed.all_cases().zip(report.all_cases()) {
+            assert_eq!(a.1.name, b.1.name);
+            assert_eq!(a.1.classname, b.1.classname);
+            assert_eq!(a.1.status(), b.1.status());
+            assert_eq!(a.1.failure_text(), b.1.failure_text());
+        }
+    }
+
+    #[test]
+    fn escapes_markup() {
+        let xml = r#"<testsuites><testsuite name="a&amp;b" tests="1" failures="1">
+            <testcase name="t&lt;1&gt;"><failure message="&quot;x&quot;">a &lt; b</failure></testcase>
+        </testsuite></testsuites>"#;
+        let report = parse_str(xml).unwrap();
+        let reparsed = parse_str(&write_xml(&report)).unwrap();
+        let tc = &reparsed.suites[0].test_cases[0];
+        assert_eq!(reparsed.suites[0].name, "a&b");
+        assert_eq!(tc.name, "t<1>");
+        assert_eq!(tc.status(), TestStatus::Failed);
+        assert_eq!(tc.failure_text().unwrap(), "\"x\"\n\na < b");
+    }
+}
@@ -0,0 +1,142 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Turns captured output containing ANSI escape sequences into styled
+/// lines. SGR sequences (`\x1b[…m`) become ratatui styles layered over
+/// `base`; every other escape sequence is dropped. Styles carry over line
+/// breaks the way they would in a terminal. Each line gets `indent`
+/// prepended.
+pub fn to_lines(text: &str, base: Style, indent: &str) -> Vec<Line<'static>> {
+    let mut style = base;
+    text.lines()
+        .map(|line| {
+            let mut spans = vec![Span::styled(indent.to_string(), base)];
+            let mut current = String::new();
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if c != '\x1b' {
+                    current.push(c);
+                    continue;
+                }
+                let Some(params) = escape_sequence(&mut chars) else {
+                    continue;
+                };
+                if !current.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut current), style));
+                }
+                style = apply_sgr(style, base, &params);
+            }
+            if !current.is_empty() {
+                spans.push(Span::styled(current, style));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Removes every ANSI escape sequence, keeping only the text.
+pub fn strip(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            escape_sequence(&mut chars);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Consumes the escape sequence following an ESC. Returns the parameters
+/// of an SGR sequence; anything else is skipped and yields `None`.
+fn escape_sequence(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+    match chars.next()? {
+        // CSI: parameters, then a final byte in `@`..=`~`.
+        '[' => {
+            let mut params = String::new();
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    return (c == 'm').then_some(params);
+                }
+                params.push(c);
+            }
+            None
+        }
+        // OSC (window titles, hyperlinks): runs to BEL or ESC \.
+        ']' => {
+            while let Some(c) = chars.next() {
+                if c == '\x07' || c == '\x1b' && chars.next_if_eq(&'\\').is_some() {
+                    break;
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Applies the semicolon-separated SGR codes in `params` to `style`.
+/// A reset returns to `base` rather than the terminal default so captured
+/// stderr keeps its color.
+fn apply_sgr(mut style: Style, base: Style, params: &str) -> Style {
+    if params.is_empty() {
+        return base;
+    }
+    let mut codes = params
+        .split([';', ':'])
+        .map(|p| p.parse::<u16>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => base,
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 | 6 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(Color::Indexed((code - 30) as u8)),
+            90..=97 => style.fg(Color::Indexed((code - 90 + 8) as u8)),
+            40..=47 => style.bg(Color::Indexed((code - 40) as u8)),
+            100..=107 => style.bg(Color::Indexed((code - 100 + 8) as u8)),
+            38 => match extended_color(&mut codes) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            48 => match extended_color(&mut codes) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            39 => Style {
+                fg: base.fg,
+                ..style
+            },
+            49 => Style {
+                bg: base.bg,
+                ..style
+            },
+            _ => style,
+        };
+    }
+    style
+}
+
+/// Reads the tail of a `38;5;n` or `38;2;r;g;b` color.
+fn extended_color(codes: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Indexed(codes.next()? as u8)),
+        2 => Some(Color::Rgb(
+            codes.next()? as u8,
+            codes.next()? as u8,
+            codes.next()? as u8,
+        )),
+        _ => None,
+    }
+}
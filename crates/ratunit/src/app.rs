@@ -1,188 +1,3523 @@
-use junit_parser::TestSuites;
+use crate::clipboard::Clipboard;
+use crate::editor::SourceLocation;
+use crate::keymap::KeyMap;
+use crate::prefilter::Prefilter;
+use crate::quarantine::Quarantine;
+use crate::state::{Position, SavedView};
+use crate::test_id::IdFormat;
+use crate::theme::Theme;
+use crate::{complete, search, watch};
+use anyhow::{bail, Context, Result};
+use junit_parser::diff;
+use junit_parser::{
+    CaseOrder, FailureGroup, PackageNode, ParameterizedGroup, ReportDiff, SkipGroup, SuiteOrder,
+    TestCase, TestStatus, TestSuite, TestSuites,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
 
 pub struct FileReport {
     pub filename: String,
+    /// Where the report was read from, for reloading. [`STDIN`] for a
+    /// report piped in.
+    pub path: PathBuf,
     pub data: TestSuites,
+    /// The XML of a report that can't be read again, such as one piped in,
+    /// for the raw XML view. Files are read back from `path` instead.
+    pub source: Option<String>,
+    /// The file's metadata as of loading, for its age and size in the
+    /// sidebar and sorting by modification time. `None` for a report piped
+    /// in.
+    pub metadata: Option<std::fs::Metadata>,
+}
+
+/// The path argument that reads a report from standard input.
+pub const STDIN: &str = "-";
+
+impl FileReport {
+    /// Parses a JUnit XML file, every XML file in a directory, or the
+    /// report on standard input for [`STDIN`].
+    pub fn load(path: &Path) -> Result<Vec<FileReport>> {
+        if path == Path::new(STDIN) {
+            let mut xml = String::new();
+            std::io::stdin()
+                .read_to_string(&mut xml)
+                .context("Failed to read standard input")?;
+            let data = junit_parser::parse_str_with_spans(&xml)
+                .context("Failed to parse standard input")?;
+            return Ok(vec![FileReport {
+                filename: "stdin".into(),
+                path: path.to_path_buf(),
+                data,
+                source: Some(xml),
+                metadata: None,
+            }]);
+        }
+        if !path.exists() {
+            bail!("Path does not exist: {}", path.display());
+        }
+        if path.is_dir() {
+            let parsed = junit_parser::parse_directory_with_spans(path)
+                .with_context(|| format!("Failed to parse directory: {}", path.display()))?;
+            if parsed.is_empty() {
+                bail!("No XML files found in: {}", path.display());
+            }
+            return Ok(parsed
+                .into_iter()
+                .map(|(name, data)| FileReport {
+                    metadata: std::fs::metadata(path.join(&name)).ok(),
+                    path: path.join(&name),
+                    filename: name,
+                    data,
+                    source: None,
+                })
+                .collect());
+        }
+        let data = junit_parser::parse_file_with_spans(path)
+            .with_context(|| format!("Failed to parse file: {}", path.display()))?;
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Ok(vec![FileReport {
+            filename,
+            path: path.to_path_buf(),
+            data,
+            source: None,
+            metadata: std::fs::metadata(path).ok(),
+        }])
+    }
+
+    /// Loads each path as [`FileReport::load`] does, into one session. A
+    /// path named twice, `-` included, is loaded once: standard input can
+    /// only be read one time.
+    pub fn load_all(paths: &[PathBuf]) -> Result<Vec<FileReport>> {
+        let mut seen = HashSet::new();
+        let mut reports: Vec<FileReport> = Vec::new();
+        for path in paths {
+            if !seen.insert(watch::canonical(path)) {
+                continue;
+            }
+            for mut report in FileReport::load(path)? {
+                if reports.iter().any(|r| r.path == report.path) {
+                    continue;
+                }
+                report.distinguish(&reports);
+                reports.push(report);
+            }
+        }
+        Ok(reports)
+    }
+
+    /// Loads `path` as [`FileReport::load`] does, merging a directory's
+    /// reports into one named after the directory.
+    pub fn load_merged(path: &Path) -> Result<FileReport> {
+        let mut reports = FileReport::load(path)?;
+        if reports.len() == 1 {
+            return Ok(reports.remove(0));
+        }
+        let data = TestSuites::merge(reports.iter().map(|r| &r.data));
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        Ok(FileReport {
+            filename,
+            path: path.to_path_buf(),
+            data,
+            source: None,
+            metadata: std::fs::metadata(path).ok(),
+        })
+    }
+
+    /// A report parsed from `xml` as if piped in, for tests.
+    #[cfg(test)]
+    pub fn from_xml(filename: &str, xml: &str) -> FileReport {
+        FileReport {
+            filename: filename.into(),
+            path: PathBuf::from(filename),
+            data: junit_parser::parse_str_with_spans(xml).unwrap(),
+            source: Some(xml.to_string()),
+            metadata: None,
+        }
+    }
+
+    /// When the file was last written, as of loading.
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.metadata.as_ref().and_then(|m| m.modified().ok())
+    }
+
+    /// Whether the report was loaded from `path`, however either is
+    /// spelled: the watcher reports absolute paths.
+    pub fn is_at(&self, path: &Path) -> bool {
+        self.path == path || watch::canonical(&self.path) == watch::canonical(path)
+    }
+
+    /// Same name, different run: tells the report apart from one of
+    /// `others` with the same filename by prefixing its directory.
+    pub fn distinguish(&mut self, others: &[FileReport]) {
+        let clash = others
+            .iter()
+            .any(|f| f.filename == self.filename && f.path != self.path);
+        if clash {
+            if let Some(dir) = self.path.parent().and_then(|p| p.file_name()) {
+                self.filename = format!("{}/{}", dir.to_string_lossy(), self.filename);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum View {
     SuiteList,
+    Tree,
     TestList,
     TestDetail,
+    /// New failures, fixes and slowdowns between two marked files.
+    Diff,
+    /// Pass/fail and duration trends across runs, one file per run.
+    History,
+    /// The slowest test cases in the current file.
+    Slowest,
+    /// Failed tests in the current file clustered by failure message.
+    Groups,
+    /// Tests arranged by the package segments of their classnames.
+    Packages,
+    /// Each suite's test durations bucketed into a histogram.
+    Durations,
+    /// Skipped tests in the current file grouped by skip reason.
+    Skips,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputMode {
+    Normal,
+    Search,
+    Finder,
+    Help,
+    /// The `<properties>` popup for the selected suite.
+    Properties,
+    /// Pass rate, timings and slowest tests of the selected suite.
+    SuiteStats,
+    /// The selected suite's own `<system-out>` and `<system-err>`.
+    SuiteOutput,
+    /// Duration and failures of the selected suite in every loaded file.
+    SuiteCompare,
+    /// Picking the format to copy the selected test's identifier in.
+    CopyId,
+    /// Typing a filter for the file sidebar.
+    SidebarFilter,
+    /// Typing a line number to jump to in the detail view.
+    GoToLine,
+    /// Typing a classname pattern to limit the lists to.
+    ClassFilter,
+    /// Typing the file to export the picked tests, or the open test, to.
+    Export,
+    /// Typing the path of a report or directory to load.
+    AddFiles,
+}
+
+/// Which tests (and suites containing them) the list views show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    All,
+    /// Failed and errored together, as `f` cycles through.
+    Failing,
+    /// Only reachable from the test list tabs.
+    Failed,
+    /// Only reachable from the test list tabs.
+    Errored,
+    Skipped,
+    Passed,
+    /// Failed and errored tests that weren't failing in the baseline;
+    /// `f` only stops here while one is set.
+    NewFailures,
+}
+
+impl StatusFilter {
+    /// The tabs above the test list, in order.
+    pub const TABS: [StatusFilter; 5] = [
+        StatusFilter::All,
+        StatusFilter::Failed,
+        StatusFilter::Errored,
+        StatusFilter::Skipped,
+        StatusFilter::Passed,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            StatusFilter::All => StatusFilter::Failing,
+            StatusFilter::Failing => StatusFilter::NewFailures,
+            StatusFilter::NewFailures | StatusFilter::Failed | StatusFilter::Errored => {
+                StatusFilter::Skipped
+            }
+            StatusFilter::Skipped => StatusFilter::Passed,
+            StatusFilter::Passed => StatusFilter::All,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::All => "all",
+            StatusFilter::Failing => "failed+errored",
+            StatusFilter::Failed => "failed",
+            StatusFilter::Errored => "errored",
+            StatusFilter::Skipped => "skipped",
+            StatusFilter::Passed => "passed",
+            StatusFilter::NewFailures => "new failures",
+        }
+    }
+
+    pub fn statuses(self) -> &'static [TestStatus] {
+        match self {
+            StatusFilter::All => &TestStatus::ALL,
+            StatusFilter::Failing | StatusFilter::NewFailures => {
+                &[TestStatus::Failed, TestStatus::Errored]
+            }
+            StatusFilter::Failed => &[TestStatus::Failed],
+            StatusFilter::Errored => &[TestStatus::Errored],
+            StatusFilter::Skipped => &[TestStatus::Skipped],
+            StatusFilter::Passed => &[TestStatus::Passed],
+        }
+    }
+
+    /// Whether the tab for `tab` is lit up under this filter. `Failing`
+    /// and `NewFailures` light both the Failed and Errored tabs.
+    pub fn shows_tab(self, tab: StatusFilter) -> bool {
+        self == tab
+            || matches!(self, StatusFilter::Failing | StatusFilter::NewFailures)
+                && matches!(tab, StatusFilter::Failed | StatusFilter::Errored)
+    }
+
+    /// Position among [`StatusFilter::TABS`]; `Failing` and `NewFailures`
+    /// count as Failed.
+    fn tab_index(self) -> usize {
+        let tab = match self {
+            StatusFilter::Failing | StatusFilter::NewFailures => StatusFilter::Failed,
+            other => other,
+        };
+        Self::TABS.iter().position(|&t| t == tab).unwrap_or(0)
+    }
+}
+
+/// Order of rows in the test list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestSort {
+    Report,
+    Duration,
+    Name,
+    Class,
+    Status,
+}
+
+impl TestSort {
+    pub fn next(self) -> Self {
+        match self {
+            TestSort::Report => TestSort::Duration,
+            TestSort::Duration => TestSort::Name,
+            TestSort::Name => TestSort::Class,
+            TestSort::Class => TestSort::Status,
+            TestSort::Status => TestSort::Report,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TestSort::Report => "report order",
+            TestSort::Duration => "slowest first",
+            TestSort::Name => "name",
+            TestSort::Class => "class",
+            TestSort::Status => "status",
+        }
+    }
+
+    fn case_order(self) -> Option<CaseOrder> {
+        match self {
+            TestSort::Report => None,
+            TestSort::Duration => Some(CaseOrder::Time),
+            TestSort::Name => Some(CaseOrder::Name),
+            TestSort::Class => Some(CaseOrder::Class),
+            TestSort::Status => Some(CaseOrder::Status),
+        }
+    }
+}
+
+/// Order of rows in the suite list (and suite headers in the tree).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuiteSort {
+    Report,
+    Name,
+    Failures,
+    Duration,
+    Timestamp,
+}
+
+impl SuiteSort {
+    pub fn next(self) -> Self {
+        match self {
+            SuiteSort::Report => SuiteSort::Name,
+            SuiteSort::Name => SuiteSort::Failures,
+            SuiteSort::Failures => SuiteSort::Duration,
+            SuiteSort::Duration => SuiteSort::Timestamp,
+            SuiteSort::Timestamp => SuiteSort::Report,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SuiteSort::Report => "report order",
+            SuiteSort::Name => "name",
+            SuiteSort::Failures => "most failures",
+            SuiteSort::Duration => "slowest first",
+            SuiteSort::Timestamp => "oldest first",
+        }
+    }
+
+    fn suite_order(self) -> Option<SuiteOrder> {
+        match self {
+            SuiteSort::Report => None,
+            SuiteSort::Name => Some(SuiteOrder::Name),
+            SuiteSort::Failures => Some(SuiteOrder::Failures),
+            SuiteSort::Duration => Some(SuiteOrder::Time),
+            SuiteSort::Timestamp => Some(SuiteOrder::Timestamp),
+        }
+    }
+}
+
+/// A report's text, kept for the raw XML view.
+pub struct RawSource {
+    path: PathBuf,
+    text: String,
+}
+
+/// Order of files in the sidebar. "All files" always stays first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSort {
+    /// By filename, the order they are loaded in.
+    #[default]
+    Name,
+    /// Most failures plus errors first.
+    Failures,
+    /// Most recently written first.
+    Modified,
+}
+
+impl FileSort {
+    pub fn next(self) -> Self {
+        match self {
+            FileSort::Name => FileSort::Failures,
+            FileSort::Failures => FileSort::Modified,
+            FileSort::Modified => FileSort::Name,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FileSort::Name => "name",
+            FileSort::Failures => "most failures",
+            FileSort::Modified => "newest first",
+        }
+    }
+}
+
+/// How the detail view arranges captured output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputLayout {
+    /// One scroll region: summary, failure, stdout, stderr.
+    Combined,
+    /// Summary and failure on top, stdout and stderr side by side below.
+    SideBySide,
+    /// Summary and failure, stdout and stderr stacked, each scrolling on
+    /// its own.
+    Stacked,
+}
+
+impl OutputLayout {
+    pub fn next(self) -> Self {
+        match self {
+            OutputLayout::Combined => OutputLayout::SideBySide,
+            OutputLayout::SideBySide => OutputLayout::Stacked,
+            OutputLayout::Stacked => OutputLayout::Combined,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputLayout::Combined => "combined",
+            OutputLayout::SideBySide => "side by side",
+            OutputLayout::Stacked => "stacked",
+        }
+    }
+}
+
+/// The pane of a split detail view that `j` / `k` scroll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetailPane {
+    Main,
+    Stdout,
+    Stderr,
+}
+
+impl DetailPane {
+    fn next(self) -> Self {
+        match self {
+            DetailPane::Main => DetailPane::Stdout,
+            DetailPane::Stdout => DetailPane::Stderr,
+            DetailPane::Stderr => DetailPane::Main,
+        }
+    }
+}
+
+/// A row of the tree view: a suite header or one of its test cases, as
+/// raw indices into the current file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeRow {
+    Suite(usize),
+    Test(usize, usize),
+}
+
+/// A row of the failure or skip groups view: a group header or one of its
+/// member tests, as indices into [`App::failure_groups`] (or
+/// [`App::skip_groups`]) and the current file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupRow {
+    Group(usize),
+    Test {
+        group: usize,
+        suite: usize,
+        test: usize,
+    },
+}
+
+/// A row of the test list: a test case, or the header of a parameterized
+/// test's cases, as an index into [`App::param_groups`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestRow {
+    Params(usize),
+    /// A test case; `group` is set when it is listed under a header.
+    Test {
+        test: usize,
+        group: Option<usize>,
+    },
+}
+
+/// A row of the package view: a package or class, by its dotted path, or
+/// a test under an expanded class. `depth` is its indentation level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageRow {
+    Node {
+        path: String,
+        depth: usize,
+    },
+    Test {
+        suite: usize,
+        test: usize,
+        depth: usize,
+    },
+}
+
+/// State of the `Ctrl-P` fuzzy finder popup.
+#[derive(Debug, Default)]
+pub struct Finder {
+    pub query: String,
+    pub selected: usize,
+}
+
+/// A test case matched by the fuzzy finder.
+pub struct FinderHit {
+    pub file: usize,
+    pub suite: usize,
+    pub test: usize,
+    pub score: i64,
+}
+
+/// Columns moved per horizontal scroll step in the detail view.
+const H_SCROLL_STEP: u16 = 4;
+
+/// Sidebar label of the merged report.
+const ALL_FILES: &str = "All files";
+
+/// Default, narrowest and widest file sidebar, in percent of the screen.
+pub const SIDEBAR_WIDTH: u16 = 25;
+const SIDEBAR_MIN: u16 = 10;
+const SIDEBAR_MAX: u16 = 60;
+/// Percent added or removed per `<` / `>` press.
+const SIDEBAR_STEP: u16 = 5;
+
+/// Rows in the slowest-tests view.
+const SLOWEST_LIMIT: usize = 100;
+
+/// Upper bound on finder results so huge reports stay responsive.
+const FINDER_LIMIT: usize = 200;
+
+/// Upper bound on a count prefix, so a held-down digit can't spin forever.
+const MAX_COUNT: usize = 9999;
+
+/// The comparison shown in [`View::Diff`], from `base` to `head`.
+pub struct FileDiff {
+    pub base: String,
+    pub head: String,
+    pub diff: ReportDiff,
 }
 
+/// A report whose failures count as already known: with the
+/// [`StatusFilter::NewFailures`] filter the lists hide them.
+pub struct Baseline {
+    /// The report's filename.
+    pub name: String,
+    /// Tests failing or erroring in the baseline.
+    failing: HashSet<diff::TestKey>,
+}
+
+impl Baseline {
+    pub fn new(name: String, data: &TestSuites) -> Self {
+        let failing = data
+            .all_cases()
+            .filter(|(_, tc)| matches!(tc.status(), TestStatus::Failed | TestStatus::Errored))
+            .map(|(suite, tc)| diff::TestKey::new(suite, tc))
+            .collect();
+        Baseline { name, failing }
+    }
+
+    /// Whether `tc` was already failing or erroring in the baseline.
+    pub fn is_known(&self, suite: &TestSuite, tc: &TestCase) -> bool {
+        self.failing.contains(&diff::TestKey::new(suite, tc))
+    }
+}
+
+/// Which tests the lists show, from the status filter, hidden passes and
+/// the baseline.
+struct TestFilter<'a> {
+    statuses: Vec<TestStatus>,
+    /// Set under [`StatusFilter::NewFailures`].
+    known_failures: Option<&'a HashSet<diff::TestKey>>,
+    /// Seconds a test must take to be shown.
+    min_time: Option<f64>,
+    /// Glob the test's classname must match.
+    class_pattern: Option<&'a str>,
+}
+
+impl TestFilter<'_> {
+    fn shows(&self, suite: &TestSuite, tc: &TestCase) -> bool {
+        self.statuses.contains(&tc.status())
+            && self
+                .known_failures
+                .is_none_or(|known| !known.contains(&diff::TestKey::new(suite, tc)))
+            && self.is_slow_enough(tc)
+            && self.matches_class(tc)
+    }
+
+    fn matches_class(&self, tc: &TestCase) -> bool {
+        self.class_pattern.is_none_or(|pattern| {
+            tc.classname
+                .as_deref()
+                .is_some_and(|class| search::glob_match(pattern, class))
+        })
+    }
+
+    fn is_slow_enough(&self, tc: &TestCase) -> bool {
+        self.min_time
+            .is_none_or(|min| tc.time.is_some_and(|time| time > min))
+    }
+}
+
+/// The duration thresholds [`App::cycle_min_time`] steps through, in
+/// seconds.
+pub const MIN_TIMES: [f64; 3] = [0.1, 1.0, 10.0];
+
+/// A threshold as `100ms` or `1s`.
+pub fn threshold_label(secs: f64) -> String {
+    if secs < 1.0 {
+        format!("{}ms", (secs * 1000.0).round())
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// The current file, suite and test by name, so the selection survives
+/// a reload that reorders or replaces them.
+struct Selection {
+    /// `None` for the "All files" view.
+    file: Option<String>,
+    suite: Option<String>,
+    test: Option<TestKey>,
+    expanded: Vec<String>,
+    tree_row: Option<(String, Option<TestKey>)>,
+}
+
+/// A test's name and classname.
+type TestKey = (String, Option<String>);
+
 pub struct App {
     pub files: Vec<FileReport>,
+    /// Every file's suites merged into one report, shown as the first
+    /// sidebar entry when more than one file is loaded.
+    pub all_files: Option<FileReport>,
+    /// Browsing `all_files` rather than `files[selected_file]`.
+    pub show_all: bool,
     pub selected_file: usize,
     pub selected_suite: usize,
     pub selected_test: usize,
     pub view: View,
     pub scroll_offset: u16,
+    /// Horizontal scroll of the detail view, used when wrapping is off.
+    pub h_scroll: u16,
+    pub wrap_detail: bool,
+    /// Style ANSI escapes in captured output rather than stripping them.
+    pub render_ansi: bool,
+    /// Leave passed tests out of every list and the tree; saved between
+    /// runs.
+    pub hide_passed: bool,
+    /// Only tests slower than this many seconds are listed.
+    pub min_time: Option<f64>,
+    /// Only tests whose classname matches this glob are listed.
+    pub class_filter: Option<String>,
+    /// The class filter prompt's input.
+    pub class_input: String,
+    /// Leave the class and file columns out of the test list; saved
+    /// between runs.
+    pub hide_columns: bool,
+    /// Order of the file sidebar; saved between runs.
+    pub file_sort: FileSort,
+    /// Paths of reports removed with `X`, which reloading must not bring
+    /// back.
+    pub dropped: BTreeSet<PathBuf>,
+    /// Swap Unicode glyphs for ASCII when drawing; see [`crate::ascii`].
+    pub ascii: bool,
+    /// Prefix suites and files with ✓ / ✗ / − as well as coloring them.
+    pub status_markers: bool,
+    /// Number the lines of stack traces and captured output.
+    pub line_numbers: bool,
+    /// Whether JSON and XML found in the detail view are pretty-printed.
+    pub pretty: bool,
+    /// Show the test's original XML in the detail view instead of the
+    /// parsed fields.
+    pub show_raw_xml: bool,
+    pub raw_source: RefCell<Option<RawSource>>,
+    /// The go-to-line prompt's input.
+    pub line_input: String,
+    /// `(suite, test)` indices in the current report picked with Space or
+    /// `V` for copying and exporting in bulk.
+    pub picked: BTreeSet<(usize, usize)>,
+    /// The last test toggled with Space; `V` picks from here to the cursor.
+    pick_anchor: Option<(usize, usize)>,
+    /// The export prompt's input.
+    pub export_input: String,
+    /// The export prompt is for the open test's detail rather than the
+    /// picked tests.
+    pub export_detail: bool,
+    /// The add-files prompt's input, and the names its last Tab could
+    /// complete to.
+    pub add_input: String,
+    pub add_completions: Vec<String>,
+    pub output_layout: OutputLayout,
+    /// Which split pane scrolls; always `Main` in the combined layout.
+    pub detail_focus: DetailPane,
+    pub stdout_scroll: u16,
+    pub stderr_scroll: u16,
+    /// Furthest each detail pane can scroll, indexed by [`DetailPane`].
+    /// The diff and history views record theirs as the main pane's.
+    /// Measured by the last draw, which only borrows the app.
+    pub detail_max_scroll: Cell<[u16; 3]>,
     pub should_quit: bool,
     pub multi_file: bool,
+    /// File sidebar width in percent, adjusted with `<` / `>`.
+    pub sidebar_width: u16,
+    /// Hidden with `b` so the lists get the full width.
+    pub sidebar_hidden: bool,
+    pub input_mode: InputMode,
+    pub search_query: String,
+    /// Narrows the file sidebar by filename; a leading `!` keeps only
+    /// files with failures.
+    pub sidebar_filter: String,
+    pub finder: Finder,
+    pub status_filter: StatusFilter,
+    pub test_sort: TestSort,
+    pub suite_sort: SuiteSort,
+    /// Show suites and tests as one collapsible tree instead of the
+    /// suite list → test list drill-down.
+    pub tree_mode: bool,
+    pub tree_selected: usize,
+    /// Raw indices of expanded suites in the current file.
+    pub expanded: HashSet<usize>,
+    /// Time and row of the last left click, for double-click detection.
+    pub last_click: Option<(Instant, usize)>,
+    pub help_scroll: u16,
+    pub properties_scroll: u16,
+    pub suite_output_scroll: u16,
+    /// Name of the suite the cross-file comparison popup shows.
+    pub compared_suite: Option<String>,
+    /// The format last copied test identifiers in, highlighted in the
+    /// copy popup.
+    pub id_format: IdFormat,
+    /// Digits typed before a motion, vim style (`5j`, `12G`).
+    pub pending_count: Option<usize>,
+    /// One-off feedback shown in the key hint line until the next key press.
+    pub status_message: Option<String>,
+    /// Set by the `e` key; the main loop suspends the TUI and opens it.
+    pub pending_edit: Option<SourceLocation>,
+    /// Configured directories to look for stack frames' files in.
+    pub source_roots: Vec<PathBuf>,
+    /// Known-failing tests, badged `QUAR` and left out of the headline
+    /// failure counts.
+    pub quarantine: Quarantine,
+    /// Tests left out of every report as it loads, from the command line.
+    pub prefilter: Prefilter,
+    /// Count quarantined tests in the headline failures after all.
+    pub count_quarantined: bool,
+    /// The stack frame picked with Enter in the detail view, by position
+    /// among the test's frames.
+    pub selected_frame: Option<usize>,
+    /// Rows of the main detail pane, recorded while drawing so a picked
+    /// frame can be scrolled into view.
+    pub detail_height: Cell<u16>,
+    /// Set by the `|` key; the main loop suspends the TUI and pipes it
+    /// into `$PAGER`.
+    pub pending_pager: Option<String>,
+    pub clipboard: Clipboard,
+    pub theme: Theme,
+    pub keymap: KeyMap,
+    /// Re-parse reports when they change on disk.
+    pub watching: bool,
+    /// Add reports that appear in the directory.
+    pub tailing: bool,
+    /// Notify the desktop when a reloaded report starts or stops failing.
+    pub notifications: bool,
+    pub slowest_selected: usize,
+    pub groups_selected: usize,
+    /// Group failures by exception class rather than by message.
+    pub group_by_exception: bool,
+    /// Signatures of expanded failure groups, so they stay open across
+    /// reloads that reorder the groups.
+    pub expanded_groups: HashSet<String>,
+    pub skips_selected: usize,
+    /// Reasons of expanded groups in the skipped tests view.
+    pub expanded_skips: HashSet<String>,
+    /// Names of expanded parameterized tests in the test list.
+    pub expanded_params: HashSet<String>,
+    pub packages_selected: usize,
+    pub durations_selected: usize,
+    /// Dotted paths of expanded nodes in the package view.
+    pub expanded_packages: HashSet<String>,
+    /// Where `go_back` returns to from the detail view, when it was opened
+    /// from somewhere other than the test list.
+    pub return_view: Option<View>,
+    /// Filenames marked for comparison, oldest first; at most two.
+    pub marked: Vec<String>,
+    pub diff: Option<FileDiff>,
+    pub baseline: Option<Baseline>,
 }
 
-impl App {
-    pub fn new(files: Vec<FileReport>) -> Self {
-        let multi_file = files.len() > 1;
-        Self {
-            files,
-            selected_file: 0,
-            selected_suite: 0,
-            selected_test: 0,
-            view: View::SuiteList,
-            scroll_offset: 0,
-            should_quit: false,
-            multi_file,
+impl App {
+    pub fn new(files: Vec<FileReport>, theme: Theme, keymap: KeyMap) -> Self {
+        let multi_file = files.len() > 1;
+        let mut app = Self {
+            files,
+            all_files: None,
+            show_all: false,
+            selected_file: 0,
+            selected_suite: 0,
+            selected_test: 0,
+            view: View::SuiteList,
+            scroll_offset: 0,
+            h_scroll: 0,
+            wrap_detail: true,
+            render_ansi: true,
+            hide_passed: false,
+            min_time: None,
+            class_filter: None,
+            class_input: String::new(),
+            hide_columns: false,
+            file_sort: FileSort::Name,
+            dropped: BTreeSet::new(),
+            ascii: false,
+            status_markers: false,
+            line_numbers: false,
+            pretty: false,
+            show_raw_xml: false,
+            raw_source: RefCell::new(None),
+            line_input: String::new(),
+            picked: BTreeSet::new(),
+            pick_anchor: None,
+            export_input: String::new(),
+            export_detail: false,
+            add_input: String::new(),
+            add_completions: Vec::new(),
+            output_layout: OutputLayout::Combined,
+            detail_focus: DetailPane::Main,
+            stdout_scroll: 0,
+            stderr_scroll: 0,
+            detail_max_scroll: Cell::new([0; 3]),
+            should_quit: false,
+            multi_file,
+            sidebar_width: SIDEBAR_WIDTH,
+            sidebar_hidden: false,
+            input_mode: InputMode::Normal,
+            search_query: String::new(),
+            sidebar_filter: String::new(),
+            finder: Finder::default(),
+            status_filter: StatusFilter::All,
+            test_sort: TestSort::Report,
+            suite_sort: SuiteSort::Report,
+            tree_mode: false,
+            tree_selected: 0,
+            expanded: HashSet::new(),
+            last_click: None,
+            help_scroll: 0,
+            properties_scroll: 0,
+            suite_output_scroll: 0,
+            compared_suite: None,
+            id_format: IdFormat::default(),
+            pending_count: None,
+            status_message: None,
+            pending_edit: None,
+            source_roots: Vec::new(),
+            quarantine: Quarantine::default(),
+            prefilter: Prefilter::default(),
+            count_quarantined: false,
+            selected_frame: None,
+            detail_height: Cell::new(0),
+            pending_pager: None,
+            clipboard: Clipboard::default(),
+            theme,
+            keymap,
+            watching: false,
+            tailing: false,
+            notifications: false,
+            slowest_selected: 0,
+            groups_selected: 0,
+            group_by_exception: false,
+            expanded_groups: HashSet::new(),
+            skips_selected: 0,
+            expanded_skips: HashSet::new(),
+            expanded_params: HashSet::new(),
+            packages_selected: 0,
+            durations_selected: 0,
+            expanded_packages: HashSet::new(),
+            return_view: None,
+            marked: Vec::new(),
+            diff: None,
+            baseline: None,
+        };
+        app.rebuild_all_files();
+        app.show_all = app.all_files.is_some();
+        app
+    }
+
+    pub fn current_file(&self) -> &FileReport {
+        match &self.all_files {
+            Some(all) if self.show_all => all,
+            _ => &self.files[self.selected_file],
+        }
+    }
+
+    /// Recomputes the merged "All files" report after `files` changed.
+    fn rebuild_all_files(&mut self) {
+        self.all_files = (self.files.len() > 1).then(|| FileReport {
+            filename: ALL_FILES.into(),
+            path: PathBuf::new(),
+            data: TestSuites::merge(self.files.iter().map(|f| &f.data)),
+            source: None,
+            metadata: None,
+        });
+        if self.all_files.is_none() {
+            self.show_all = false;
+        }
+    }
+
+    /// The sidebar's entries in display order: `None` for "All files",
+    /// which is always first when present, then the indices of the files
+    /// passing the sidebar filter.
+    pub fn sidebar_entries(&self) -> Vec<Option<usize>> {
+        let mut files: Vec<usize> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| self.sidebar_filter_matches(f))
+            .map(|(i, _)| i)
+            .collect();
+        match self.file_sort {
+            FileSort::Name => {}
+            FileSort::Failures => files.sort_by_key(|&i| {
+                let data = &self.files[i].data;
+                Reverse(data.total_failures() + data.total_errors())
+            }),
+            FileSort::Modified => files.sort_by_key(|&i| Reverse(self.files[i].modified())),
+        }
+        let all = self.all_files.as_ref().map(|_| None);
+        all.into_iter().chain(files.into_iter().map(Some)).collect()
+    }
+
+    /// Advances the sidebar sort order. The open file stays open.
+    pub fn cycle_file_sort(&mut self) {
+        self.file_sort = self.file_sort.next();
+        self.status_message = Some(format!("Files sorted by {}", self.file_sort.label()));
+    }
+
+    /// The report behind a sidebar entry.
+    pub fn entry_file(&self, entry: Option<usize>) -> &FileReport {
+        match (entry, &self.all_files) {
+            (Some(i), _) => &self.files[i],
+            (None, Some(all)) => all,
+            (None, None) => self.current_file(),
+        }
+    }
+
+    fn sidebar_filter_matches(&self, file: &FileReport) -> bool {
+        let (failing_only, text) = match self.sidebar_filter.strip_prefix('!') {
+            Some(rest) => (true, rest.trim()),
+            None => (false, self.sidebar_filter.trim()),
+        };
+        let failing = file.data.total_failures() + file.data.total_errors() > 0;
+        (!failing_only || failing)
+            && (text.is_empty() || search::find_ignore_case(&file.filename, text).is_some())
+    }
+
+    /// Number of sidebar entries, including "All files".
+    pub fn sidebar_len(&self) -> usize {
+        self.sidebar_entries().len()
+    }
+
+    /// The sidebar entry of the current file; see [`App::sidebar_entries`].
+    fn current_entry(&self) -> Option<usize> {
+        (!self.show_all).then_some(self.selected_file)
+    }
+
+    /// Position of the current file in the sidebar, or 0 when the filter
+    /// hides it.
+    pub fn sidebar_selected(&self) -> usize {
+        let current = self.current_entry();
+        self.sidebar_entries()
+            .iter()
+            .position(|&e| e == current)
+            .unwrap_or(0)
+    }
+
+    /// The file a suite of the current report came from. In the "All
+    /// files" view `suite` indexes the merged report.
+    pub fn suite_origin(&self, suite: usize) -> &FileReport {
+        self.origin_index(suite).0
+    }
+
+    /// [`Self::suite_origin`], plus the suite's index within that file.
+    fn origin_index(&self, suite: usize) -> (&FileReport, usize) {
+        if !self.show_all {
+            return (self.current_file(), suite);
+        }
+        let mut remaining = suite;
+        for file in &self.files {
+            if remaining < file.data.suites.len() {
+                return (file, remaining);
+            }
+            remaining -= file.data.suites.len();
+        }
+        (self.current_file(), suite)
+    }
+
+    /// The current test's `<testcase>` element as written in its report,
+    /// dedented. Each case's span is recorded when its report is parsed,
+    /// before any prefilter drops cases; a file is read on first use, then
+    /// cached until it is reloaded.
+    pub fn raw_xml(&self) -> Result<String> {
+        let (Some(suite), Some(tc)) = (self.current_suite_index(), self.current_test()) else {
+            bail!("No test selected");
+        };
+        let Some(span) = tc.span.clone() else {
+            bail!("No markup recorded for {}", tc.name);
+        };
+        let file = self.suite_origin(suite);
+
+        let mut cache = self.raw_source.borrow_mut();
+        let text = match &file.source {
+            Some(text) => text,
+            None => {
+                if cache.as_ref().is_none_or(|c| c.path != file.path) {
+                    let text = std::fs::read_to_string(&file.path)
+                        .with_context(|| format!("Failed to read {}", file.path.display()))?;
+                    *cache = Some(RawSource {
+                        path: file.path.clone(),
+                        text,
+                    });
+                }
+                let Some(source) = cache.as_ref() else {
+                    bail!("No source loaded");
+                };
+                &source.text
+            }
+        };
+        let Some(markup) = text.get(span.clone()) else {
+            bail!(
+                "Test not found in {}; it may have changed on disk",
+                file.filename
+            );
+        };
+
+        // Continuation lines keep the file's indentation; strip what the
+        // opening tag had.
+        let line_start = text[..span.start].rfind('\n').map_or(0, |i| i + 1);
+        let indent = &text[line_start..span.start];
+        let indent = indent.len() - indent.trim_start().len();
+        Ok(markup
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let leading = line.len() - line.trim_start().len();
+                if i == 0 {
+                    line
+                } else {
+                    &line[leading.min(indent)..]
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    pub fn toggle_raw_xml(&mut self) {
+        self.show_raw_xml = !self.show_raw_xml;
+        self.detail_focus = DetailPane::Main;
+        self.reset_detail_scroll();
+    }
+
+    /// Indices into the current file's suites that pass the status filter,
+    /// in the chosen sort order. `selected_suite` is a position in this
+    /// list.
+    pub fn visible_suites(&self) -> Vec<usize> {
+        let data = &self.current_file().data;
+        let order = match self.suite_sort.suite_order() {
+            Some(order) => data.order_suite_indices(order),
+            None => (0..data.suites.len()).collect(),
+        };
+        order
+            .into_iter()
+            .filter(|&i| self.shows_suite(&data.suites[i]))
+            .collect()
+    }
+
+    /// Indices into the current suite's test cases that pass the status
+    /// filter, in the chosen sort order. `selected_test` is a position in
+    /// this list.
+    pub fn visible_tests(&self) -> Vec<usize> {
+        let filter = self.test_filter();
+        let Some(suite) = self.current_suite() else {
+            return Vec::new();
+        };
+        let order = match self.test_sort.case_order() {
+            Some(order) => suite.order_indices(order),
+            None => (0..suite.test_cases.len()).collect(),
+        };
+        order
+            .into_iter()
+            .filter(|&i| filter.shows(suite, &suite.test_cases[i]))
+            .collect()
+    }
+
+    /// The statuses the lists show: the status filter's, less passes while
+    /// they are hidden.
+    pub fn shown_statuses(&self) -> Vec<TestStatus> {
+        self.status_filter
+            .statuses()
+            .iter()
+            .copied()
+            .filter(|&s| !(self.hide_passed && s == TestStatus::Passed))
+            .collect()
+    }
+
+    fn test_filter(&self) -> TestFilter<'_> {
+        TestFilter {
+            statuses: self.shown_statuses(),
+            known_failures: match (self.status_filter, &self.baseline) {
+                (StatusFilter::NewFailures, Some(baseline)) => Some(&baseline.failing),
+                _ => None,
+            },
+            min_time: self.min_time,
+            class_pattern: self.class_filter.as_deref(),
+        }
+    }
+
+    /// Whether a suite has any test the lists show. With nothing filtered
+    /// out, empty suites are shown too.
+    fn shows_suite(&self, suite: &TestSuite) -> bool {
+        let filter = self.test_filter();
+        self.status_filter == StatusFilter::All
+            && !self.hide_passed
+            && self.min_time.is_none()
+            && self.class_filter.is_none()
+            || suite.test_cases.iter().any(|tc| filter.shows(suite, tc))
+    }
+
+    /// Hides or shows passed tests in every list, keeping the selection
+    /// where it can.
+    pub fn toggle_hide_passed(&mut self) {
+        self.refilter(|app| app.hide_passed = !app.hide_passed);
+        self.status_message = Some(if self.hide_passed {
+            "Hiding passed tests".into()
+        } else {
+            "Showing passed tests".into()
+        });
+    }
+
+    /// Steps the duration threshold through [`MIN_TIMES`] and back to
+    /// off, keeping the selection where it can.
+    pub fn cycle_min_time(&mut self) {
+        let next = match self.min_time {
+            None => Some(MIN_TIMES[0]),
+            Some(min) => MIN_TIMES.iter().copied().find(|&t| t > min),
+        };
+        self.refilter(|app| app.min_time = next);
+        self.status_message = Some(match next {
+            Some(min) => format!("Showing tests slower than {}", threshold_label(min)),
+            None => "Showing tests of any duration".into(),
+        });
+    }
+
+    pub fn start_class_filter(&mut self) {
+        self.class_input = self.class_filter.clone().unwrap_or_default();
+        self.input_mode = InputMode::ClassFilter;
+    }
+
+    /// Limits the lists to classes matching the typed pattern, or lifts
+    /// the limit when it's empty.
+    pub fn confirm_class_filter(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let pattern = self.class_input.trim();
+        let pattern = (!pattern.is_empty()).then(|| pattern.to_string());
+        self.status_message = Some(match &pattern {
+            Some(pattern) => format!("Showing classes matching {}", pattern),
+            None => "Showing every class".into(),
+        });
+        self.refilter(|app| app.class_filter = pattern);
+    }
+
+    /// How many tests of the current report the typed pattern matches.
+    pub fn class_input_matches(&self) -> usize {
+        let pattern = self.class_input.trim();
+        let filter = TestFilter {
+            statuses: TestStatus::ALL.to_vec(),
+            known_failures: None,
+            min_time: None,
+            class_pattern: (!pattern.is_empty()).then_some(pattern),
+        };
+        self.current_file()
+            .data
+            .suites
+            .iter()
+            .flat_map(|suite| suite.test_cases.iter().map(move |tc| (suite, tc)))
+            .filter(|(suite, tc)| filter.shows(suite, tc))
+            .count()
+    }
+
+    /// Counts quarantined tests in the headline failures, or leaves them
+    /// out again.
+    pub fn toggle_count_quarantined(&mut self) {
+        if self.quarantine.is_empty() {
+            self.status_message = Some("No quarantine patterns configured".into());
+            return;
+        }
+        self.count_quarantined = !self.count_quarantined;
+        self.status_message = Some(if self.count_quarantined {
+            "Counting quarantined tests as failures".into()
+        } else {
+            "Leaving quarantined tests out of the failure counts".into()
+        });
+    }
+
+    /// Shows or hides the test list's class and file columns.
+    pub fn toggle_columns(&mut self) {
+        self.hide_columns = !self.hide_columns;
+        self.status_message = Some(if self.hide_columns {
+            "Hiding class and file columns".into()
+        } else {
+            "Showing class and file columns".into()
+        });
+    }
+
+    pub fn current_suite_index(&self) -> Option<usize> {
+        self.visible_suites().get(self.selected_suite).copied()
+    }
+
+    pub fn current_suite(&self) -> Option<&TestSuite> {
+        let index = self.current_suite_index()?;
+        self.current_file().data.suites.get(index)
+    }
+
+    pub fn current_test_index(&self) -> Option<usize> {
+        match *self.test_rows().get(self.selected_test)? {
+            TestRow::Test { test, .. } => Some(test),
+            TestRow::Params(_) => None,
+        }
+    }
+
+    /// The visible tests grouped by parameterized test, in list order.
+    pub fn param_groups(&self) -> Vec<ParameterizedGroup> {
+        match self.current_suite() {
+            Some(suite) => junit_parser::group_parameterized(suite, &self.visible_tests()),
+            None => Vec::new(),
+        }
+    }
+
+    /// Rows of the test list: the cases of each parameterized test are
+    /// collapsed under one header unless it is expanded.
+    pub fn test_rows(&self) -> Vec<TestRow> {
+        let mut rows = Vec::new();
+        for (gi, group) in self.param_groups().into_iter().enumerate() {
+            if let [test] = group.cases[..] {
+                rows.push(TestRow::Test { test, group: None });
+                continue;
+            }
+            rows.push(TestRow::Params(gi));
+            if self.expanded_params.contains(&group.name) {
+                rows.extend(group.cases.iter().map(|&test| TestRow::Test {
+                    test,
+                    group: Some(gi),
+                }));
+            }
+        }
+        rows
+    }
+
+    /// The visible tests in the order the test list shows them, including
+    /// those under collapsed headers.
+    fn listed_tests(&self) -> Vec<usize> {
+        self.param_groups()
+            .into_iter()
+            .flat_map(|group| group.cases)
+            .collect()
+    }
+
+    /// The test under the test list cursor, or the first case of the
+    /// parameterized test whose header is there.
+    fn test_near_cursor(&self) -> Option<usize> {
+        match *self.test_rows().get(self.selected_test)? {
+            TestRow::Test { test, .. } => Some(test),
+            TestRow::Params(gi) => self.param_groups().get(gi)?.cases.first().copied(),
+        }
+    }
+
+    /// Row of `test` in the test list, or of the header it is collapsed
+    /// under.
+    fn test_row_position(&self, test: usize) -> Option<usize> {
+        let rows = self.test_rows();
+        rows.iter()
+            .position(|row| matches!(row, TestRow::Test { test: t, .. } if *t == test))
+            .or_else(|| {
+                let groups = self.param_groups();
+                let gi = groups.iter().position(|g| g.cases.contains(&test))?;
+                rows.iter().position(|&row| row == TestRow::Params(gi))
+            })
+    }
+
+    /// Moves the test list cursor onto `test`, expanding the parameterized
+    /// test it belongs to.
+    fn select_test(&mut self, test: usize) {
+        let group = self
+            .param_groups()
+            .into_iter()
+            .find(|g| g.cases.len() > 1 && g.cases.contains(&test));
+        if let Some(group) = group {
+            self.expanded_params.insert(group.name);
+        }
+        self.selected_test = self.test_row_position(test).unwrap_or(0);
+    }
+
+    /// The parameterized test under the cursor, as a header or one of its
+    /// cases.
+    fn selected_params(&self) -> Option<usize> {
+        match *self.test_rows().get(self.selected_test)? {
+            TestRow::Params(gi)
+            | TestRow::Test {
+                group: Some(gi), ..
+            } => Some(gi),
+            TestRow::Test { group: None, .. } => None,
+        }
+    }
+
+    fn selected_params_expanded(&self) -> bool {
+        let groups = self.param_groups();
+        self.selected_params()
+            .is_some_and(|gi| self.expanded_params.contains(&groups[gi].name))
+    }
+
+    /// Expands or collapses the parameterized test under the cursor. On one
+    /// of its cases the test collapses and the cursor moves onto its header.
+    pub fn toggle_params(&mut self) {
+        let Some(gi) = self.selected_params() else {
+            return;
+        };
+        let name = self.param_groups().swap_remove(gi).name;
+        if !self.expanded_params.remove(&name) {
+            self.expanded_params.insert(name);
+        }
+        self.selected_test = self
+            .test_rows()
+            .iter()
+            .position(|&row| row == TestRow::Params(gi))
+            .unwrap_or(0);
+    }
+
+    pub fn current_test(&self) -> Option<&TestCase> {
+        let index = self.current_test_index()?;
+        self.current_suite()?.test_cases.get(index)
+    }
+
+    /// The test under the cursor in whichever view is open.
+    pub fn cursor_test(&self) -> Option<&TestCase> {
+        match self.view {
+            View::Tree => self.tree_test(),
+            View::Slowest => self.slowest_test(),
+            View::Groups => self.group_test(),
+            View::Skips => self.skip_test(),
+            View::Packages => self.package_test(),
+            _ => self.current_test(),
+        }
+    }
+
+    /// Rows of the tree view in display order, honouring the status filter
+    /// and test sort inside expanded suites.
+    pub fn tree_rows(&self) -> Vec<TreeRow> {
+        let filter = self.test_filter();
+        let suites = &self.current_file().data.suites;
+        let mut rows = Vec::new();
+        for si in self.visible_suites() {
+            rows.push(TreeRow::Suite(si));
+            if self.expanded.contains(&si) {
+                let suite = &suites[si];
+                let order = match self.test_sort.case_order() {
+                    Some(order) => suite.order_indices(order),
+                    None => (0..suite.test_cases.len()).collect(),
+                };
+                rows.extend(
+                    order
+                        .into_iter()
+                        .filter(|&ti| filter.shows(suite, &suite.test_cases[ti]))
+                        .map(|ti| TreeRow::Test(si, ti)),
+                );
+            }
+        }
+        rows
+    }
+
+    /// The test under the tree cursor, if the cursor is on a test row.
+    pub fn tree_test(&self) -> Option<&TestCase> {
+        match self.tree_rows().get(self.tree_selected)? {
+            TreeRow::Test(si, ti) => self.current_file().data.suites[*si].test_cases.get(*ti),
+            TreeRow::Suite(_) => None,
+        }
+    }
+
+    /// The view at the top of the navigation stack.
+    fn top_view(&self) -> View {
+        if self.tree_mode {
+            View::Tree
+        } else {
+            View::SuiteList
+        }
+    }
+
+    pub fn toggle_tree_mode(&mut self) {
+        if self.view == View::TestDetail {
+            return;
+        }
+        self.tree_mode = !self.tree_mode;
+        if self.tree_mode {
+            if self.view == View::TestList {
+                self.sync_tree_to_selection();
+            } else {
+                self.tree_selected = self
+                    .tree_rows()
+                    .iter()
+                    .position(|&r| Some(r) == self.current_suite_index().map(TreeRow::Suite))
+                    .unwrap_or(0);
+            }
+        }
+        self.view = self.top_view();
+        self.clear_search();
+    }
+
+    /// Expands or collapses the suite under the cursor. On a test row the
+    /// parent suite collapses and the cursor moves onto it.
+    pub fn toggle_expand(&mut self) {
+        let rows = self.tree_rows();
+        let suite = match rows.get(self.tree_selected) {
+            Some(TreeRow::Suite(si)) | Some(TreeRow::Test(si, _)) => *si,
+            None => return,
+        };
+        if !self.expanded.remove(&suite) {
+            self.expanded.insert(suite);
+        }
+        self.tree_selected = self
+            .tree_rows()
+            .iter()
+            .position(|&r| r == TreeRow::Suite(suite))
+            .unwrap_or(0);
+    }
+
+    /// Position of `row` in the tree, falling back to its suite's header
+    /// when the row itself is no longer shown.
+    fn tree_position_near(&self, row: Option<TreeRow>) -> usize {
+        let rows = self.tree_rows();
+        let suite_row = row.map(|r| match r {
+            TreeRow::Suite(si) | TreeRow::Test(si, _) => TreeRow::Suite(si),
+        });
+        row.and_then(|r| rows.iter().position(|&x| x == r))
+            .or_else(|| suite_row.and_then(|r| rows.iter().position(|&x| x == r)))
+            .unwrap_or(0)
+    }
+
+    /// Points the tree cursor at the currently selected test, expanding
+    /// its suite if needed.
+    fn sync_tree_to_selection(&mut self) {
+        let Some(suite) = self.current_suite_index() else {
+            return;
+        };
+        self.expanded.insert(suite);
+        let target = match self.current_test_index() {
+            Some(test) => TreeRow::Test(suite, test),
+            None => TreeRow::Suite(suite),
+        };
+        self.tree_selected = self
+            .tree_rows()
+            .iter()
+            .position(|&r| r == target)
+            .unwrap_or(0);
+    }
+
+    pub fn suite_count(&self) -> usize {
+        self.visible_suites().len()
+    }
+
+    /// Rows of the test list, counting each collapsed parameterized test
+    /// once.
+    pub fn test_count(&self) -> usize {
+        self.test_rows().len()
+    }
+
+    pub fn cycle_status_filter(&mut self) {
+        let mut next = self.status_filter.next();
+        if next == StatusFilter::NewFailures && self.baseline.is_none() {
+            next = next.next();
+        }
+        self.set_status_filter(next);
+    }
+
+    /// Switches to the test list tab at `index` (0 is All).
+    pub fn select_tab(&mut self, index: usize) {
+        if let Some(&tab) = StatusFilter::TABS.get(index) {
+            self.set_status_filter(tab);
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        let len = StatusFilter::TABS.len();
+        self.select_tab((self.status_filter.tab_index() + 1) % len);
+    }
+
+    pub fn prev_tab(&mut self) {
+        let len = StatusFilter::TABS.len();
+        self.select_tab((self.status_filter.tab_index() + len - 1) % len);
+    }
+
+    /// Applies `filter`, keeping the selected suite and test selected when
+    /// they are still shown.
+    fn set_status_filter(&mut self, filter: StatusFilter) {
+        self.refilter(|app| app.status_filter = filter);
+    }
+
+    /// Changes what the lists show with `change`, keeping the selected
+    /// suite and test selected when they are still shown.
+    fn refilter(&mut self, change: impl FnOnce(&mut Self)) {
+        let suite = self.current_suite_index();
+        let test = self.test_near_cursor();
+        let tree_row = self.tree_rows().get(self.tree_selected).copied();
+        let package_row = self.package_rows().get(self.packages_selected).cloned();
+        change(self);
+
+        match suite.and_then(|s| self.visible_suites().iter().position(|&i| i == s)) {
+            Some(position) => self.selected_suite = position,
+            None => {
+                // The open suite has nothing left to show.
+                self.selected_suite = 0;
+                if self.view != View::Packages {
+                    self.view = self.top_view();
+                }
+            }
+        }
+        if let Some(row) = package_row {
+            let rows = self.package_rows();
+            self.packages_selected = rows
+                .iter()
+                .position(|r| *r == row)
+                .unwrap_or_else(|| self.packages_selected.min(rows.len().saturating_sub(1)));
+        }
+        match test.and_then(|t| self.test_row_position(t)) {
+            Some(position) => self.selected_test = position,
+            None => {
+                self.selected_test = 0;
+                if self.view == View::TestDetail {
+                    self.view = View::TestList;
+                }
+            }
+        }
+        if self.tree_mode && self.view != View::SuiteList {
+            if self.view == View::TestList {
+                self.view = View::Tree;
+            }
+            self.tree_selected = self.tree_position_near(tree_row);
+        }
+    }
+
+    /// Advances the test sort order, keeping the selected test selected.
+    pub fn cycle_test_sort(&mut self) {
+        self.set_test_sort(self.test_sort.next());
+    }
+
+    /// Sorts tests by a clicked column header. Clicking the column already
+    /// sorted on goes back to report order.
+    pub fn sort_tests_by(&mut self, sort: TestSort) {
+        if self.test_sort == sort {
+            self.set_test_sort(TestSort::Report);
+        } else {
+            self.set_test_sort(sort);
+        }
+    }
+
+    /// Changes the test sort order, keeping the selected test selected.
+    fn set_test_sort(&mut self, sort: TestSort) {
+        let test = self.test_near_cursor();
+        let tree_row = self.tree_rows().get(self.tree_selected).copied();
+        self.test_sort = sort;
+        self.tree_selected = self.tree_position_near(tree_row);
+        self.selected_test = test.and_then(|t| self.test_row_position(t)).unwrap_or(0);
+    }
+
+    /// Advances the suite sort order, keeping the selected suite selected.
+    pub fn cycle_suite_sort(&mut self) {
+        let suite = self.current_suite_index();
+        let tree_row = self.tree_rows().get(self.tree_selected).copied();
+        self.suite_sort = self.suite_sort.next();
+        self.tree_selected = self.tree_position_near(tree_row);
+        self.selected_suite = suite
+            .and_then(|s| self.visible_suites().iter().position(|&i| i == s))
+            .unwrap_or(0);
+    }
+
+    /// Selects a test by its raw indices, clearing the status filter if it
+    /// would hide the target. The "All files" view stays open, pointing at
+    /// the same test in the merged report.
+    pub fn select_test_at(&mut self, file: usize, suite: usize, test: usize) {
+        if self.show_all {
+            let offset: usize = self.files[..file].iter().map(|f| f.data.suites.len()).sum();
+            return self.select_current_at(offset + suite, test);
+        }
+        if file != self.selected_file {
+            self.expanded.clear();
+        }
+        self.selected_file = file;
+        self.select_current_at(suite, test);
+    }
+
+    /// Selects a test by its raw indices within the current report.
+    fn select_current_at(&mut self, suite: usize, test: usize) {
+        self.return_view = None;
+        let s = &self.current_file().data.suites[suite];
+        let status = s.test_cases[test].status();
+        let filter = self.test_filter();
+        let hidden_by_baseline = filter
+            .known_failures
+            .is_some_and(|known| known.contains(&diff::TestKey::new(s, &s.test_cases[test])));
+        let too_fast = !filter.is_slow_enough(&s.test_cases[test]);
+        let other_class = !filter.matches_class(&s.test_cases[test]);
+        if !self.status_filter.statuses().contains(&status) || hidden_by_baseline {
+            self.status_filter = StatusFilter::All;
+        }
+        if status == TestStatus::Passed {
+            self.hide_passed = false;
+        }
+        if too_fast {
+            self.min_time = None;
+        }
+        if other_class {
+            self.class_filter = None;
+        }
+        self.selected_suite = self
+            .visible_suites()
+            .iter()
+            .position(|&i| i == suite)
+            .unwrap_or(0);
+        self.select_test(test);
+    }
+
+    pub fn select_next(&mut self) {
+        match self.view {
+            View::SuiteList => {
+                let count = self.suite_count();
+                if count > 0 && self.selected_suite < count - 1 {
+                    self.selected_suite += 1;
+                }
+            }
+            View::TestList => {
+                let count = self.test_count();
+                if count > 0 && self.selected_test < count - 1 {
+                    self.selected_test += 1;
+                }
+            }
+            View::Tree => {
+                let count = self.tree_rows().len();
+                if count > 0 && self.tree_selected < count - 1 {
+                    self.tree_selected += 1;
+                }
+            }
+            View::Slowest => {
+                let count = self.slowest_rows().len();
+                if count > 0 && self.slowest_selected < count - 1 {
+                    self.slowest_selected += 1;
+                }
+            }
+            View::Groups => {
+                let count = self.group_rows().len();
+                if count > 0 && self.groups_selected < count - 1 {
+                    self.groups_selected += 1;
+                }
+            }
+            View::Skips => {
+                let count = self.skip_rows().len();
+                if count > 0 && self.skips_selected < count - 1 {
+                    self.skips_selected += 1;
+                }
+            }
+            View::Packages => {
+                let count = self.package_rows().len();
+                if count > 0 && self.packages_selected < count - 1 {
+                    self.packages_selected += 1;
+                }
+            }
+            View::Durations => {
+                let count = self.suite_count();
+                if count > 0 && self.durations_selected < count - 1 {
+                    self.durations_selected += 1;
+                }
+            }
+            View::TestDetail => self.set_detail_scroll(self.detail_scroll() as usize + 1),
+            View::Diff | View::History => {
+                let max = self.max_scroll(DetailPane::Main);
+                self.scroll_offset = self.scroll_offset.saturating_add(1).min(max);
+            }
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        match self.view {
+            View::SuiteList => {
+                self.selected_suite = self.selected_suite.saturating_sub(1);
+            }
+            View::TestList => {
+                self.selected_test = self.selected_test.saturating_sub(1);
+            }
+            View::Tree => {
+                self.tree_selected = self.tree_selected.saturating_sub(1);
+            }
+            View::Slowest => {
+                self.slowest_selected = self.slowest_selected.saturating_sub(1);
+            }
+            View::Groups => {
+                self.groups_selected = self.groups_selected.saturating_sub(1);
+            }
+            View::Skips => {
+                self.skips_selected = self.skips_selected.saturating_sub(1);
+            }
+            View::Packages => {
+                self.packages_selected = self.packages_selected.saturating_sub(1);
+            }
+            View::Durations => {
+                self.durations_selected = self.durations_selected.saturating_sub(1);
+            }
+            View::TestDetail => {
+                self.set_detail_scroll((self.detail_scroll() as usize).saturating_sub(1))
+            }
+            View::Diff | View::History => {
+                self.scroll_offset = self.scroll_offset.saturating_sub(1);
+            }
+        }
+    }
+
+    pub fn select_first(&mut self) {
+        match self.view {
+            View::SuiteList => self.selected_suite = 0,
+            View::TestList => self.selected_test = 0,
+            View::Tree => self.tree_selected = 0,
+            View::Slowest => self.slowest_selected = 0,
+            View::Groups => self.groups_selected = 0,
+            View::Skips => self.skips_selected = 0,
+            View::Packages => self.packages_selected = 0,
+            View::Durations => self.durations_selected = 0,
+            View::TestDetail => self.set_detail_scroll(0),
+            View::Diff | View::History => self.scroll_offset = 0,
+        }
+    }
+
+    pub fn select_last(&mut self) {
+        match self.view {
+            View::SuiteList => {
+                let count = self.suite_count();
+                if count > 0 {
+                    self.selected_suite = count - 1;
+                }
+            }
+            View::TestList => {
+                let count = self.test_count();
+                if count > 0 {
+                    self.selected_test = count - 1;
+                }
+            }
+            View::Tree => {
+                let count = self.tree_rows().len();
+                if count > 0 {
+                    self.tree_selected = count - 1;
+                }
+            }
+            View::Slowest => {
+                let count = self.slowest_rows().len();
+                if count > 0 {
+                    self.slowest_selected = count - 1;
+                }
+            }
+            View::Groups => {
+                let count = self.group_rows().len();
+                if count > 0 {
+                    self.groups_selected = count - 1;
+                }
+            }
+            View::Skips => {
+                let count = self.skip_rows().len();
+                if count > 0 {
+                    self.skips_selected = count - 1;
+                }
+            }
+            View::Packages => {
+                let count = self.package_rows().len();
+                if count > 0 {
+                    self.packages_selected = count - 1;
+                }
+            }
+            View::Durations => {
+                let count = self.suite_count();
+                if count > 0 {
+                    self.durations_selected = count - 1;
+                }
+            }
+            View::TestDetail => self.set_detail_scroll(usize::MAX),
+            View::Diff | View::History => self.scroll_offset = self.max_scroll(DetailPane::Main),
+        }
+    }
+
+    pub fn enter(&mut self) {
+        match self.view {
+            View::SuiteList => {
+                if self.suite_count() > 0 {
+                    self.selected_test = 0;
+                    self.view = View::TestList;
+                    self.clear_search();
+                }
+            }
+            View::TestList => {
+                if let Some(TestRow::Params(_)) = self.test_rows().get(self.selected_test) {
+                    self.toggle_params();
+                } else if self.test_count() > 0 {
+                    self.reset_detail_scroll();
+                    self.view = View::TestDetail;
+                    self.clear_search();
+                }
+            }
+            View::Slowest => {
+                if let Some(&(suite, test)) = self.slowest_rows().get(self.slowest_selected) {
+                    self.select_current_at(suite, test);
+                    self.return_view = Some(View::Slowest);
+                    self.reset_detail_scroll();
+                    self.view = View::TestDetail;
+                    self.clear_search();
+                }
+            }
+            View::Groups => match self.group_rows().get(self.groups_selected) {
+                Some(&GroupRow::Test { suite, test, .. }) => {
+                    self.select_current_at(suite, test);
+                    self.return_view = Some(View::Groups);
+                    self.reset_detail_scroll();
+                    self.view = View::TestDetail;
+                    self.clear_search();
+                }
+                Some(GroupRow::Group(_)) => self.toggle_group(),
+                None => {}
+            },
+            View::Skips => match self.skip_rows().get(self.skips_selected) {
+                Some(&GroupRow::Test { suite, test, .. }) => {
+                    self.select_current_at(suite, test);
+                    self.return_view = Some(View::Skips);
+                    self.reset_detail_scroll();
+                    self.view = View::TestDetail;
+                    self.clear_search();
+                }
+                Some(GroupRow::Group(_)) => self.toggle_skip_group(),
+                None => {}
+            },
+            View::Packages => match self.package_rows().get(self.packages_selected) {
+                Some(&PackageRow::Test { suite, test, .. }) => {
+                    self.select_current_at(suite, test);
+                    self.return_view = Some(View::Packages);
+                    self.reset_detail_scroll();
+                    self.view = View::TestDetail;
+                    self.clear_search();
+                }
+                Some(PackageRow::Node { .. }) => self.toggle_package(),
+                None => {}
+            },
+            View::Durations => {
+                if self.durations_selected < self.suite_count() {
+                    self.selected_suite = self.durations_selected;
+                    self.selected_test = 0;
+                    self.view = View::TestList;
+                    self.clear_search();
+                }
+            }
+            View::Tree => match self.tree_rows().get(self.tree_selected) {
+                Some(&TreeRow::Test(suite, test)) => {
+                    self.select_current_at(suite, test);
+                    self.reset_detail_scroll();
+                    self.view = View::TestDetail;
+                    self.clear_search();
+                }
+                Some(TreeRow::Suite(_)) => self.toggle_expand(),
+                None => {}
+            },
+            View::TestDetail | View::Diff | View::History => {}
+        }
+    }
+
+    /// Picks or unpicks the test under the cursor in the test list.
+    /// On a parameterized test's header, every case is picked, or unpicked
+    /// when all already are.
+    pub fn toggle_pick(&mut self) {
+        let Some(suite) = self.current_suite_index() else {
+            return;
+        };
+        let tests = match self.test_rows().get(self.selected_test) {
+            Some(&TestRow::Test { test, .. }) => vec![test],
+            Some(&TestRow::Params(gi)) => self.param_groups().swap_remove(gi).cases,
+            None => return,
+        };
+        if tests
+            .iter()
+            .all(|&test| self.picked.contains(&(suite, test)))
+        {
+            for &test in &tests {
+                self.picked.remove(&(suite, test));
+            }
+        } else {
+            self.picked.extend(tests.iter().map(|&test| (suite, test)));
+        }
+        self.pick_anchor = tests.first().map(|&test| (suite, test));
+        self.select_next();
+    }
+
+    /// Picks every visible test between the last one toggled with Space
+    /// and the cursor, or just the cursor's test without an anchor in this
+    /// suite.
+    pub fn pick_range(&mut self) {
+        let Some(suite) = self.current_suite_index() else {
+            return;
+        };
+        let anchor = self
+            .pick_anchor
+            .filter(|&(s, _)| s == suite)
+            .and_then(|(_, t)| self.test_row_position(t))
+            .unwrap_or(self.selected_test);
+        let (from, to) = if anchor <= self.selected_test {
+            (anchor, self.selected_test)
+        } else {
+            (self.selected_test, anchor)
+        };
+        let groups = self.param_groups();
+        for row in self.test_rows().get(from..=to).unwrap_or_default() {
+            match *row {
+                TestRow::Test { test, .. } => {
+                    self.picked.insert((suite, test));
+                }
+                TestRow::Params(gi) => {
+                    self.picked
+                        .extend(groups[gi].cases.iter().map(|&test| (suite, test)));
+                }
+            }
+        }
+        self.status_message = Some(format!("{} tests picked", self.picked.len()));
+    }
+
+    pub fn clear_picks(&mut self) {
+        self.picked.clear();
+        self.pick_anchor = None;
+    }
+
+    /// The picked tests, in report order.
+    pub fn picked_tests(&self) -> Vec<&TestCase> {
+        let suites = &self.current_file().data.suites;
+        self.picked
+            .iter()
+            .filter_map(|&(s, t)| suites.get(s)?.test_cases.get(t))
+            .collect()
+    }
+
+    pub fn start_export(&mut self) {
+        if self.picked.is_empty() {
+            self.status_message = Some("Pick tests with Space or V first".into());
+            return;
+        }
+        if self.export_input.is_empty() {
+            self.export_input = "ratunit-tests.txt".into();
+        }
+        self.export_detail = false;
+        self.input_mode = InputMode::Export;
+    }
+
+    /// Prompts for a file to write the open test's detail to, suggesting
+    /// a Markdown file named after the test.
+    pub fn start_export_detail(&mut self) {
+        let Some(tc) = self.current_test() else {
+            return;
+        };
+        let stem: String = tc
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.export_input = format!("{}.md", stem.trim_matches('_'));
+        self.export_detail = true;
+        self.input_mode = InputMode::Export;
+    }
+
+    pub fn start_add_files(&mut self) {
+        self.add_completions.clear();
+        self.input_mode = InputMode::AddFiles;
+    }
+
+    /// Completes the add-files prompt's path on Tab.
+    pub fn complete_add_input(&mut self) {
+        let (completed, matches) = complete::complete_path(&self.add_input);
+        self.add_input = completed;
+        self.add_completions = matches;
+    }
+
+    /// Loads the report or directory typed at the add-files prompt into
+    /// the session and opens the first file it brought in.
+    pub fn add_files(&mut self) {
+        let input = self.add_input.trim();
+        if input.is_empty() {
+            return;
+        }
+        let reports = match FileReport::load(&complete::expand_home(input)) {
+            Ok(reports) => reports,
+            Err(e) => {
+                self.status_message = Some(format!("{:#}", e));
+                return;
+            }
+        };
+        let count = reports.len();
+        let mut first = None;
+        for mut report in reports {
+            self.prefilter.apply(&mut report.data);
+            report.distinguish(&self.files);
+            self.dropped.retain(|path| !report.is_at(path));
+            first.get_or_insert_with(|| report.filename.clone());
+            self.update_file(report);
+        }
+        if let Some(row) = first.and_then(|name| {
+            self.sidebar_entries()
+                .iter()
+                .position(|&e| e.is_some_and(|i| self.files[i].filename == name))
+        }) {
+            self.select_sidebar(row);
+        }
+        self.add_input.clear();
+        self.status_message = Some(format!(
+            "Added {} file{}",
+            count,
+            if count == 1 { "" } else { "s" }
+        ));
+    }
+
+    /// Opens the next (or previous) test's detail from the detail view,
+    /// stepping through the list it was opened from. The test list
+    /// continues into neighbouring suites; the status filter stays.
+    pub fn step_detail_test(&mut self, forward: bool) {
+        let moved = match self.return_view {
+            Some(View::Slowest) => {
+                let rows = self.slowest_rows();
+                match step(self.slowest_selected, rows.len(), forward) {
+                    Some(next) => {
+                        self.slowest_selected = next;
+                        let (suite, test) = rows[next];
+                        self.select_current_at(suite, test);
+                        self.return_view = Some(View::Slowest);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            Some(view @ (View::Groups | View::Skips)) => {
+                let (rows, selected) = if view == View::Groups {
+                    (self.group_rows(), self.groups_selected)
+                } else {
+                    (self.skip_rows(), self.skips_selected)
+                };
+                let is_test = |&(_, row): &(usize, &GroupRow)| matches!(row, GroupRow::Test { .. });
+                let next = if forward {
+                    rows.iter().enumerate().skip(selected + 1).find(is_test)
+                } else {
+                    rows.iter().enumerate().take(selected).rev().find(is_test)
+                };
+                match next {
+                    Some((next, &GroupRow::Test { suite, test, .. })) => {
+                        if view == View::Groups {
+                            self.groups_selected = next;
+                        } else {
+                            self.skips_selected = next;
+                        }
+                        self.select_current_at(suite, test);
+                        self.return_view = Some(view);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            Some(View::Packages) => {
+                let rows = self.package_rows();
+                let is_test =
+                    |&(_, row): &(usize, &PackageRow)| matches!(row, PackageRow::Test { .. });
+                let next = if forward {
+                    rows.iter()
+                        .enumerate()
+                        .skip(self.packages_selected + 1)
+                        .find(is_test)
+                } else {
+                    rows.iter()
+                        .enumerate()
+                        .take(self.packages_selected)
+                        .rev()
+                        .find(is_test)
+                };
+                match next {
+                    Some((next, &PackageRow::Test { suite, test, .. })) => {
+                        self.packages_selected = next;
+                        self.select_current_at(suite, test);
+                        self.return_view = Some(View::Packages);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            _ => self.step_list_test(forward),
+        };
+        if moved {
+            self.reset_detail_scroll();
+            self.clear_search();
+        } else {
+            self.status_message = Some(if forward {
+                "Already at the last test".into()
+            } else {
+                "Already at the first test".into()
+            });
+        }
+    }
+
+    /// Moves `selected_test` one visible test along in list order,
+    /// expanding parameterized tests it steps into, and crossing into the
+    /// nearest suite that has visible tests at either end.
+    fn step_list_test(&mut self, forward: bool) -> bool {
+        let tests = self.listed_tests();
+        let position = self
+            .current_test_index()
+            .and_then(|test| tests.iter().position(|&t| t == test));
+        if let Some(next) = position.and_then(|p| step(p, tests.len(), forward)) {
+            self.select_test(tests[next]);
+            return true;
+        }
+        let original = self.selected_suite;
+        let mut suite = original;
+        while let Some(next) = step(suite, self.suite_count(), forward) {
+            suite = next;
+            self.selected_suite = suite;
+            let tests = self.listed_tests();
+            let test = if forward { tests.first() } else { tests.last() };
+            if let Some(&test) = test {
+                self.select_test(test);
+                return true;
+            }
+        }
+        self.selected_suite = original;
+        false
+    }
+
+    pub fn go_back(&mut self) {
+        match self.view {
+            View::SuiteList => {}
+            View::Tree => {
+                let rows = self.tree_rows();
+                if let Some(TreeRow::Suite(si)) | Some(TreeRow::Test(si, _)) =
+                    rows.get(self.tree_selected)
+                {
+                    if self.expanded.contains(si) {
+                        self.toggle_expand();
+                    }
+                }
+            }
+            View::TestList if self.selected_params_expanded() => self.toggle_params(),
+            View::TestList => {
+                self.view = View::SuiteList;
+            }
+            View::TestDetail if self.return_view.is_some() => {
+                self.view = self.return_view.take().unwrap_or(View::TestList);
+            }
+            View::TestDetail if self.tree_mode => {
+                self.sync_tree_to_selection();
+                self.view = View::Tree;
+            }
+            View::TestDetail => {
+                self.view = View::TestList;
+            }
+            View::Groups if self.selected_group_expanded() => self.toggle_group(),
+            View::Skips if self.selected_skip_expanded() => self.toggle_skip_group(),
+            View::Packages if self.package_to_collapse().is_some() => self.collapse_package(),
+            View::Diff
+            | View::History
+            | View::Slowest
+            | View::Groups
+            | View::Skips
+            | View::Packages
+            | View::Durations => {
+                self.view = self.top_view();
+                self.scroll_offset = 0;
+            }
+        }
+        self.clear_search();
+    }
+
+    /// Scrolls the detail view back to the top, in every pane.
+    fn reset_detail_scroll(&mut self) {
+        self.selected_frame = None;
+        self.scroll_offset = 0;
+        self.h_scroll = 0;
+        self.stdout_scroll = 0;
+        self.stderr_scroll = 0;
+    }
+
+    /// The scroll position `j` / `k` move in the detail view.
+    pub fn detail_scroll(&self) -> u16 {
+        match self.detail_focus {
+            DetailPane::Main => self.scroll_offset,
+            DetailPane::Stdout => self.stdout_scroll,
+            DetailPane::Stderr => self.stderr_scroll,
+        }
+    }
+
+    /// Scrolls the focused detail pane to `row`, stopping where its last
+    /// line reaches the bottom of the pane.
+    fn set_detail_scroll(&mut self, row: usize) {
+        let row = row.min(self.max_scroll(self.detail_focus) as usize) as u16;
+        match self.detail_focus {
+            DetailPane::Main => self.scroll_offset = row,
+            DetailPane::Stdout => self.stdout_scroll = row,
+            DetailPane::Stderr => self.stderr_scroll = row,
+        }
+    }
+
+    /// How far `pane` can scroll, as of the last time it was drawn.
+    pub fn max_scroll(&self, pane: DetailPane) -> u16 {
+        self.detail_max_scroll.get()[pane as usize]
+    }
+
+    /// Records how far `pane` can scroll. Called while drawing, since only
+    /// then is the wrapped height of the content known.
+    pub fn set_max_scroll(&self, pane: DetailPane, max: u16) {
+        let mut limits = self.detail_max_scroll.get();
+        limits[pane as usize] = max;
+        self.detail_max_scroll.set(limits);
+    }
+
+    pub fn toggle_line_numbers(&mut self) {
+        self.line_numbers = !self.line_numbers;
+    }
+
+    pub fn toggle_pretty(&mut self) {
+        self.pretty = !self.pretty;
+        self.status_message = Some(
+            if self.pretty {
+                "Pretty-printing JSON and XML"
+            } else {
+                "Showing JSON and XML as written"
+            }
+            .into(),
+        );
+    }
+
+    pub fn start_go_to_line(&mut self) {
+        self.line_input.clear();
+        self.input_mode = InputMode::GoToLine;
+    }
+
+    /// Scrolls so that `row` of `pane` is at the top. In the combined
+    /// layout every pane is part of the main one, so `row` counts from
+    /// the top of the whole detail.
+    pub fn scroll_detail_to(&mut self, pane: DetailPane, row: usize) {
+        if self.output_layout != OutputLayout::Combined {
+            self.detail_focus = pane;
+        }
+        self.set_detail_scroll(row);
+    }
+
+    /// Picks the frame at `index`, which sits on `row` of the main pane,
+    /// scrolling it into view.
+    pub fn select_frame(&mut self, index: usize, row: usize) {
+        self.selected_frame = Some(index);
+        let height = self.detail_height.get().max(1) as usize;
+        let top = self.scroll_offset as usize;
+        if self.detail_focus != DetailPane::Main || row < top || row >= top + height {
+            self.scroll_detail_to(DetailPane::Main, row.saturating_sub(height / 3));
+        }
+    }
+
+    pub fn cycle_output_layout(&mut self) {
+        self.output_layout = self.output_layout.next();
+        self.detail_focus = DetailPane::Main;
+        self.status_message = Some(format!("Output layout: {}", self.output_layout.label()));
+    }
+
+    /// Moves scrolling to the next pane of a split detail view.
+    pub fn cycle_detail_focus(&mut self) {
+        if self.output_layout != OutputLayout::Combined && !self.show_raw_xml {
+            self.detail_focus = self.detail_focus.next();
+        }
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.wrap_detail = !self.wrap_detail;
+        self.h_scroll = 0;
+    }
+
+    pub fn scroll_left(&mut self) {
+        self.h_scroll = self.h_scroll.saturating_sub(H_SCROLL_STEP);
+    }
+
+    pub fn scroll_right(&mut self) {
+        self.h_scroll = self.h_scroll.saturating_add(H_SCROLL_STEP);
+    }
+
+    pub fn next_file(&mut self) {
+        if self.multi_file {
+            self.select_sidebar((self.sidebar_selected() + 1) % self.sidebar_len());
+        }
+    }
+
+    /// Jumps to the `n`th file in the sidebar, counting from 1; `0` is
+    /// "All files".
+    pub fn select_file_number(&mut self, n: usize) {
+        if !self.multi_file {
+            return;
+        }
+        let offset = usize::from(self.all_files.is_some());
+        match n {
+            0 if offset == 1 => self.select_sidebar(0),
+            0 => {}
+            n => self.select_sidebar(n - 1 + offset),
+        }
+    }
+
+    pub fn prev_file(&mut self) {
+        if self.multi_file {
+            let len = self.sidebar_len();
+            self.select_sidebar((self.sidebar_selected() + len - 1) % len);
+        }
+    }
+
+    /// Sets the sidebar width, clamped to a usable range.
+    pub fn set_sidebar_width(&mut self, percent: u16) {
+        self.sidebar_width = percent.clamp(SIDEBAR_MIN, SIDEBAR_MAX);
+    }
+
+    pub fn grow_sidebar(&mut self) {
+        self.resize_sidebar(self.sidebar_width.saturating_add(SIDEBAR_STEP));
+    }
+
+    pub fn shrink_sidebar(&mut self) {
+        self.resize_sidebar(self.sidebar_width.saturating_sub(SIDEBAR_STEP));
+    }
+
+    /// Whether the file sidebar is on screen.
+    pub fn show_sidebar(&self) -> bool {
+        self.multi_file && !self.sidebar_hidden
+    }
+
+    pub fn toggle_sidebar(&mut self) {
+        if self.multi_file {
+            self.sidebar_hidden = !self.sidebar_hidden;
+        }
+    }
+
+    fn resize_sidebar(&mut self, percent: u16) {
+        if !self.show_sidebar() {
+            return;
+        }
+        self.set_sidebar_width(percent);
+        self.status_message = Some(format!("Sidebar width {}%", self.sidebar_width));
+    }
+
+    /// Appends a digit to the pending count. A leading zero is ignored.
+    pub fn push_count_digit(&mut self, digit: u32) {
+        let count = self.pending_count.unwrap_or(0);
+        if count == 0 && digit == 0 {
+            return;
+        }
+        self.pending_count = Some((count * 10 + digit as usize).min(MAX_COUNT));
+    }
+
+    /// Consumes the pending count, defaulting to 1.
+    pub fn take_count(&mut self) -> usize {
+        self.pending_count.take().unwrap_or(1)
+    }
+
+    /// Jumps to the 1-based row `line` of the current list, or scrolls
+    /// the detail view to that line. Rows past the end select the last.
+    pub fn go_to_line(&mut self, line: usize) {
+        let index = line.saturating_sub(1);
+        if self.view == View::TestDetail {
+            self.set_detail_scroll(index);
+            return;
+        }
+        let count = self.row_count();
+        if count > 0 {
+            self.set_selected_index(index.min(count - 1));
+        }
+    }
+
+    /// Selects the file at sidebar position `row`, where "All files"
+    /// (when present) is row 0.
+    pub fn select_sidebar(&mut self, row: usize) {
+        let Some(&entry) = self.sidebar_entries().get(row) else {
+            return;
+        };
+        if entry == self.current_entry() {
+            return;
+        }
+        match entry {
+            None => self.show_all = true,
+            Some(file) => {
+                self.show_all = false;
+                self.selected_file = file;
+            }
+        }
+        self.reset_selection();
+    }
+
+    pub fn start_sidebar_filter(&mut self) {
+        if self.show_sidebar() {
+            self.input_mode = InputMode::SidebarFilter;
+        }
+    }
+
+    pub fn sidebar_filter_push(&mut self, c: char) {
+        self.sidebar_filter.push(c);
+    }
+
+    pub fn sidebar_filter_pop(&mut self) {
+        self.sidebar_filter.pop();
+    }
+
+    /// Keeps the filter and, if it hides the current file, opens the first
+    /// file that matches.
+    pub fn confirm_sidebar_filter(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let entries = self.sidebar_entries();
+        if !entries.contains(&self.current_entry()) {
+            if let Some(row) = entries.iter().position(Option::is_some) {
+                self.select_sidebar(row);
+            }
+        }
+    }
+
+    pub fn clear_sidebar_filter(&mut self) {
+        self.sidebar_filter.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn toggle_watching(&mut self) {
+        self.watching = !self.watching;
+        self.status_message = Some(if self.watching {
+            "Watching reports for changes".into()
+        } else {
+            "Stopped watching".into()
+        });
+    }
+
+    /// `(suite, case)` indices of the slowest tests in the current file
+    /// that pass the status filter, slowest first.
+    pub fn slowest_rows(&self) -> Vec<(usize, usize)> {
+        let filter = self.test_filter();
+        let data = &self.current_file().data;
+        data.slowest(usize::MAX)
+            .into_iter()
+            .filter(|&(si, ti)| filter.shows(&data.suites[si], &data.suites[si].test_cases[ti]))
+            .take(SLOWEST_LIMIT)
+            .collect()
+    }
+
+    /// The test under the cursor in the slowest-tests view.
+    pub fn slowest_test(&self) -> Option<&TestCase> {
+        let (si, ti) = *self.slowest_rows().get(self.slowest_selected)?;
+        self.current_file().data.suites[si].test_cases.get(ti)
+    }
+
+    pub fn open_slowest(&mut self) {
+        self.slowest_selected = 0;
+        self.view = View::Slowest;
+        self.clear_search();
+    }
+
+    /// Opens the duration histogram on the selected suite.
+    pub fn open_durations(&mut self) {
+        self.durations_selected = self.selected_suite;
+        self.view = View::Durations;
+        self.clear_search();
+    }
+
+    /// Failed and errored tests in the current file grouped by message,
+    /// largest group first.
+    pub fn failure_groups(&self) -> Vec<FailureGroup> {
+        let data = &self.current_file().data;
+        if self.group_by_exception {
+            junit_parser::group_exceptions(data)
+        } else {
+            junit_parser::group_failures(data)
+        }
+    }
+
+    /// Rows of the failure groups view: every group, followed by its
+    /// members when expanded.
+    pub fn group_rows(&self) -> Vec<GroupRow> {
+        let mut rows = Vec::new();
+        for (gi, group) in self.failure_groups().into_iter().enumerate() {
+            rows.push(GroupRow::Group(gi));
+            if self.expanded_groups.contains(&group.signature) {
+                rows.extend(group.cases.iter().map(|&(suite, test)| GroupRow::Test {
+                    group: gi,
+                    suite,
+                    test,
+                }));
+            }
+        }
+        rows
+    }
+
+    /// The test under the cursor in the failure groups view.
+    pub fn group_test(&self) -> Option<&TestCase> {
+        match *self.group_rows().get(self.groups_selected)? {
+            GroupRow::Test { suite, test, .. } => {
+                self.current_file().data.suites[suite].test_cases.get(test)
+            }
+            GroupRow::Group(_) => None,
+        }
+    }
+
+    /// Opens the failure groups view, or in it switches between grouping
+    /// by message and by exception class.
+    pub fn open_groups(&mut self) {
+        if self.view == View::Groups {
+            self.group_by_exception = !self.group_by_exception;
+            self.expanded_groups.clear();
+        }
+        self.groups_selected = 0;
+        self.view = View::Groups;
+        self.clear_search();
+    }
+
+    /// Index of the group under the cursor, or of the group a member row
+    /// belongs to.
+    fn selected_group(&self) -> Option<usize> {
+        match *self.group_rows().get(self.groups_selected)? {
+            GroupRow::Group(group) | GroupRow::Test { group, .. } => Some(group),
+        }
+    }
+
+    fn selected_group_expanded(&self) -> bool {
+        let groups = self.failure_groups();
+        self.selected_group()
+            .is_some_and(|gi| self.expanded_groups.contains(&groups[gi].signature))
+    }
+
+    /// Expands or collapses the group under the cursor. On a member row the
+    /// group collapses and the cursor moves onto its header.
+    pub fn toggle_group(&mut self) {
+        let Some(gi) = self.selected_group() else {
+            return;
+        };
+        let signature = self.failure_groups().swap_remove(gi).signature;
+        if !self.expanded_groups.remove(&signature) {
+            self.expanded_groups.insert(signature);
+        }
+        self.groups_selected = self
+            .group_rows()
+            .iter()
+            .position(|&r| r == GroupRow::Group(gi))
+            .unwrap_or(0);
+    }
+
+    /// Skipped tests in the current file grouped by reason, largest group
+    /// first.
+    pub fn skip_groups(&self) -> Vec<SkipGroup> {
+        junit_parser::group_skipped(&self.current_file().data)
+    }
+
+    /// Rows of the skipped tests view: every reason, followed by its tests
+    /// when expanded.
+    pub fn skip_rows(&self) -> Vec<GroupRow> {
+        let mut rows = Vec::new();
+        for (gi, group) in self.skip_groups().into_iter().enumerate() {
+            rows.push(GroupRow::Group(gi));
+            if self.expanded_skips.contains(&group.reason) {
+                rows.extend(group.cases.iter().map(|&(suite, test)| GroupRow::Test {
+                    group: gi,
+                    suite,
+                    test,
+                }));
+            }
+        }
+        rows
+    }
+
+    /// The test under the cursor in the skipped tests view.
+    pub fn skip_test(&self) -> Option<&TestCase> {
+        match *self.skip_rows().get(self.skips_selected)? {
+            GroupRow::Test { suite, test, .. } => {
+                self.current_file().data.suites[suite].test_cases.get(test)
+            }
+            GroupRow::Group(_) => None,
+        }
+    }
+
+    pub fn open_skips(&mut self) {
+        self.skips_selected = 0;
+        self.view = View::Skips;
+        self.clear_search();
+    }
+
+    /// Index of the skip group under the cursor, or of the group a member
+    /// row belongs to.
+    fn selected_skip_group(&self) -> Option<usize> {
+        match *self.skip_rows().get(self.skips_selected)? {
+            GroupRow::Group(group) | GroupRow::Test { group, .. } => Some(group),
+        }
+    }
+
+    fn selected_skip_expanded(&self) -> bool {
+        let groups = self.skip_groups();
+        self.selected_skip_group()
+            .is_some_and(|gi| self.expanded_skips.contains(&groups[gi].reason))
+    }
+
+    /// Expands or collapses the skip group under the cursor. On a member
+    /// row the group collapses and the cursor moves onto its header.
+    pub fn toggle_skip_group(&mut self) {
+        let Some(gi) = self.selected_skip_group() else {
+            return;
+        };
+        let reason = self.skip_groups().swap_remove(gi).reason;
+        if !self.expanded_skips.remove(&reason) {
+            self.expanded_skips.insert(reason);
+        }
+        self.skips_selected = self
+            .skip_rows()
+            .iter()
+            .position(|&r| r == GroupRow::Group(gi))
+            .unwrap_or(0);
+    }
+
+    /// Rows of the package view: each package and class under the
+    /// report's package tree, with the contents of expanded ones. A class's
+    /// tests follow its nested nodes and honour the status filter.
+    pub fn package_rows(&self) -> Vec<PackageRow> {
+        let data = &self.current_file().data;
+        let filter = self.test_filter();
+        let mut tests: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        for (si, suite) in data.suites.iter().enumerate() {
+            for (ti, tc) in suite.test_cases.iter().enumerate() {
+                if !filter.shows(suite, tc) {
+                    continue;
+                }
+                let classname = tc.classname.as_deref().unwrap_or(&suite.name);
+                let path = junit_parser::split_classname(classname)
+                    .collect::<Vec<_>>()
+                    .join(".");
+                tests.entry(path).or_default().push((si, ti));
+            }
+        }
+
+        let mut rows = Vec::new();
+        self.push_package_rows(&data.package_tree(), 0, &tests, &mut rows);
+        rows
+    }
+
+    fn push_package_rows(
+        &self,
+        node: &PackageNode,
+        depth: usize,
+        tests: &HashMap<String, Vec<(usize, usize)>>,
+        rows: &mut Vec<PackageRow>,
+    ) {
+        for child in &node.children {
+            rows.push(PackageRow::Node {
+                path: child.path.clone(),
+                depth,
+            });
+            if self.expanded_packages.contains(&child.path) {
+                self.push_package_rows(child, depth + 1, tests, rows);
+                rows.extend(
+                    tests
+                        .get(&child.path)
+                        .into_iter()
+                        .flatten()
+                        .map(|&(suite, test)| PackageRow::Test {
+                            suite,
+                            test,
+                            depth: depth + 1,
+                        }),
+                );
+            }
+        }
+    }
+
+    /// The test under the cursor in the package view.
+    pub fn package_test(&self) -> Option<&TestCase> {
+        match *self.package_rows().get(self.packages_selected)? {
+            PackageRow::Test { suite, test, .. } => {
+                self.current_file().data.suites[suite].test_cases.get(test)
+            }
+            PackageRow::Node { .. } => None,
+        }
+    }
+
+    /// Opens the package view with the packages every test shares (such as
+    /// `com.example`) already expanded.
+    pub fn open_packages(&mut self) {
+        let tree = self.current_file().data.package_tree();
+        let mut node = &tree;
+        while let [child] = &node.children[..] {
+            self.expanded_packages.insert(child.path.clone());
+            node = child;
+        }
+        self.packages_selected = 0;
+        self.view = View::Packages;
+        self.clear_search();
+    }
+
+    /// The node `h` collapses in the package view.
+    fn package_to_collapse(&self) -> Option<String> {
+        let rows = self.package_rows();
+        let depth = match rows.get(self.packages_selected)? {
+            PackageRow::Node { path, .. } if self.expanded_packages.contains(path) => {
+                return Some(path.clone());
+            }
+            PackageRow::Node { depth, .. } | PackageRow::Test { depth, .. } => *depth,
+        };
+        rows[..self.packages_selected]
+            .iter()
+            .rev()
+            .find_map(|row| match row {
+                PackageRow::Node { path, depth: d } if *d + 1 == depth => Some(path.clone()),
+                _ => None,
+            })
+    }
+
+    /// Expands the collapsed node under the cursor, or collapses the one
+    /// [`collapse_package`](Self::collapse_package) would.
+    pub fn toggle_package(&mut self) {
+        match self.package_rows().get(self.packages_selected) {
+            Some(PackageRow::Node { path, .. }) if !self.expanded_packages.contains(path) => {
+                self.expanded_packages.insert(path.clone());
+            }
+            _ => self.collapse_package(),
+        }
+    }
+
+    /// Collapses the node under the cursor, or the parent of a test or of a
+    /// collapsed node, and moves the cursor onto it.
+    pub fn collapse_package(&mut self) {
+        let Some(path) = self.package_to_collapse() else {
+            return;
+        };
+        self.expanded_packages.remove(&path);
+        self.packages_selected = self
+            .package_rows()
+            .iter()
+            .position(|row| matches!(row, PackageRow::Node { path: p, .. } if *p == path))
+            .unwrap_or(0);
+    }
+
+    pub fn open_history(&mut self) {
+        if self.files.len() < 2 {
+            self.status_message = Some("History needs a directory with several runs".into());
+            return;
+        }
+        self.scroll_offset = 0;
+        self.view = View::History;
+        self.clear_search();
+    }
+
+    /// Marks or unmarks the current file for comparison. Marking a third
+    /// file drops the oldest mark.
+    pub fn toggle_mark(&mut self) {
+        if !self.multi_file || self.show_all {
+            self.status_message = Some("Select a single file to mark it".into());
+            return;
+        }
+        let name = self.current_file().filename.clone();
+        match self.marked.iter().position(|m| *m == name) {
+            Some(i) => {
+                self.marked.remove(i);
+            }
+            None => {
+                self.marked.push(name);
+                if self.marked.len() > 2 {
+                    self.marked.remove(0);
+                }
+            }
+        }
+    }
+
+    /// Makes the current file the baseline that the new failures filter
+    /// compares against, or clears it if it already is.
+    pub fn toggle_baseline(&mut self) {
+        if self.show_all {
+            self.status_message = Some("Select a single file to use it as the baseline".into());
+            return;
+        }
+        let name = self.current_file().filename.clone();
+        if self.baseline.as_ref().is_some_and(|b| b.name == name) {
+            self.clear_baseline();
+            self.status_message = Some("Baseline cleared".into());
+            return;
+        }
+        let baseline = Baseline::new(name.clone(), &self.current_file().data);
+        self.set_baseline(baseline);
+        self.status_message = Some(format!("Baseline: {} — f filters to new failures", name));
+    }
+
+    pub fn set_baseline(&mut self, baseline: Baseline) {
+        self.refilter(|app| app.baseline = Some(baseline));
+    }
+
+    fn clear_baseline(&mut self) {
+        self.refilter(|app| {
+            app.baseline = None;
+            if app.status_filter == StatusFilter::NewFailures {
+                app.status_filter = StatusFilter::Failing;
+            }
+        });
+    }
+
+    /// Compares the two marked files, or the marked file against the
+    /// current one, oldest mark as the base.
+    pub fn open_diff(&mut self) {
+        let current = (!self.show_all).then(|| self.current_file().filename.clone());
+        let (base, head) = match (self.marked.as_slice(), current) {
+            ([base, head], _) => (base.clone(), head.clone()),
+            ([base], Some(current)) if *base != current => (base.clone(), current),
+            _ => {
+                self.status_message = Some("Mark two files with m to compare them".into());
+                return;
+            }
+        };
+        self.diff = Some(FileDiff {
+            base,
+            head,
+            diff: ReportDiff::default(),
+        });
+        if self.refresh_diff() {
+            self.scroll_offset = 0;
+            self.view = View::Diff;
+            self.clear_search();
+        }
+    }
+
+    /// Recomputes the open diff from the current file contents. Returns
+    /// false (and closes the diff) if either file is gone.
+    fn refresh_diff(&mut self) -> bool {
+        let Some(diff) = self.diff.as_mut() else {
+            return false;
+        };
+        let find = |name: &str| self.files.iter().find(|f| f.filename == name);
+        match (find(&diff.base), find(&diff.head)) {
+            (Some(base), Some(head)) => {
+                diff.diff = junit_parser::diff(&base.data, &head.data);
+                true
+            }
+            _ => {
+                self.diff = None;
+                if self.view == View::Diff {
+                    self.view = self.top_view();
+                }
+                false
+            }
+        }
+    }
+
+    /// Replaces the report loaded from the same path, or adds it in sorted
+    /// position, keeping the current file, suite and test selected.
+    pub fn update_file(&mut self, report: FileReport) {
+        let selection = self.selection();
+        let view = self.view;
+        match self.files.iter().position(|f| f.is_at(&report.path)) {
+            Some(i) => self.files[i] = report,
+            None => {
+                let i = self.files.partition_point(|f| f.filename < report.filename);
+                self.files.insert(i, report);
+            }
+        }
+        self.rebuild_all_files();
+        self.clear_picks();
+        self.raw_source.take();
+        self.restore_selection(selection);
+        self.refresh_open_diff(view);
+    }
+
+    /// Drops the report loaded from `path`, such as one that disappeared
+    /// from disk. The last file is kept so there is always something to
+    /// show.
+    pub fn remove_file(&mut self, path: &Path) {
+        if self.files.len() <= 1 {
+            return;
+        }
+        let Some(i) = self.files.iter().position(|f| f.is_at(path)) else {
+            return;
+        };
+        let selection = self.selection();
+        let view = self.view;
+        let removed = self.files.remove(i);
+        self.marked.retain(|m| *m != removed.filename);
+        self.rebuild_all_files();
+        self.clear_picks();
+        self.restore_selection(selection);
+        self.refresh_open_diff(view);
+    }
+
+    /// Drops the open file from this session, e.g. a stray or duplicate
+    /// report that would skew the totals. It stays out on reloads.
+    pub fn drop_current_file(&mut self) {
+        if self.show_all {
+            self.status_message = Some("Open a single file to remove it".into());
+            return;
         }
+        if self.files.len() <= 1 {
+            self.status_message = Some("Can't remove the last file".into());
+            return;
+        }
+        let filename = self.current_file().filename.clone();
+        let path = self.current_file().path.clone();
+        self.dropped.insert(path.clone());
+        self.remove_file(&path);
+        self.status_message = Some(format!("Removed {} from this session", filename));
     }
 
-    pub fn current_file(&self) -> &FileReport {
-        &self.files[self.selected_file]
+    /// Keeps the diff view open and up to date across a reload.
+    fn refresh_open_diff(&mut self, view: View) {
+        if view == View::Diff && self.refresh_diff() {
+            self.view = View::Diff;
+        }
     }
 
-    pub fn suite_count(&self) -> usize {
-        self.current_file().data.suites.len()
+    /// Where the user is, for restoring on the next launch.
+    pub fn position(&self) -> Position {
+        let selection = self.selection();
+        // In the tree the cursor row, not the list selection, is where the
+        // user is.
+        let (suite, test, view) = match (self.view, selection.tree_row) {
+            (View::Tree, Some((suite, Some(test)))) => (Some(suite), Some(test), SavedView::Tests),
+            (View::Tree, Some((suite, None))) => (Some(suite), None, SavedView::Suites),
+            (View::TestDetail, _) => (selection.suite, selection.test, SavedView::Detail),
+            (View::TestList, _) => (selection.suite, selection.test, SavedView::Tests),
+            _ => (selection.suite, selection.test, SavedView::Suites),
+        };
+        let (test, classname) = test.unzip();
+        Position {
+            file: selection.file,
+            suite,
+            test,
+            classname: classname.flatten(),
+            view,
+            tree: self.tree_mode,
+        }
     }
 
-    pub fn test_count(&self) -> usize {
-        if self.selected_suite < self.suite_count() {
-            self.current_file().data.suites[self.selected_suite]
-                .test_cases
-                .len()
-        } else {
-            0
+    /// Reopens a saved [`Position`], falling back to the nearest thing
+    /// that still exists in the reports.
+    pub fn restore_position(&mut self, position: &Position) {
+        self.show_all = position.file.is_none() && self.all_files.is_some();
+        self.tree_mode = position.tree;
+        let test = position
+            .test
+            .clone()
+            .map(|name| (name, position.classname.clone()));
+        self.view = match position.view {
+            SavedView::Detail => View::TestDetail,
+            SavedView::Tests if !self.tree_mode => View::TestList,
+            SavedView::Tests | SavedView::Suites => self.top_view(),
+        };
+        let tree_row = position.suite.clone().map(|suite| {
+            (
+                suite,
+                test.clone().filter(|_| position.view != SavedView::Suites),
+            )
+        });
+        self.restore_selection(Selection {
+            file: position.file.clone(),
+            suite: position.suite.clone(),
+            test,
+            expanded: position
+                .suite
+                .iter()
+                .filter(|_| self.tree_mode && position.view != SavedView::Suites)
+                .cloned()
+                .collect(),
+            tree_row,
+        });
+    }
+
+    fn selection(&self) -> Selection {
+        let suites = &self.current_file().data.suites;
+        let suite_name = |si: usize| suites[si].name.clone();
+        let test_key = |si: usize, ti: usize| {
+            let tc = &suites[si].test_cases[ti];
+            (tc.name.clone(), tc.classname.clone())
+        };
+        Selection {
+            file: (!self.show_all).then(|| self.current_file().filename.clone()),
+            suite: self.current_suite_index().map(suite_name),
+            test: self
+                .current_suite_index()
+                .zip(self.test_near_cursor())
+                .map(|(si, ti)| test_key(si, ti)),
+            expanded: self.expanded.iter().map(|&si| suite_name(si)).collect(),
+            tree_row: self
+                .tree_rows()
+                .get(self.tree_selected)
+                .map(|&row| match row {
+                    TreeRow::Suite(si) => (suite_name(si), None),
+                    TreeRow::Test(si, ti) => (suite_name(si), Some(test_key(si, ti))),
+                }),
         }
     }
 
-    pub fn select_next(&mut self) {
-        match self.view {
-            View::SuiteList => {
-                let count = self.suite_count();
-                if count > 0 && self.selected_suite < count - 1 {
-                    self.selected_suite += 1;
+    /// Re-resolves a [`Selection`] by name after the reports changed,
+    /// falling back to the nearest thing that still exists.
+    fn restore_selection(&mut self, selection: Selection) {
+        self.multi_file = self.files.len() > 1;
+        let found = match &selection.file {
+            // Cleared by `rebuild_all_files` if only one file is left.
+            None => self.show_all,
+            Some(name) => match self.files.iter().position(|f| &f.filename == name) {
+                Some(i) => {
+                    self.selected_file = i;
+                    true
                 }
+                None => false,
+            },
+        };
+        if !found {
+            self.selected_file = self.selected_file.min(self.files.len() - 1);
+            self.reset_selection();
+            return;
+        }
+
+        let suites = &self.current_file().data.suites;
+        let find_suite = |name: &str| suites.iter().position(|s| s.name == name);
+        let find_test = |si: usize, key: &TestKey| {
+            suites[si]
+                .test_cases
+                .iter()
+                .position(|tc| tc.name == key.0 && tc.classname == key.1)
+        };
+        let suite = selection.suite.as_deref().and_then(find_suite);
+        let test = suite
+            .zip(selection.test.as_ref())
+            .and_then(|(si, key)| find_test(si, key));
+        let expanded = selection
+            .expanded
+            .iter()
+            .filter_map(|name| find_suite(name))
+            .collect();
+        let tree_row = selection.tree_row.as_ref().and_then(|(name, test)| {
+            let si = find_suite(name)?;
+            Some(match test.as_ref().and_then(|key| find_test(si, key)) {
+                Some(ti) => TreeRow::Test(si, ti),
+                None => TreeRow::Suite(si),
+            })
+        });
+        self.expanded = expanded;
+
+        match suite.and_then(|s| self.visible_suites().iter().position(|&i| i == s)) {
+            Some(position) => self.selected_suite = position,
+            None => {
+                self.selected_suite = self
+                    .selected_suite
+                    .min(self.suite_count().saturating_sub(1));
+                self.view = self.top_view();
             }
-            View::TestList => {
-                let count = self.test_count();
-                if count > 0 && self.selected_test < count - 1 {
-                    self.selected_test += 1;
+        }
+        match test.and_then(|t| self.test_row_position(t).map(|p| (t, p))) {
+            // The open test may have just become one of several cases.
+            Some((test, _)) if self.view == View::TestDetail => self.select_test(test),
+            Some((_, position)) => self.selected_test = position,
+            None => {
+                self.selected_test = self.selected_test.min(self.test_count().saturating_sub(1));
+                if self.view == View::TestDetail {
+                    self.view = View::TestList;
+                    self.reset_detail_scroll();
                 }
             }
-            View::TestDetail => {
-                self.scroll_offset = self.scroll_offset.saturating_add(1);
-            }
         }
+        self.tree_selected = self.tree_position_near(tree_row);
     }
 
-    pub fn select_prev(&mut self) {
+    pub fn page_down(&mut self) {
+        for _ in 0..10 {
+            self.select_next();
+        }
+    }
+
+    pub fn page_up(&mut self) {
+        for _ in 0..10 {
+            self.select_prev();
+        }
+    }
+
+    fn reset_selection(&mut self) {
+        self.selected_suite = 0;
+        self.selected_test = 0;
+        self.reset_detail_scroll();
+        self.tree_selected = 0;
+        self.slowest_selected = 0;
+        self.groups_selected = 0;
+        self.expanded_groups.clear();
+        self.skips_selected = 0;
+        self.expanded_skips.clear();
+        self.expanded_params.clear();
+        self.packages_selected = 0;
+        self.expanded_packages.clear();
+        self.return_view = None;
+        self.expanded.clear();
+        self.clear_picks();
+        self.view = self.top_view();
+        self.clear_search();
+    }
+
+    pub fn selected_row(&self) -> usize {
+        self.selected_index()
+    }
+
+    pub fn select_row(&mut self, index: usize) {
+        self.set_selected_index(index);
+    }
+
+    /// Number of rows in the current list view.
+    pub fn row_count(&self) -> usize {
         match self.view {
-            View::SuiteList => {
-                self.selected_suite = self.selected_suite.saturating_sub(1);
-            }
-            View::TestList => {
-                self.selected_test = self.selected_test.saturating_sub(1);
-            }
-            View::TestDetail => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(1);
-            }
+            View::SuiteList => self.suite_count(),
+            View::TestList => self.test_count(),
+            View::Tree => self.tree_rows().len(),
+            View::Slowest => self.slowest_rows().len(),
+            View::Groups => self.group_rows().len(),
+            View::Skips => self.skip_rows().len(),
+            View::Packages => self.package_rows().len(),
+            View::Durations => self.suite_count(),
+            View::TestDetail | View::Diff | View::History => 0,
         }
     }
 
-    pub fn select_first(&mut self) {
+    fn selected_index(&self) -> usize {
         match self.view {
-            View::SuiteList => self.selected_suite = 0,
-            View::TestList => self.selected_test = 0,
-            View::TestDetail => self.scroll_offset = 0,
+            View::SuiteList => self.selected_suite,
+            View::TestList => self.selected_test,
+            View::Tree => self.tree_selected,
+            View::Slowest => self.slowest_selected,
+            View::Groups => self.groups_selected,
+            View::Skips => self.skips_selected,
+            View::Packages => self.packages_selected,
+            View::Durations => self.durations_selected,
+            View::TestDetail | View::Diff | View::History => 0,
         }
     }
 
-    pub fn select_last(&mut self) {
+    fn set_selected_index(&mut self, index: usize) {
         match self.view {
-            View::SuiteList => {
-                let count = self.suite_count();
-                if count > 0 {
-                    self.selected_suite = count - 1;
-                }
+            View::SuiteList => self.selected_suite = index,
+            View::TestList => self.selected_test = index,
+            View::Tree => self.tree_selected = index,
+            View::Slowest => self.slowest_selected = index,
+            View::Groups => self.groups_selected = index,
+            View::Skips => self.skips_selected = index,
+            View::Packages => self.packages_selected = index,
+            View::Durations => self.durations_selected = index,
+            View::TestDetail | View::Diff | View::History => {}
+        }
+    }
+
+    /// Labels of the rows in the current list view, in display order.
+    fn row_labels(&self) -> Vec<Cow<'_, str>> {
+        match self.view {
+            View::SuiteList | View::Durations => {
+                let suites = &self.current_file().data.suites;
+                self.visible_suites()
+                    .into_iter()
+                    .map(|i| Cow::from(suites[i].name.as_str()))
+                    .collect()
             }
-            View::TestList => {
-                let count = self.test_count();
-                if count > 0 {
-                    self.selected_test = count - 1;
+            View::TestList => match self.current_suite() {
+                Some(suite) => {
+                    let groups = self.param_groups();
+                    self.test_rows()
+                        .into_iter()
+                        .map(|row| match row {
+                            TestRow::Params(gi) => Cow::from(groups[gi].name.clone()),
+                            TestRow::Test { test, .. } => {
+                                Cow::from(suite.test_cases[test].name.as_str())
+                            }
+                        })
+                        .collect()
                 }
+                None => Vec::new(),
+            },
+            View::Tree => {
+                let suites = &self.current_file().data.suites;
+                self.tree_rows()
+                    .into_iter()
+                    .map(|row| match row {
+                        TreeRow::Suite(si) => suites[si].name.as_str().into(),
+                        TreeRow::Test(si, ti) => suites[si].test_cases[ti].name.as_str().into(),
+                    })
+                    .collect()
             }
-            View::TestDetail => {
-                self.scroll_offset = u16::MAX / 2;
+            View::Slowest => {
+                let suites = &self.current_file().data.suites;
+                self.slowest_rows()
+                    .into_iter()
+                    .map(|(si, ti)| Cow::from(suites[si].test_cases[ti].name.as_str()))
+                    .collect()
+            }
+            View::Groups => {
+                let suites = &self.current_file().data.suites;
+                let groups = self.failure_groups();
+                self.group_rows()
+                    .into_iter()
+                    .map(|row| match row {
+                        GroupRow::Group(gi) => groups[gi].signature.clone().into(),
+                        GroupRow::Test { suite, test, .. } => {
+                            suites[suite].test_cases[test].name.as_str().into()
+                        }
+                    })
+                    .collect()
+            }
+            View::Skips => {
+                let suites = &self.current_file().data.suites;
+                let groups = self.skip_groups();
+                self.skip_rows()
+                    .into_iter()
+                    .map(|row| match row {
+                        GroupRow::Group(gi) => groups[gi].reason.clone().into(),
+                        GroupRow::Test { suite, test, .. } => {
+                            suites[suite].test_cases[test].name.as_str().into()
+                        }
+                    })
+                    .collect()
             }
+            View::Packages => {
+                let suites = &self.current_file().data.suites;
+                self.package_rows()
+                    .into_iter()
+                    .map(|row| match row {
+                        PackageRow::Node { path, .. } => {
+                            Cow::from(path.rsplit('.').next().unwrap_or_default().to_string())
+                        }
+                        PackageRow::Test { suite, test, .. } => {
+                            suites[suite].test_cases[test].name.as_str().into()
+                        }
+                    })
+                    .collect()
+            }
+            View::TestDetail | View::Diff | View::History => Vec::new(),
         }
     }
 
-    pub fn enter(&mut self) {
+    pub fn start_search(&mut self) {
+        if self.view != View::TestDetail {
+            self.search_query.clear();
+            self.input_mode = InputMode::Search;
+        }
+    }
+
+    pub fn search_push(&mut self, c: char) {
+        self.search_query.push(c);
+        self.jump_to_match(self.selected_index(), true);
+    }
+
+    pub fn search_pop(&mut self) {
+        self.search_query.pop();
+        self.jump_to_match(self.selected_index(), true);
+    }
+
+    pub fn confirm_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn has_search(&self) -> bool {
+        !self.search_query.is_empty()
+    }
+
+    pub fn matches_search(&self, text: &str) -> bool {
+        search::find_ignore_case(text, &self.search_query).is_some()
+    }
+
+    /// Row positions in the current list view matching the search query.
+    pub fn search_matches(&self) -> Vec<usize> {
+        self.row_labels()
+            .iter()
+            .enumerate()
+            .filter(|(_, label)| self.matches_search(label))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Index of the selected row within the match list, if it is a match.
+    pub fn current_match_position(&self) -> Option<usize> {
+        let selected = self.selected_index();
+        self.search_matches().iter().position(|&i| i == selected)
+    }
+
+    pub fn next_match(&mut self) {
+        self.jump_to_match(self.selected_index() + 1, true);
+    }
+
+    pub fn prev_match(&mut self) {
+        self.jump_to_match(self.selected_index(), false);
+    }
+
+    /// Moves the selection to the nearest match at or after `from` (or
+    /// strictly before it when searching backwards), wrapping around.
+    fn jump_to_match(&mut self, from: usize, forward: bool) {
+        let matches = self.search_matches();
+        let target = if forward {
+            matches
+                .iter()
+                .find(|&&i| i >= from)
+                .or_else(|| matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&i| i < from)
+                .or_else(|| matches.last())
+        };
+        if let Some(&index) = target {
+            self.set_selected_index(index);
+        }
+    }
+
+    pub fn open_help(&mut self) {
+        self.help_scroll = 0;
+        self.input_mode = InputMode::Help;
+    }
+
+    pub fn close_help(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// The suite the `p` and `O` popups describe: the selected suite in
+    /// the lists, or the suite under the cursor in the tree.
+    pub fn properties_suite(&self) -> Option<&TestSuite> {
         match self.view {
-            View::SuiteList => {
-                if self.suite_count() > 0 {
-                    self.selected_test = 0;
-                    self.view = View::TestList;
-                }
-            }
-            View::TestList => {
-                if self.test_count() > 0 {
-                    self.scroll_offset = 0;
-                    self.view = View::TestDetail;
+            View::SuiteList | View::TestList => self.current_suite(),
+            View::Tree => match *self.tree_rows().get(self.tree_selected)? {
+                TreeRow::Suite(si) | TreeRow::Test(si, _) => {
+                    self.current_file().data.suites.get(si)
                 }
-            }
-            View::TestDetail => {}
+            },
+            _ => None,
         }
     }
 
-    pub fn go_back(&mut self) {
-        match self.view {
-            View::SuiteList => {}
-            View::TestList => {
-                self.view = View::SuiteList;
-            }
-            View::TestDetail => {
-                self.view = View::TestList;
-            }
+    pub fn open_properties(&mut self) {
+        if self.properties_suite().is_none() {
+            return;
         }
+        self.properties_scroll = 0;
+        self.input_mode = InputMode::Properties;
     }
 
-    pub fn next_file(&mut self) {
-        if self.multi_file {
-            self.selected_file = (self.selected_file + 1) % self.files.len();
-            self.reset_selection();
+    pub fn close_properties(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn open_suite_output(&mut self) {
+        if self.properties_suite().is_none() {
+            return;
         }
+        self.suite_output_scroll = 0;
+        self.input_mode = InputMode::SuiteOutput;
     }
 
-    pub fn prev_file(&mut self) {
-        if self.multi_file {
-            if self.selected_file == 0 {
-                self.selected_file = self.files.len() - 1;
-            } else {
-                self.selected_file -= 1;
-            }
-            self.reset_selection();
+    pub fn close_suite_output(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Compares the selected suite across the loaded files, when more than
+    /// one of them has a suite of that name.
+    pub fn open_suite_compare(&mut self) {
+        let Some(name) = self.properties_suite().map(|s| s.name.clone()) else {
+            return;
+        };
+        let found = self
+            .files
+            .iter()
+            .filter(|f| f.data.suites.iter().any(|s| s.name == name))
+            .count();
+        if found < 2 {
+            self.status_message = Some(format!("{} only appears in one file", name));
+            return;
         }
+        self.compared_suite = Some(name);
+        self.input_mode = InputMode::SuiteCompare;
     }
 
-    pub fn page_down(&mut self) {
-        for _ in 0..10 {
-            self.select_next();
+    pub fn close_suite_compare(&mut self) {
+        self.compared_suite = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn open_copy_id(&mut self) {
+        if self.cursor_test().is_some() {
+            self.input_mode = InputMode::CopyId;
         }
     }
 
-    pub fn page_up(&mut self) {
-        for _ in 0..10 {
-            self.select_prev();
+    /// Moves the copy popup's highlight to the next or previous format.
+    pub fn step_id_format(&mut self, forward: bool) {
+        let formats = IdFormat::ALL;
+        let index = formats
+            .iter()
+            .position(|&f| f == self.id_format)
+            .unwrap_or(0);
+        if let Some(next) = step(index, formats.len(), forward) {
+            self.id_format = formats[next];
         }
     }
 
-    fn reset_selection(&mut self) {
-        self.selected_suite = 0;
-        self.selected_test = 0;
-        self.scroll_offset = 0;
-        self.view = View::SuiteList;
+    pub fn open_suite_stats(&mut self) {
+        if self.view == View::SuiteList && self.current_suite().is_some() {
+            self.input_mode = InputMode::SuiteStats;
+        }
+    }
+
+    pub fn close_suite_stats(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn open_finder(&mut self) {
+        self.finder = Finder::default();
+        self.input_mode = InputMode::Finder;
+    }
+
+    pub fn close_finder(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn finder_push(&mut self, c: char) {
+        self.finder.query.push(c);
+        self.finder.selected = 0;
+    }
+
+    pub fn finder_pop(&mut self) {
+        self.finder.query.pop();
+        self.finder.selected = 0;
+    }
+
+    pub fn finder_next(&mut self) {
+        let count = self.finder_results().len();
+        if count > 0 && self.finder.selected < count - 1 {
+            self.finder.selected += 1;
+        }
+    }
+
+    pub fn finder_prev(&mut self) {
+        self.finder.selected = self.finder.selected.saturating_sub(1);
+    }
+
+    /// The text a test case is matched against: its file (in multi-file
+    /// mode), suite and name.
+    pub fn finder_label(&self, file: usize, suite: usize, test: usize) -> String {
+        let report = &self.files[file];
+        let s = &report.data.suites[suite];
+        let tc = &s.test_cases[test];
+        if self.multi_file {
+            format!("{} › {} › {}", report.filename, s.name, tc.name)
+        } else {
+            format!("{} › {}", s.name, tc.name)
+        }
+    }
+
+    /// Every test case across all files matching the finder query, best
+    /// match first. An empty query lists tests in report order.
+    pub fn finder_results(&self) -> Vec<FinderHit> {
+        let mut hits = Vec::new();
+        for (fi, file) in self.files.iter().enumerate() {
+            for (si, suite) in file.data.suites.iter().enumerate() {
+                for ti in 0..suite.test_cases.len() {
+                    let label = self.finder_label(fi, si, ti);
+                    if let Some(score) = search::fuzzy_score(&label, &self.finder.query) {
+                        hits.push(FinderHit {
+                            file: fi,
+                            suite: si,
+                            test: ti,
+                            score,
+                        });
+                    }
+                }
+            }
+        }
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+        hits.truncate(FINDER_LIMIT);
+        hits
+    }
+
+    /// Jumps to the detail view of the selected finder result.
+    pub fn finder_accept(&mut self) {
+        let results = self.finder_results();
+        if let Some(hit) = results.get(self.finder.selected) {
+            self.select_test_at(hit.file, hit.suite, hit.test);
+            self.reset_detail_scroll();
+            self.view = View::TestDetail;
+            self.search_query.clear();
+        }
+        self.close_finder();
     }
 
     pub fn aggregate_tests(&self) -> u64 {
@@ -194,14 +3529,322 @@ impl App {
     }
 
     pub fn aggregate_failures(&self) -> u64 {
-        self.files.iter().map(|f| f.data.total_failures()).sum()
+        let total: u64 = self.files.iter().map(|f| f.data.total_failures()).sum();
+        total.saturating_sub(self.uncounted_quarantined(TestStatus::Failed))
     }
 
     pub fn aggregate_errors(&self) -> u64 {
-        self.files.iter().map(|f| f.data.total_errors()).sum()
+        let total: u64 = self.files.iter().map(|f| f.data.total_errors()).sum();
+        total.saturating_sub(self.uncounted_quarantined(TestStatus::Errored))
+    }
+
+    /// Quarantined failures and errors across every file.
+    pub fn aggregate_quarantined(&self) -> u64 {
+        self.files
+            .iter()
+            .flat_map(|f| &f.data.suites)
+            .flat_map(|suite| &suite.test_cases)
+            .filter(|tc| self.quarantine.contains(tc))
+            .count() as u64
+    }
+
+    /// Quarantined tests with `status` to leave out of the headline counts.
+    fn uncounted_quarantined(&self, status: TestStatus) -> u64 {
+        if self.count_quarantined {
+            return 0;
+        }
+        self.files
+            .iter()
+            .flat_map(|f| &f.data.suites)
+            .flat_map(|suite| &suite.test_cases)
+            .filter(|tc| tc.status() == status && self.quarantine.contains(tc))
+            .count() as u64
     }
 
     pub fn aggregate_skipped(&self) -> u64 {
         self.files.iter().map(|f| f.data.total_skipped()).sum()
     }
 }
+
+/// The index after (or before) `index` in a list of `len`, if any.
+fn step(index: usize, len: usize, forward: bool) -> Option<usize> {
+    if forward {
+        (index + 1 < len).then_some(index + 1)
+    } else {
+        index.checked_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIXED: &str = r#"<testsuite name="s" tests="3" failures="1">
+    <testcase classname="C" name="first"/>
+    <testcase classname="C" name="second">
+        <failure message="boom">trace</failure>
+    </testcase>
+    <testcase classname="C" name="third"/>
+</testsuite>"#;
+
+    fn app(files: Vec<FileReport>) -> App {
+        let keymap = KeyMap::new(&Default::default()).unwrap();
+        App::new(files, Theme::no_color(), keymap)
+    }
+
+    fn prefiltered(mut report: FileReport, prefilter: Prefilter) -> App {
+        prefilter.apply(&mut report.data);
+        app(vec![report])
+    }
+
+    #[test]
+    fn raw_xml_of_prefiltered_piped_report() {
+        let report = FileReport::from_xml("r.xml", MIXED);
+        let only_failed = Prefilter {
+            only_failed: true,
+            ..Default::default()
+        };
+        let app = prefiltered(report, only_failed);
+        assert_eq!(app.current_test().unwrap().name, "second");
+        let xml = app.raw_xml().unwrap();
+        assert!(xml.starts_with(r#"<testcase classname="C" name="second">"#));
+        assert!(xml.ends_with("</testcase>"));
+    }
+
+    #[test]
+    fn raw_xml_of_prefiltered_file() {
+        let dir = std::env::temp_dir().join(format!("ratunit-raw-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("results.xml");
+        std::fs::write(&path, MIXED).unwrap();
+        let report = FileReport::load(&path).unwrap().remove(0);
+        let exclude = Prefilter {
+            exclude: vec!["C.first".into()],
+            ..Default::default()
+        };
+        let mut app = prefiltered(report, exclude);
+        app.select_test_at(0, 0, 1);
+        assert_eq!(app.current_test().unwrap().name, "third");
+        let xml = app.raw_xml();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(xml.unwrap(), r#"<testcase classname="C" name="third"/>"#);
+    }
+
+    #[test]
+    fn sorts_files_by_recorded_modification_time() {
+        let dir = std::env::temp_dir().join(format!("ratunit-mtime-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        for (name, age) in [("a.xml", 60), ("b.xml", 0), ("c.xml", 30)] {
+            let path = dir.join(name);
+            std::fs::write(&path, MIXED).unwrap();
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(now - std::time::Duration::from_secs(age))
+                .unwrap();
+        }
+        let mut app = app(FileReport::load(&dir).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        app.file_sort = FileSort::Modified;
+        let order: Vec<&str> = app
+            .sidebar_entries()
+            .into_iter()
+            .map(|entry| app.entry_file(entry).filename.as_str())
+            .collect();
+        assert_eq!(order, [ALL_FILES, "b.xml", "c.xml", "a.xml"]);
+    }
+
+    #[test]
+    fn last_row_of_history_is_its_furthest_scroll() {
+        let mut app = app(vec![FileReport::from_xml("r.xml", MIXED)]);
+        app.view = View::History;
+        app.set_max_scroll(DetailPane::Main, 7);
+        app.select_last();
+        assert_eq!(app.scroll_offset, 7);
+        app.select_next();
+        assert_eq!(app.scroll_offset, 7);
+        app.select_prev();
+        assert_eq!(app.scroll_offset, 6);
+    }
+
+    const SUITES: &str = r#"<testsuites>
+    <testsuite name="com.example.Login" tests="4" failures="1" errors="1" skipped="1">
+        <testcase classname="com.example.Login" name="accepts" time="0.1"/>
+        <testcase classname="com.example.Login" name="rejects" time="3">
+            <failure message="expected 401"/>
+        </testcase>
+        <testcase classname="com.example.Login" name="expires"><skipped/></testcase>
+        <testcase classname="com.example.Login" name="locks_out" time="1">
+            <error message="timeout"/>
+        </testcase>
+    </testsuite>
+    <testsuite name="com.example.Sum" tests="3">
+        <testcase classname="com.example.Sum" name="adds[1]"/>
+        <testcase classname="com.example.Sum" name="adds[2]"/>
+        <testcase classname="com.example.Sum" name="carries"/>
+    </testsuite>
+</testsuites>"#;
+
+    fn app_from(xml: &str) -> App {
+        app(vec![FileReport::from_xml("r.xml", xml)])
+    }
+
+    /// The app open on the test list of `SUITES`' first suite.
+    fn test_list() -> App {
+        let mut app = app_from(SUITES);
+        app.enter();
+        assert_eq!(app.view, View::TestList);
+        app
+    }
+
+    /// Names of the test list's rows; a parameterized header shows as its
+    /// base name.
+    fn rows(app: &App) -> Vec<String> {
+        let suite = app.current_suite().unwrap();
+        let groups = app.param_groups();
+        app.test_rows()
+            .into_iter()
+            .map(|row| match row {
+                TestRow::Test { test, .. } => suite.test_cases[test].name.clone(),
+                TestRow::Params(gi) => format!("{}[…]", groups[gi].name),
+            })
+            .collect()
+    }
+
+    fn current(app: &App) -> &str {
+        &app.current_test().unwrap().name
+    }
+
+    #[test]
+    fn status_filter_keeps_the_selected_test() {
+        let mut app = test_list();
+        app.select_next();
+        assert_eq!(current(&app), "rejects");
+
+        app.cycle_status_filter();
+        assert_eq!(app.status_filter, StatusFilter::Failing);
+        assert_eq!(rows(&app), ["rejects", "locks_out"]);
+        assert_eq!(current(&app), "rejects");
+
+        app.select_tab(4);
+        assert_eq!(app.status_filter, StatusFilter::Passed);
+        assert_eq!(rows(&app), ["accepts"]);
+        assert_eq!(current(&app), "accepts");
+    }
+
+    #[test]
+    fn hiding_passed_tests() {
+        let mut app = test_list();
+        app.toggle_hide_passed();
+        assert_eq!(rows(&app), ["rejects", "expires", "locks_out"]);
+    }
+
+    #[test]
+    fn sorting_keeps_the_selected_test() {
+        let mut app = test_list();
+        app.select_last();
+        assert_eq!(current(&app), "locks_out");
+
+        app.sort_tests_by(TestSort::Duration);
+        assert_eq!(rows(&app), ["rejects", "locks_out", "accepts", "expires"]);
+        assert_eq!(current(&app), "locks_out");
+
+        app.sort_tests_by(TestSort::Name);
+        assert_eq!(rows(&app), ["accepts", "expires", "locks_out", "rejects"]);
+        assert_eq!(current(&app), "locks_out");
+
+        // Choosing the current order again goes back to the report's.
+        app.sort_tests_by(TestSort::Name);
+        assert_eq!(app.test_sort, TestSort::Report);
+        assert_eq!(current(&app), "locks_out");
+    }
+
+    #[test]
+    fn search_jumps_between_matches() {
+        let mut app = test_list();
+        app.start_search();
+        for c in "ex".chars() {
+            app.search_push(c);
+        }
+        app.confirm_search();
+        assert_eq!(current(&app), "expires");
+        assert_eq!(app.search_matches(), [2]);
+
+        // A new search starts from the cursor, and stepping wraps around.
+        app.clear_search();
+        app.start_search();
+        app.search_push('s');
+        app.confirm_search();
+        assert_eq!(current(&app), "expires");
+        app.next_match();
+        assert_eq!(current(&app), "locks_out");
+        app.next_match();
+        assert_eq!(current(&app), "accepts");
+        app.prev_match();
+        assert_eq!(current(&app), "locks_out");
+    }
+
+    #[test]
+    fn parameterized_cases_collapse_under_a_header() {
+        let mut app = test_list();
+        app.go_back();
+        app.select_next();
+        app.enter();
+        assert_eq!(rows(&app), ["adds[…]", "carries"]);
+        assert!(app.current_test().is_none());
+
+        app.toggle_params();
+        assert_eq!(rows(&app), ["adds[…]", "adds[1]", "adds[2]", "carries"]);
+        app.select_next();
+        assert_eq!(current(&app), "adds[1]");
+
+        app.toggle_params();
+        assert_eq!(rows(&app), ["adds[…]", "carries"]);
+        assert_eq!(app.selected_test, 0);
+    }
+
+    #[test]
+    fn tree_follows_the_selected_test() {
+        let mut app = test_list();
+        app.select_next();
+        app.toggle_tree_mode();
+        assert_eq!(app.view, View::Tree);
+        assert_eq!(app.tree_test().unwrap().name, "rejects");
+
+        app.toggle_expand();
+        assert_eq!(app.tree_rows(), [TreeRow::Suite(0), TreeRow::Suite(1)]);
+        assert_eq!(app.tree_selected, 0);
+    }
+
+    #[test]
+    fn restores_a_saved_session() {
+        let mut app = test_list();
+        app.select_last();
+        app.enter();
+        assert_eq!(app.view, View::TestDetail);
+        let position = app.position();
+
+        // Names, not indices, are saved: the test is found again after
+        // the report's order changes.
+        let accepts = r#"<testcase classname="com.example.Login" name="accepts" time="0.1"/>"#;
+        let reordered = SUITES.replace(accepts, "").replacen(
+            "    </testsuite>",
+            &format!("    {}\n    </testsuite>", accepts),
+            1,
+        );
+        let mut restored = app_from(&reordered);
+        restored.restore_position(&position);
+        assert_eq!(restored.view, View::TestDetail);
+        assert_eq!(restored.current_test_index(), Some(2));
+        assert_eq!(current(&restored), "locks_out");
+
+        // A test that's gone falls back to its suite's list.
+        let mut gone = position.clone();
+        gone.test = Some("vanished".into());
+        gone.view = SavedView::Tests;
+        let mut restored = app_from(SUITES);
+        restored.restore_position(&gone);
+        assert_eq!(restored.view, View::TestList);
+        assert_eq!(restored.current_suite().unwrap().name, "com.example.Login");
+    }
+}
@@ -0,0 +1,45 @@
+use ratatui::buffer::Buffer;
+
+/// Replaces the box-drawing characters, arrows, markers and other glyphs
+/// the UI draws with ASCII look-alikes, for terminals and CI consoles that
+/// mangle them. Runs over the finished frame so borders, scrollbars and
+/// charts drawn by ratatui itself are covered too. Other non-ASCII text,
+/// such as test names, is left alone.
+pub fn downgrade(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        if cell.symbol().is_ascii() {
+            continue;
+        }
+        if let Some(ascii) = ascii_glyph(cell.symbol()) {
+            cell.set_symbol(ascii);
+        }
+    }
+}
+
+fn ascii_glyph(symbol: &str) -> Option<&'static str> {
+    let mut chars = symbol.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    Some(match c {
+        '─' | '━' | '═' | '—' | '–' | '−' => "-",
+        '│' | '┃' | '║' => "|",
+        '┌' | '┐' | '└' | '┘' | '┏' | '┓' | '┗' | '┛' | '╔' | '╗' | '╚' | '╝' | '╭' | '╮' | '╰'
+        | '╯' | '├' | '┤' | '┬' | '┴' | '┼' => "+",
+        '▸' | '▶' | '→' => ">",
+        '◂' | '◀' | '←' => "<",
+        '▾' | '▼' | '↓' => "v",
+        '▴' | '▲' | '↑' => "^",
+        '█' | '▓' | '▒' | '▀' | '▄' | '■' => "#",
+        '░' => ".",
+        '●' | '•' => "*",
+        '…' => ".",
+        '✓' => "+",
+        '✗' | '×' => "x",
+        'Σ' => "S",
+        // Braille dots from chart lines.
+        '\u{2800}' => " ",
+        '\u{2801}'..='\u{28ff}' => ".",
+        _ => return None,
+    })
+}
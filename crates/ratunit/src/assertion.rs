@@ -0,0 +1,231 @@
+use crate::highlight;
+use crate::theme::Theme;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+
+/// Above this many cells the line diff gives up on aligning lines and
+/// shows every expected line followed by every actual one.
+const MAX_DIFF_CELLS: usize = 250_000;
+
+/// An assertion message split around the values it compares.
+struct Comparison<'a> {
+    before: &'a str,
+    expected: &'a str,
+    actual: &'a str,
+    after: &'a str,
+}
+
+/// Finds the compared values in the shapes test frameworks commonly print:
+/// `expected:<X> but was:<Y>` (JUnit, AssertJ, TestNG), and separate
+/// `Expected: X` / `Actual: Y` lines (Jest's `Received:`, Hamcrest's
+/// `but: was`).
+fn comparison(message: &str) -> Option<Comparison<'_>> {
+    inline_comparison(message).or_else(|| line_comparison(message))
+}
+
+fn inline_comparison(message: &str) -> Option<Comparison<'_>> {
+    let lower = message.to_ascii_lowercase();
+    let start = lower.find("expected:")?;
+    let but = start + lower[start..].find("but was:")?;
+    let expected = message[start + "expected:".len()..but].trim();
+
+    let rest = &message[but + "but was:".len()..];
+    let trimmed = rest.trim_start();
+    let offset = rest.len() - trimmed.len();
+    let end = if trimmed.starts_with('<') {
+        trimmed.rfind('>').map_or(trimmed.len(), |i| i + 1)
+    } else {
+        trimmed.find('\n').unwrap_or(trimmed.len())
+    };
+    let actual_start = but + "but was:".len() + offset;
+    Some(Comparison {
+        before: message[..start].trim_end(),
+        expected: unwrap_angles(expected.trim_end_matches(',')),
+        actual: unwrap_angles(trimmed[..end].trim_end()),
+        after: message[actual_start + end..].trim(),
+    })
+}
+
+fn line_comparison(message: &str) -> Option<Comparison<'_>> {
+    fn value<'a>(line: &'a str, labels: &[&str]) -> Option<&'a str> {
+        let trimmed = line.trim_start();
+        labels.iter().find_map(|label| {
+            let prefix = trimmed.get(..label.len())?;
+            prefix
+                .eq_ignore_ascii_case(label)
+                .then(|| trimmed[label.len()..].trim())
+        })
+    }
+    let lines: Vec<(usize, &str)> = message
+        .split_inclusive('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len();
+            Some((start, line))
+        })
+        .collect();
+
+    let (e, expected) = lines
+        .iter()
+        .enumerate()
+        .find_map(|(i, (_, l))| value(l, &["expected:"]).map(|v| (i, v)))?;
+    let (a, actual) = lines[e + 1..].iter().enumerate().find_map(|(i, (_, l))| {
+        value(l, &["actual:", "received:", "but was:", "but: was"]).map(|v| (e + 1 + i, v))
+    })?;
+    let after = lines
+        .get(a + 1)
+        .map_or("", |&(offset, _)| &message[offset..]);
+    Some(Comparison {
+        before: message[..lines[e].0].trim_end(),
+        expected: unwrap_angles(expected),
+        actual: unwrap_angles(actual),
+        after: after.trim(),
+    })
+}
+
+/// Strips the `<…>` JUnit puts around each value.
+fn unwrap_angles(value: &str) -> &str {
+    value
+        .strip_prefix('<')
+        .and_then(|v| v.strip_suffix('>'))
+        .unwrap_or(value)
+}
+
+/// A failure or error message. When it compares an expected and an actual
+/// value, those are shown on their own lines with the differences
+/// highlighted; the rest of the message keeps `color`.
+pub fn message_lines(message: &str, color: Color, theme: &Theme) -> Vec<Line<'static>> {
+    let plain = |text: &str| -> Vec<Line<'static>> {
+        text.lines()
+            .map(|l| Line::styled(l.to_string(), Style::default().fg(color)))
+            .collect()
+    };
+    let Some(cmp) = comparison(message) else {
+        return plain(message);
+    };
+
+    let mut lines = plain(cmp.before);
+    if cmp.expected.contains('\n') || cmp.actual.contains('\n') {
+        lines.extend(line_diff(cmp.expected, cmp.actual, theme));
+    } else {
+        let (expected, actual) = char_diff(cmp.expected, cmp.actual, theme);
+        let label =
+            |text: &'static str| Span::styled(text, Style::default().bold().fg(theme.accent));
+        lines.push(Line::from([vec![label("Expected: ")], expected].concat()));
+        lines.push(Line::from([vec![label("  Actual: ")], actual].concat()));
+    }
+    lines.extend(plain(cmp.after));
+    lines
+}
+
+/// Both values with the part between their common prefix and suffix
+/// emphasised.
+fn char_diff(
+    expected: &str,
+    actual: &str,
+    theme: &Theme,
+) -> (Vec<Span<'static>>, Vec<Span<'static>>) {
+    let e: Vec<char> = expected.chars().collect();
+    let a: Vec<char> = actual.chars().collect();
+    let prefix = e.iter().zip(&a).take_while(|(x, y)| x == y).count();
+    let suffix = e[prefix..]
+        .iter()
+        .rev()
+        .zip(a[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let spans = |chars: &[char], color| {
+        let style = Style::default().fg(color);
+        let part = |range: &[char]| range.iter().collect::<String>();
+        let middle = &chars[prefix..chars.len() - suffix];
+        let mut spans = vec![Span::styled(part(&chars[..prefix]), style)];
+        if !middle.is_empty() {
+            spans.push(Span::styled(part(middle), style.bold().reversed()));
+        }
+        spans.push(Span::styled(part(&chars[chars.len() - suffix..]), style));
+        spans
+    };
+    (spans(&e, theme.passed), spans(&a, theme.failed))
+}
+
+/// Multi-line values as a line diff: `-` for lines only expected, `+` for
+/// lines only in the actual value.
+fn line_diff(expected: &str, actual: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let e: Vec<&str> = expected.lines().collect();
+    let a: Vec<&str> = actual.lines().collect();
+    let only_expected =
+        |l: &str| Line::styled(format!("- {}", l), Style::default().fg(theme.passed));
+    let only_actual = |l: &str| Line::styled(format!("+ {}", l), Style::default().fg(theme.failed));
+
+    let mut lines = vec![Line::from(vec![
+        Span::styled("Diff ", Style::default().bold().fg(theme.accent)),
+        Span::styled("(- expected, + actual)", Style::default().fg(theme.muted)),
+    ])];
+    if e.len().saturating_mul(a.len()) > MAX_DIFF_CELLS {
+        lines.extend(e.iter().map(|l| only_expected(l)));
+        lines.extend(a.iter().map(|l| only_actual(l)));
+        return lines;
+    }
+
+    // Longest common subsequence lengths of every pair of suffixes.
+    let mut lcs = vec![vec![0usize; a.len() + 1]; e.len() + 1];
+    for i in (0..e.len()).rev() {
+        for j in (0..a.len()).rev() {
+            lcs[i][j] = if e[i] == a[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < e.len() || j < a.len() {
+        if i < e.len() && j < a.len() && e[i] == a[j] {
+            lines.push(Line::styled(
+                format!("  {}", e[i]),
+                Style::default().fg(theme.text),
+            ));
+            i += 1;
+            j += 1;
+        } else if i < e.len() && (j == a.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(only_expected(e[i]));
+            i += 1;
+        } else {
+            lines.push(only_actual(a[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// Whether a failure body contains a unified diff, or the `- `/`+ ` diff
+/// pytest prints after `Full diff:`.
+pub fn has_diff(body: &str) -> bool {
+    let lines: Vec<&str> = body.lines().map(str::trim_start).collect();
+    lines
+        .iter()
+        .any(|l| l.starts_with("@@ ") || l.starts_with("Full diff:"))
+        || lines
+            .windows(2)
+            .any(|pair| pair[0].starts_with("--- ") && pair[1].starts_with("+++ "))
+}
+
+/// Styles one line of a body that [`has_diff`]: removed lines red, added
+/// lines green, hunk headers in the accent color. Other lines are
+/// highlighted as part of a stack trace.
+pub fn diff_line(line: &str, theme: &Theme) -> Line<'static> {
+    let trimmed = line.trim_start();
+    let style = if trimmed.starts_with("@@") {
+        Style::default().fg(theme.accent)
+    } else if trimmed.starts_with("--- ") || trimmed.starts_with("+++ ") {
+        Style::default().fg(theme.muted).bold()
+    } else if trimmed.starts_with('-') {
+        Style::default().fg(theme.failed)
+    } else if trimmed.starts_with('+') {
+        Style::default().fg(theme.passed)
+    } else {
+        return highlight::trace_line(line, theme);
+    };
+    Line::styled(format!("  {}", line), style)
+}
@@ -0,0 +1,252 @@
+use crate::ascii;
+use crate::theme::Theme;
+use anyhow::Result;
+use crossterm::event::{self as ct_event, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A row of the browser: the parent directory, a subdirectory or a report.
+struct Entry {
+    name: String,
+    path: PathBuf,
+    kind: EntryKind,
+}
+
+#[derive(PartialEq, Eq)]
+enum EntryKind {
+    Parent,
+    Dir,
+    Report,
+}
+
+struct Browser {
+    dir: PathBuf,
+    entries: Vec<Entry>,
+    selected: usize,
+    /// Number of XML files directly in `dir`, which `o` opens together.
+    reports: usize,
+    error: Option<String>,
+}
+
+impl Browser {
+    fn new(dir: &Path) -> Self {
+        let mut browser = Browser {
+            dir: PathBuf::new(),
+            entries: Vec::new(),
+            selected: 0,
+            reports: 0,
+            error: None,
+        };
+        browser.enter(dir);
+        browser
+    }
+
+    /// Lists `dir`: its parent first, then subdirectories, then XML files,
+    /// each alphabetically. Hidden entries are skipped.
+    fn enter(&mut self, dir: &Path) {
+        let dir = std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let read = match std::fs::read_dir(&dir) {
+            Ok(read) => read,
+            Err(e) => {
+                self.error = Some(format!("{}: {}", dir.display(), e));
+                return;
+            }
+        };
+        let mut entries: Vec<Entry> = read
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let path = entry.path();
+                if name.starts_with('.') {
+                    None
+                } else if path.is_dir() {
+                    Some(Entry {
+                        name: format!("{}/", name),
+                        path,
+                        kind: EntryKind::Dir,
+                    })
+                } else if path.extension().is_some_and(|ext| ext == "xml") {
+                    Some(Entry {
+                        name,
+                        path,
+                        kind: EntryKind::Report,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| {
+            (a.kind == EntryKind::Report, &a.name).cmp(&(b.kind == EntryKind::Report, &b.name))
+        });
+        self.reports = entries
+            .iter()
+            .filter(|e| e.kind == EntryKind::Report)
+            .count();
+        if let Some(parent) = dir.parent() {
+            entries.insert(
+                0,
+                Entry {
+                    name: "../".into(),
+                    path: parent.to_path_buf(),
+                    kind: EntryKind::Parent,
+                },
+            );
+        }
+
+        // Coming back up, keep the directory just left selected.
+        let previous = std::mem::replace(&mut self.dir, dir);
+        self.selected = entries
+            .iter()
+            .position(|e| e.kind == EntryKind::Dir && e.path == previous)
+            .unwrap_or(0);
+        self.entries = entries;
+        self.error = None;
+    }
+
+    fn go_up(&mut self) {
+        if let Some(parent) = self.dir.parent().map(Path::to_path_buf) {
+            self.enter(&parent);
+        }
+    }
+
+    /// Opens the selected entry: directories are entered, a report is
+    /// returned.
+    fn open(&mut self) -> Option<PathBuf> {
+        let entry = self.entries.get(self.selected)?;
+        match entry.kind {
+            EntryKind::Report => Some(entry.path.clone()),
+            EntryKind::Parent | EntryKind::Dir => {
+                let path = entry.path.clone();
+                self.enter(&path);
+                None
+            }
+        }
+    }
+}
+
+/// Shows a directory browser starting at `start` until a report file, or
+/// a directory of them, is picked. `None` if the user quit instead.
+pub fn pick(start: &Path, theme: &Theme, ascii: bool) -> Result<Option<PathBuf>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = run(&mut terminal, &mut Browser::new(start), theme, ascii);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    result
+}
+
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    browser: &mut Browser,
+    theme: &Theme,
+    ascii: bool,
+) -> Result<Option<PathBuf>> {
+    loop {
+        terminal.draw(|frame| {
+            render(frame, browser, theme);
+            if ascii {
+                ascii::downgrade(frame.buffer_mut());
+            }
+        })?;
+
+        let Event::Key(key) = ct_event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let last = browser.entries.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+            KeyCode::Char('j') | KeyCode::Down => {
+                browser.selected = (browser.selected + 1).min(last)
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                browser.selected = browser.selected.saturating_sub(1)
+            }
+            KeyCode::Char('g') | KeyCode::Home => browser.selected = 0,
+            KeyCode::Char('G') | KeyCode::End => browser.selected = last,
+            KeyCode::Char('h') | KeyCode::Left | KeyCode::Backspace => browser.go_up(),
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                if let Some(path) = browser.open() {
+                    return Ok(Some(path));
+                }
+            }
+            KeyCode::Char('o') if browser.reports > 0 => return Ok(Some(browser.dir.clone())),
+            _ => {}
+        }
+    }
+}
+
+fn render(frame: &mut Frame, browser: &Browser, theme: &Theme) {
+    let [list_area, status_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+
+    let items: Vec<ListItem> = browser
+        .entries
+        .iter()
+        .map(|entry| {
+            let style = match entry.kind {
+                EntryKind::Parent => Style::default().fg(theme.muted),
+                EntryKind::Dir => Style::default().fg(theme.accent),
+                EntryKind::Report => Style::default().fg(theme.text),
+            };
+            ListItem::new(Line::styled(entry.name.clone(), style))
+        })
+        .collect();
+    let block = Block::default()
+        .title(format!(" Open a report — {} ", browser.dir.display()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let mut state = ListState::default().with_selected(Some(browser.selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, list_area, &mut state);
+
+    let key = |k: &'static str| Span::styled(k, Style::default().bold().fg(theme.accent));
+    let status = match &browser.error {
+        Some(error) => Line::styled(format!(" {}", error), Style::default().fg(theme.failed)),
+        None => {
+            let mut spans = vec![
+                key(" Enter"),
+                Span::raw(" open  "),
+                key("h"),
+                Span::raw(" up  "),
+            ];
+            if browser.reports > 0 {
+                spans.extend([
+                    key("o"),
+                    Span::raw(format!(
+                        " open all {} report{} here  ",
+                        browser.reports,
+                        if browser.reports == 1 { "" } else { "s" }
+                    )),
+                ]);
+            }
+            spans.extend([key("q"), Span::raw(" quit")]);
+            Line::from(spans)
+        }
+    };
+    frame.render_widget(
+        Paragraph::new(status).style(Style::default().bg(theme.status_bg).fg(theme.status_fg)),
+        status_area,
+    );
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use base64::Engine;
+use std::io::Write;
+
+/// The system clipboard, opened on first copy and kept open for the
+/// session: on X11 and Wayland without a clipboard manager, copied text is
+/// only served while it is.
+#[derive(Default)]
+pub struct Clipboard {
+    native: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Copies `text` to the system clipboard, falling back to an OSC 52
+    /// escape sequence when no native clipboard is reachable (e.g. over
+    /// SSH).
+    pub fn copy(&mut self, text: &str) -> Result<()> {
+        if self.native.is_none() {
+            self.native = arboard::Clipboard::new().ok();
+        }
+        if let Some(native) = &mut self.native {
+            if native.set_text(text.to_owned()).is_ok() {
+                return Ok(());
+            }
+        }
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+        let mut stdout = std::io::stdout();
+        write!(stdout, "\x1b]52;c;{}\x07", encoded)?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use crate::app::{Baseline, FileReport};
+use crate::quarantine::Quarantine;
+use crate::test_id::IdFormat;
+use crate::{html, summary, ui};
+use junit_parser::{TestCase, TestStatus, TestSuites};
+
+/// Builds the Markdown comment for `ratunit comment`: the pass rate and
+/// verdict, as `ratunit check` works them out, and the totals, the failing
+/// tests (only those new since `baseline` when there is one), the
+/// `slowest` slowest tests, then each failure's trace in a collapsed
+/// section. Failures are dropped from the end, with a note, to keep the
+/// comment within `max_size` bytes.
+pub fn comment(
+    files: &[FileReport],
+    baseline: Option<&Baseline>,
+    quarantine: &Quarantine,
+    slowest: usize,
+    max_size: usize,
+) -> String {
+    let report = TestSuites::merge(files.iter().map(|f| &f.data));
+    let tests = report.total_tests();
+    let passed = report.total_passed();
+    let failed = report.total_failures();
+    let errors = report.total_errors();
+    let skipped = report.total_skipped();
+
+    let failing: Vec<&TestCase> = report
+        .all_cases()
+        .filter(|(_, tc)| matches!(tc.status(), TestStatus::Failed | TestStatus::Errored))
+        .filter(|(suite, tc)| baseline.is_none_or(|b| !b.is_known(suite, tc)))
+        .map(|(_, tc)| tc)
+        .collect();
+
+    let regressed = summary::counted_failures(files, quarantine, baseline)
+        .next()
+        .is_some();
+    let icon = if regressed { "❌" } else { "✅" };
+    let rate = summary::pass_rate(files, quarantine);
+    let mut head = format!("## {} {:.1}% of tests passed\n\n", icon, rate);
+    let mut totals = vec![format!("{} tests", tests), format!("**{} passed**", passed)];
+    for (n, label) in [(failed, "failed"), (errors, "errors"), (skipped, "skipped")] {
+        if n > 0 {
+            totals.push(format!("**{} {}**", n, label));
+        }
+    }
+    let time = report.total_case_time();
+    if time > 0.0 {
+        totals.push(format!("{:.1}s", time));
+    }
+    head.push_str(&totals.join(" · "));
+    head.push('\n');
+
+    let title = match baseline {
+        Some(b) => format!("New failures since `{}`", b.name),
+        None => "Failures".to_string(),
+    };
+    if baseline.is_some() || !failing.is_empty() {
+        head.push_str(&format!("\n### {} ({})\n\n", title, failing.len()));
+    }
+    if baseline.is_some() && failing.is_empty() {
+        head.push_str("None.\n");
+    }
+    let items: Vec<String> = failing
+        .iter()
+        .map(|tc| match tc.failure_line() {
+            Some(message) => format!(
+                "- `{}` — {}\n",
+                IdFormat::Dotted.format(tc),
+                html::escape(message)
+            ),
+            None => format!("- `{}`\n", IdFormat::Dotted.format(tc)),
+        })
+        .collect();
+
+    let mut slow: Vec<&TestCase> = report
+        .all_cases()
+        .map(|(_, tc)| tc)
+        .filter(|tc| tc.time.is_some())
+        .collect();
+    slow.sort_by(|a, b| b.time.unwrap_or(0.0).total_cmp(&a.time.unwrap_or(0.0)));
+    slow.truncate(slowest);
+    let mut slow_table = String::new();
+    if !slow.is_empty() {
+        slow_table.push_str(&format!(
+            "\n<details>\n<summary>Slowest {} tests</summary>\n\n| Test | Time |\n|---|--:|\n",
+            slow.len()
+        ));
+        for tc in &slow {
+            slow_table.push_str(&format!(
+                "| `{}` | {:.2}s |\n",
+                IdFormat::Dotted.format(tc).replace('|', "\\|"),
+                tc.time.unwrap_or(0.0)
+            ));
+        }
+        slow_table.push_str("\n</details>\n");
+    }
+
+    let traces: Vec<String> = failing
+        .iter()
+        .filter_map(|tc| {
+            let block = ui::fenced(&tc.failure_text()?)?;
+            Some(format!(
+                "\n#### `{}`\n\n{}",
+                IdFormat::Dotted.format(tc),
+                block
+            ))
+        })
+        .collect();
+    let traces_open = "\n<details>\n<summary>Failure details</summary>\n";
+    let traces_close = "\n</details>\n";
+
+    // Room is kept for a truncation note at each step, and for the
+    // collapsed section around the traces.
+    let note_room = 80;
+    let traces_room = if traces.is_empty() {
+        0
+    } else {
+        traces_open.len() + traces_close.len() + note_room
+    };
+    let mut md = head;
+    let shown = push_within(
+        &mut md,
+        &items,
+        max_size.saturating_sub(slow_table.len() + traces_room + note_room),
+    );
+    if shown < items.len() {
+        md.push_str(&format!("- … and {} more\n", items.len() - shown));
+    }
+    md.push_str(&slow_table);
+    if !traces.is_empty() {
+        md.push_str(traces_open);
+        let limit = max_size.saturating_sub(traces_close.len() + note_room);
+        let shown = push_within(&mut md, &traces, limit);
+        md.push_str(traces_close);
+        if shown < traces.len() {
+            md.push_str(&format!(
+                "\n_{} of {} failures not shown to keep this comment short._\n",
+                traces.len() - shown,
+                traces.len()
+            ));
+        }
+    }
+    md
+}
+
+/// Appends `items` in order while `out` stays within `limit` bytes, and
+/// returns how many fit.
+fn push_within(out: &mut String, items: &[String], limit: usize) -> usize {
+    for (i, item) in items.iter().enumerate() {
+        if out.len() + item.len() > limit {
+            return i;
+        }
+        out.push_str(item);
+    }
+    items.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(failures: usize) -> FileReport {
+        let trace = "at com.example.Test.run(Test.java:1)\n".repeat(20);
+        let cases: String = (0..failures)
+            .map(|i| {
+                format!(
+                    r#"<testcase classname="C" name="t{}" time="0.1"><failure message="boom">{}</failure></testcase>"#,
+                    i, trace
+                )
+            })
+            .collect();
+        let xml = format!(
+            r#"<testsuite name="s" tests="{0}" failures="{0}">{1}</testsuite>"#,
+            failures, cases
+        );
+        FileReport::from_xml("r.xml", &xml)
+    }
+
+    #[test]
+    fn truncates_within_max_size() {
+        let files = [report(40)];
+        for max_size in [2_000, 5_000, 20_000] {
+            let md = comment(&files, None, &Quarantine::default(), 5, max_size);
+            assert!(md.len() <= max_size, "{} > {}", md.len(), max_size);
+            assert!(md.ends_with("failures not shown to keep this comment short._\n"));
+        }
+    }
+
+    #[test]
+    fn short_comments_are_not_truncated() {
+        let files = [report(2)];
+        let md = comment(&files, None, &Quarantine::default(), 5, 65_000);
+        assert!(md.ends_with("</details>\n"));
+        assert!(!md.contains("not shown"));
+    }
+
+    #[test]
+    fn quarantined_failures_pass_the_headline() {
+        let files = [report(2)];
+        let md = comment(&files, None, &Quarantine::default(), 5, 65_000);
+        assert!(md.starts_with("## ❌ 0.0% of tests passed"));
+
+        let config = crate::config::Config {
+            quarantine: vec!["C.*".into()],
+            ..Default::default()
+        };
+        let quarantine = Quarantine::load(&config).unwrap();
+        let md = comment(&files, None, &quarantine, 5, 65_000);
+        assert!(md.starts_with("## ✅ 100.0% of tests passed"));
+    }
+}
@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+/// Completes the last component of a typed path against the filesystem.
+/// Only directories (shown with a trailing `/`) and `.xml` files are
+/// offered. Returns the input extended by the longest prefix shared by all
+/// matches, and the matching names when there is more than one.
+pub fn complete_path(input: &str) -> (String, Vec<String>) {
+    let (dir, prefix) = match input.rfind('/') {
+        Some(i) => (&input[..=i], &input[i + 1..]),
+        None => ("", input),
+    };
+    let Ok(entries) = std::fs::read_dir(expand_home(if dir.is_empty() { "." } else { dir })) else {
+        return (input.to_string(), Vec::new());
+    };
+
+    let mut matches: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            if entry.path().is_dir() {
+                Some(format!("{}/", name))
+            } else if name.ends_with(".xml") {
+                Some(name)
+            } else {
+                None
+            }
+        })
+        .collect();
+    matches.sort();
+
+    let Some(first) = matches.first() else {
+        return (input.to_string(), Vec::new());
+    };
+    let common = matches.iter().fold(first.as_str(), |common, name| {
+        let len = common
+            .char_indices()
+            .zip(name.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8());
+        &common[..len]
+    });
+    let completed = format!("{}{}", dir, common);
+    if matches.len() == 1 {
+        (completed, Vec::new())
+    } else {
+        (completed, matches)
+    }
+}
+
+/// Replaces a leading `~` with the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            Path::new(&home).join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
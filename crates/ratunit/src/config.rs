@@ -0,0 +1,98 @@
+use crate::keymap::{Action, KeyList};
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Contents of `~/.config/ratunit/config.toml`. Every key is optional.
+///
+/// ```toml
+/// theme = "mine"
+///
+/// [themes.mine]
+/// base = "solarized"
+/// failed = "#ff5555"
+/// accent = "lightblue"
+///
+/// [keys]
+/// quit = ["q", "ctrl-q"]
+/// filter = "F"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: Option<String>,
+    /// Render ANSI colors in captured output (default true). When false the
+    /// escape codes are stripped instead.
+    pub ansi: Option<bool>,
+    /// Prefix suites and files with ✓ / ✗ / − so status doesn't rely on
+    /// color. On by default with `--no-color` and the colorblind theme.
+    pub markers: Option<bool>,
+    /// Pop up a desktop notification when a watched report starts or stops
+    /// failing (default true).
+    pub notifications: Option<bool>,
+    /// Directories searched for the files stack frames name, before the
+    /// usual `src/main/java` style layouts. `~` is expanded.
+    pub source_roots: Vec<String>,
+    /// Tests known to fail, as globs over `classname.name`; see
+    /// [`crate::quarantine::Quarantine`].
+    pub quarantine: Vec<String>,
+    /// A file of more quarantine patterns, one per line. `~` is expanded.
+    pub quarantine_file: Option<String>,
+    pub themes: HashMap<String, ThemeConfig>,
+    /// Replacement keys per action; see [`crate::keymap::KeyMap::new`].
+    pub keys: BTreeMap<Action, KeyList>,
+}
+
+/// A user-defined palette: any color left out is taken from `base`
+/// (default `dark`). Colors are names (`red`, `lightblue`), `#rrggbb`
+/// hex strings or 256-color indexes.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    pub base: Option<String>,
+    pub accent: Option<Color>,
+    pub text: Option<Color>,
+    pub muted: Option<Color>,
+    pub passed: Option<Color>,
+    pub failed: Option<Color>,
+    pub errored: Option<Color>,
+    pub skipped: Option<Color>,
+    pub selection_bg: Option<Color>,
+    pub status_bg: Option<Color>,
+    pub status_fg: Option<Color>,
+    pub match_fg: Option<Color>,
+    pub match_bg: Option<Color>,
+    pub stdout: Option<Color>,
+    pub stderr: Option<Color>,
+    pub location: Option<Color>,
+    pub line_number: Option<Color>,
+    pub symbol: Option<Color>,
+}
+
+/// `$XDG_CONFIG_HOME/ratunit/config.toml`, defaulting to
+/// `~/.config/ratunit/config.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(base.join("ratunit").join("config.toml"))
+}
+
+/// Loads the config file at `path`, or from [`default_path`] when none is
+/// given. A missing default file yields the defaults; a missing explicit
+/// file is an error.
+pub fn load(path: Option<&Path>) -> Result<Config> {
+    let path = match path {
+        Some(path) => path.to_path_buf(),
+        None => match default_path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Config::default()),
+        },
+    };
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read config: {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse config: {}", path.display()))
+}
@@ -0,0 +1,105 @@
+use crate::app::FileReport;
+use junit_parser::{TestCase, TestStatus};
+
+/// One RFC 4180 row per test case — file, suite, class, name, status, time
+/// in seconds and message — under a header row, for spreadsheets and
+/// warehouse loads. The message is the first line of a failure or error,
+/// or a skipped test's reason.
+pub fn table(files: &[FileReport]) -> String {
+    let mut csv = String::from("file,suite,class,name,status,time,message\r\n");
+    for file in files {
+        for (suite, tc) in file.data.all_cases() {
+            let status = match tc.status() {
+                TestStatus::Passed => "passed",
+                TestStatus::Failed => "failed",
+                TestStatus::Errored => "errored",
+                TestStatus::Skipped => "skipped",
+            };
+            let time = tc.time.map(|t| t.to_string()).unwrap_or_default();
+            let fields = [
+                file.filename.as_str(),
+                &suite.name,
+                tc.classname.as_deref().unwrap_or_default(),
+                &tc.name,
+                status,
+                &time,
+                &message(tc).unwrap_or_default(),
+            ];
+            let row: Vec<String> = fields.iter().map(|f| field(f)).collect();
+            csv.push_str(&row.join(","));
+            csv.push_str("\r\n");
+        }
+    }
+    csv
+}
+
+/// A skipped test's reason, or the first line of the failure or error
+/// message.
+fn message(tc: &TestCase) -> Option<String> {
+    if let Some(reason) = tc.skipped.as_ref().and_then(|s| s.reason()) {
+        return Some(reason.to_string());
+    }
+    tc.failure_line().map(String::from)
+}
+
+/// `text` quoted when it holds a comma, quote or line break, with quotes
+/// doubled.
+fn field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_fields_are_left_alone() {
+        assert_eq!(field("com.example.LoginTest"), "com.example.LoginTest");
+        assert_eq!(field(""), "");
+    }
+
+    #[test]
+    fn commas_are_quoted() {
+        assert_eq!(field("a,b"), r#""a,b""#);
+    }
+
+    #[test]
+    fn quotes_are_doubled() {
+        assert_eq!(field(r#"expected "x""#), r#""expected ""x""""#);
+    }
+
+    #[test]
+    fn line_breaks_are_quoted() {
+        assert_eq!(field("one\ntwo"), "\"one\ntwo\"");
+        assert_eq!(field("one\r\ntwo"), "\"one\r\ntwo\"");
+    }
+
+    #[test]
+    fn one_row_per_case_under_a_header() {
+        let xml = r#"<testsuite name="Suite" tests="4" failures="1" errors="1" skipped="1">
+            <testcase classname="C" name="passes" time="0.5"/>
+            <testcase classname="C" name="fails" time="1.25">
+                <failure message="expected 1, got 2">trace</failure>
+            </testcase>
+            <testcase name="errors"><error>NullPointerException
+at Foo.bar</error></testcase>
+            <testcase classname="C" name="skips"><skipped message="flaky"/></testcase>
+        </testsuite>"#;
+        let csv = table(&[FileReport::from_xml("r.xml", xml)]);
+        let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+        assert_eq!(
+            rows,
+            [
+                "file,suite,class,name,status,time,message",
+                "r.xml,Suite,C,passes,passed,0.5,",
+                r#"r.xml,Suite,C,fails,failed,1.25,"expected 1, got 2""#,
+                "r.xml,Suite,,errors,errored,,NullPointerException",
+                "r.xml,Suite,C,skips,skipped,,flaky",
+            ]
+        );
+    }
+}
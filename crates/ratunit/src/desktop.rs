@@ -0,0 +1,13 @@
+use notify_rust::Notification;
+
+/// Pops up a desktop notification without holding up the UI. Failures,
+/// such as there being no notification daemon, are ignored.
+pub fn notify(summary: String, body: String) {
+    std::thread::spawn(move || {
+        let _ = Notification::new()
+            .appname("ratunit")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
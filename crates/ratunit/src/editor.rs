@@ -0,0 +1,202 @@
+use anyhow::{bail, Context, Result};
+use junit_parser::TestCase;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A place in a source file to open in the user's editor.
+pub struct SourceLocation {
+    pub file: String,
+    pub line: Option<u32>,
+}
+
+/// The test's `file` attribute, plus a line number if one of its stack
+/// traces or captured output mentions `<basename>:<line>`.
+pub fn source_location(tc: &TestCase) -> Option<SourceLocation> {
+    let file = tc.file.clone()?;
+    let basename = Path::new(&file)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file.clone());
+
+    let texts = [
+        tc.failure_text(),
+        tc.system_err.clone(),
+        tc.system_out.clone(),
+    ];
+    let line = texts
+        .iter()
+        .flatten()
+        .find_map(|text| find_line(text, &basename));
+
+    Some(SourceLocation { file, line })
+}
+
+/// Finds the first `<basename>:<digits>` in `text`.
+fn find_line(text: &str, basename: &str) -> Option<u32> {
+    let needle = format!("{}:", basename);
+    text.match_indices(&needle).find_map(|(i, _)| {
+        let digits: String = text[i + needle.len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits.parse().ok()
+    })
+}
+
+/// Directories tried, after any configured `source_roots`, when looking
+/// for the file a stack frame names.
+const DEFAULT_SOURCE_ROOTS: &[&str] = &[
+    ".",
+    "src/main/java",
+    "src/test/java",
+    "src/main/kotlin",
+    "src/test/kotlin",
+];
+
+/// The source reference of one stack trace line.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// The file as the trace names it: `Bar.java` in a JVM frame, a path
+    /// otherwise.
+    pub file: String,
+    pub line: Option<u32>,
+    /// The package of a JVM frame (`com.foo`), whose directories lead to
+    /// `file` under a source root.
+    pub package: Option<String>,
+}
+
+/// Recognises the same frames the detail view highlights: JVM and
+/// JavaScript `at …(File:line)`, Python `File "x.py", line 3` and
+/// `path:line:` locations.
+pub fn parse_frame(line: &str) -> Option<Frame> {
+    let line = line.trim();
+    if let Some(frame) = line.strip_prefix("at ") {
+        let Some((method, location)) = frame.split_once('(') else {
+            return path_frame(frame);
+        };
+        let location = location.strip_suffix(')').unwrap_or(location);
+        let mut frame = path_frame(location)?;
+        // `java.base/java.util.List.of` → class `java.util.List`.
+        let qualified = method.trim().rsplit('/').next().unwrap_or(method);
+        if !frame.file.contains('/') {
+            frame.package = qualified
+                .rsplit_once('.')
+                .and_then(|(class, _)| class.rsplit_once('.'))
+                .map(|(package, _)| package.to_string());
+        }
+        return Some(frame);
+    }
+    if let Some(frame) = line.strip_prefix("File \"") {
+        let (file, rest) = frame.split_once('"')?;
+        let digits: String = rest
+            .split_once("line ")?
+            .1
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        return Some(Frame {
+            file: file.to_string(),
+            line: digits.parse().ok(),
+            package: None,
+        });
+    }
+    path_frame(line)
+}
+
+/// `path/to/file.cpp:72: rest`, with the same rules as the highlighter:
+/// the path needs a `.` or `/`, and a line number must follow.
+fn path_frame(text: &str) -> Option<Frame> {
+    let (path, after) = text.split_once(':')?;
+    if path.is_empty() || path.contains(char::is_whitespace) || !path.contains(['.', '/']) {
+        return None;
+    }
+    let digits: String = after.chars().take_while(|c| c.is_ascii_digit()).collect();
+    Some(Frame {
+        file: path.to_string(),
+        line: Some(digits.parse().ok()?),
+        package: None,
+    })
+}
+
+/// Finds the file a frame names: as written, as the test's own `file`
+/// when the names match, then under each source root, where JVM frames
+/// are also looked for in their package's directory.
+pub fn resolve(
+    frame: &Frame,
+    roots: &[PathBuf],
+    test_file: Option<&str>,
+) -> Option<SourceLocation> {
+    let name = Path::new(&frame.file).file_name();
+    let mut candidates = vec![PathBuf::from(&frame.file)];
+    candidates.extend(
+        test_file
+            .map(PathBuf::from)
+            .filter(|path| path.file_name() == name),
+    );
+    let defaults = DEFAULT_SOURCE_ROOTS.iter().map(PathBuf::from);
+    for root in roots.iter().cloned().chain(defaults) {
+        if let Some(package) = &frame.package {
+            candidates.push(root.join(package.replace('.', "/")).join(&frame.file));
+        }
+        candidates.push(root.join(&frame.file));
+    }
+    let path = candidates.into_iter().find(|path| path.is_file())?;
+    Some(SourceLocation {
+        file: path.to_string_lossy().into_owned(),
+        line: frame.line,
+    })
+}
+
+/// Where a failing test went wrong, for reports that point at code: the
+/// first stack frame that resolves to a file on disk, else the test's
+/// `file` attribute when that exists. Paths are made relative with
+/// [`repo_relative`].
+pub fn failure_location(tc: &TestCase) -> Option<(String, u32)> {
+    let frame = tc.failure_text().and_then(|text| {
+        text.lines()
+            .filter_map(parse_frame)
+            .find_map(|frame| resolve(&frame, &[], tc.file.as_deref()))
+    });
+    let location =
+        frame.or_else(|| source_location(tc).filter(|l| Path::new(&l.file).is_file()))?;
+    Some((
+        repo_relative(Path::new(&location.file)),
+        location.line.unwrap_or(1),
+    ))
+}
+
+/// `path` relative to the working directory, which CI services take to be
+/// the repository root, when it lies inside it.
+pub fn repo_relative(path: &Path) -> String {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let path = path.strip_prefix(&cwd).unwrap_or(path);
+    let path = path.strip_prefix(".").unwrap_or(path);
+    path.display().to_string()
+}
+
+/// Runs `$VISUAL` or `$EDITOR` (default `vi`) on the location and waits for
+/// it to exit. The caller is responsible for handing over the terminal.
+pub fn open(location: &SourceLocation) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    let mut parts = editor.split_whitespace();
+    let Some(program) = parts.next() else {
+        bail!("$EDITOR is empty");
+    };
+
+    let mut command = Command::new(program);
+    command.args(parts);
+    if let Some(line) = location.line {
+        command.arg(format!("+{}", line));
+    }
+    command.arg(&location.file);
+
+    let status = command
+        .status()
+        .with_context(|| format!("Failed to launch editor: {}", editor))?;
+    if !status.success() {
+        bail!("Editor exited with {}", status);
+    }
+    Ok(())
+}
@@ -1,28 +1,829 @@
-use crate::app::App;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::app::{App, DetailPane, InputMode, OutputLayout, View};
+use crate::keymap::Action;
+use crate::test_id::IdFormat;
+use crate::{editor, summary, ui};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Two clicks on the same row within this window count as a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
 
 pub fn handle_key(app: &mut App, key: KeyEvent) {
+    app.status_message = None;
+
+    match app.input_mode {
+        InputMode::Search => return handle_search_key(app, key),
+        InputMode::Finder => return handle_finder_key(app, key),
+        InputMode::Help => return handle_help_key(app, key),
+        InputMode::Properties => return handle_properties_key(app, key),
+        InputMode::SuiteStats => return handle_suite_stats_key(app, key),
+        InputMode::SuiteOutput => return handle_suite_output_key(app, key),
+        InputMode::SuiteCompare => return handle_suite_compare_key(app, key),
+        InputMode::CopyId => return handle_copy_id_key(app, key),
+        InputMode::SidebarFilter => return handle_sidebar_filter_key(app, key),
+        InputMode::GoToLine => return handle_go_to_line_key(app, key),
+        InputMode::ClassFilter => return handle_class_filter_key(app, key),
+        InputMode::Export => return handle_export_key(app, key),
+        InputMode::AddFiles => return handle_add_files_key(app, key),
+        InputMode::Normal => {}
+    }
+
+    if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        app.should_quit = true;
+        return;
+    }
+    if key.code == KeyCode::Esc && app.has_search() {
+        app.clear_search();
+        return;
+    }
+    if key.code == KeyCode::Esc && app.view == View::TestList && !app.picked.is_empty() {
+        app.clear_picks();
+        return;
+    }
+    let Some(action) = app.keymap.action(key) else {
+        // With several files, digits pick a file from the suite list or
+        // tree (0: All files). Elsewhere, digits not bound to anything
+        // build a count for the next motion, and with Alt, 1–5 pick a
+        // status tab in the test list.
+        match key.code {
+            KeyCode::Char(c @ '0'..='9')
+                if key.modifiers.is_empty()
+                    && app.multi_file
+                    && matches!(app.view, View::SuiteList | View::Tree) =>
+            {
+                app.select_file_number(c as usize - '0' as usize);
+            }
+            KeyCode::Char(c @ '1'..='5')
+                if key.modifiers == KeyModifiers::ALT && app.view == View::TestList =>
+            {
+                app.select_tab(c as usize - '1' as usize);
+            }
+            KeyCode::Char(c) if key.modifiers.is_empty() && c.is_ascii_digit() => {
+                app.push_count_digit(c.to_digit(10).unwrap_or(0));
+            }
+            KeyCode::Esc => app.pending_count = None,
+            _ => {}
+        }
+        return;
+    };
+
+    // Motions repeat `count` times; anything else drops the count.
+    let explicit = app.pending_count.is_some();
+    let count = app.take_count();
+    let repeat = |app: &mut App, motion: fn(&mut App)| {
+        for _ in 0..count {
+            motion(app);
+        }
+    };
+
+    let scrolling_detail = app.view == View::TestDetail && !app.wrap_detail;
+    let picking_frame = app.view == View::TestDetail && app.selected_frame.is_some();
+    match action {
+        Action::Quit => app.should_quit = true,
+
+        Action::Down if picking_frame => repeat(app, |app| step_frame(app, true)),
+        Action::Up if picking_frame => repeat(app, |app| step_frame(app, false)),
+        Action::Open if app.view == View::TestDetail && !app.show_raw_xml => open_frame(app),
+        Action::Back if picking_frame => app.selected_frame = None,
+
+        Action::Down => repeat(app, App::select_next),
+        Action::Up => repeat(app, App::select_prev),
+        Action::First if explicit => app.go_to_line(count),
+        Action::Last if explicit => app.go_to_line(count),
+        Action::First => app.select_first(),
+        Action::Last => app.select_last(),
+        Action::PageDown => repeat(app, App::page_down),
+        Action::PageUp => repeat(app, App::page_up),
+
+        Action::Left if scrolling_detail => repeat(app, App::scroll_left),
+        Action::Right if scrolling_detail => repeat(app, App::scroll_right),
+        Action::ToggleWrap if app.view == View::TestDetail => app.toggle_wrap(),
+        Action::OutputLayout if app.view == View::TestDetail => app.cycle_output_layout(),
+        Action::FocusPane if app.view == View::TestDetail => app.cycle_detail_focus(),
+        Action::LineNumbers if app.view == View::TestDetail => app.toggle_line_numbers(),
+        Action::Pretty if app.view == View::TestDetail => app.toggle_pretty(),
+        Action::RawXml if app.view == View::TestDetail => app.toggle_raw_xml(),
+        Action::NextSection if app.view == View::TestDetail && !app.show_raw_xml => {
+            repeat(app, |app| jump_section(app, true))
+        }
+        Action::PrevSection if app.view == View::TestDetail && !app.show_raw_xml => {
+            repeat(app, |app| jump_section(app, false))
+        }
+        Action::GoToLine if app.view == View::TestDetail => app.start_go_to_line(),
+        Action::NextTest if app.view == View::TestDetail => {
+            repeat(app, |app| app.step_detail_test(true))
+        }
+        Action::PrevTest if app.view == View::TestDetail => {
+            repeat(app, |app| app.step_detail_test(false))
+        }
+
+        Action::Open | Action::Right => app.enter(),
+        Action::Back | Action::Left => app.go_back(),
+
+        Action::Finder => app.open_finder(),
+        Action::CopyFailure if app.view == View::TestDetail => copy_failure(app),
+        Action::CopyDetail if app.view == View::TestDetail => copy_detail(app),
+        Action::CopyId if app.view != View::SuiteList => app.open_copy_id(),
+        Action::Pager if app.view == View::TestDetail => {
+            app.pending_pager = app
+                .current_test()
+                .map(|tc| ui::detail_text(tc, &app.theme, app.render_ansi));
+        }
+        Action::Edit if app.view != View::SuiteList => request_edit(app),
+
+        Action::Help => app.open_help(),
+        Action::Search => app.start_search(),
+        Action::Filter => app.cycle_status_filter(),
+        Action::HidePassed => app.toggle_hide_passed(),
+        Action::MinTime => app.cycle_min_time(),
+        Action::ClassFilter => app.start_class_filter(),
+        Action::Quarantine => app.toggle_count_quarantined(),
+        Action::Columns if app.view == View::TestList => app.toggle_columns(),
+        Action::Sort if matches!(app.view, View::TestList | View::Tree) => app.cycle_test_sort(),
+        Action::Sort if app.view == View::SuiteList => app.cycle_suite_sort(),
+        Action::Tree => app.toggle_tree_mode(),
+        Action::Expand if app.view == View::Tree => app.toggle_expand(),
+        Action::Expand if app.view == View::Groups => app.toggle_group(),
+        Action::Expand if app.view == View::Skips => app.toggle_skip_group(),
+        Action::Expand if app.view == View::Packages => app.toggle_package(),
+        Action::Expand if app.view == View::TestList => repeat(app, App::toggle_pick),
+        Action::PickRange if app.view == View::TestList => app.pick_range(),
+        Action::CopyFailure if app.view == View::TestList && !app.picked.is_empty() => {
+            copy_picked_names(app)
+        }
+        Action::CopyDetail if app.view == View::TestList && !app.picked.is_empty() => {
+            copy_picked_details(app)
+        }
+        Action::CopyFailure if app.view == View::SuiteList => copy_suite_table(app),
+        Action::Export if app.view == View::TestList => app.start_export(),
+        Action::Export if app.view == View::TestDetail => app.start_export_detail(),
+        Action::NextMatch if app.has_search() => repeat(app, App::next_match),
+        Action::PrevMatch if app.has_search() => repeat(app, App::prev_match),
+
+        Action::GrowSidebar => repeat(app, App::grow_sidebar),
+        Action::ShrinkSidebar => repeat(app, App::shrink_sidebar),
+        Action::ToggleSidebar => app.toggle_sidebar(),
+        Action::SortFiles if app.multi_file => app.cycle_file_sort(),
+        Action::DropFile if app.multi_file => app.drop_current_file(),
+        Action::AddFiles => app.start_add_files(),
+        Action::FilterFiles => app.start_sidebar_filter(),
+        Action::NextFile => repeat(app, App::next_file),
+        Action::PrevFile => repeat(app, App::prev_file),
+        Action::ToggleWatch => app.toggle_watching(),
+        Action::Mark => app.toggle_mark(),
+        Action::Baseline => app.toggle_baseline(),
+        Action::Diff => app.open_diff(),
+        Action::History => app.open_history(),
+        Action::Slowest => app.open_slowest(),
+        Action::Groups => app.open_groups(),
+        Action::Skips => app.open_skips(),
+        Action::Packages => app.open_packages(),
+        Action::Durations => app.open_durations(),
+        Action::Properties => app.open_properties(),
+        Action::SuiteStats => app.open_suite_stats(),
+        Action::CompareSuite => app.open_suite_compare(),
+        Action::SuiteOutput => app.open_suite_output(),
+        Action::NextTab if app.view == View::TestList => app.next_tab(),
+        Action::PrevTab if app.view == View::TestList => app.prev_tab(),
+
+        _ => {}
+    }
+}
+
+fn handle_search_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Esc => app.clear_search(),
+        KeyCode::Enter => app.confirm_search(),
+        KeyCode::Backspace => app.search_pop(),
+        KeyCode::Char(c) => app.search_push(c),
+        _ => {}
+    }
+}
+
+fn handle_sidebar_filter_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Esc => app.clear_sidebar_filter(),
+        KeyCode::Enter => app.confirm_sidebar_filter(),
+        KeyCode::Backspace => app.sidebar_filter_pop(),
+        KeyCode::Char(c) => app.sidebar_filter_push(c),
+        _ => {}
+    }
+}
+
+fn handle_class_filter_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Esc => app.input_mode = InputMode::Normal,
+        KeyCode::Enter => app.confirm_class_filter(),
+        KeyCode::Backspace => {
+            app.class_input.pop();
+        }
+        KeyCode::Char(c) => app.class_input.push(c),
+        _ => {}
+    }
+}
+
+fn handle_go_to_line_key(app: &mut App, key: KeyEvent) {
     match key.code {
-        KeyCode::Char('q') => app.should_quit = true,
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
             app.should_quit = true;
         }
+        KeyCode::Esc => app.input_mode = InputMode::Normal,
+        KeyCode::Enter => {
+            app.input_mode = InputMode::Normal;
+            let input = std::mem::take(&mut app.line_input);
+            go_to_output_line(app, &input);
+        }
+        KeyCode::Backspace => {
+            app.line_input.pop();
+        }
+        KeyCode::Char(c) if c.is_ascii_alphanumeric() => app.line_input.push(c),
+        _ => {}
+    }
+}
+
+/// Jumps to a line of one section of the detail: `42` for line 42 of the
+/// focused pane (stdout in the combined layout), or `o42`, `e42`, `t42`
+/// for stdout, stderr or the stack trace.
+fn go_to_output_line(app: &mut App, input: &str) {
+    let (pane, digits) = match input.chars().next() {
+        Some('o') => (DetailPane::Stdout, &input[1..]),
+        Some('e') => (DetailPane::Stderr, &input[1..]),
+        Some('t') => (DetailPane::Main, &input[1..]),
+        _ if app.output_layout == OutputLayout::Combined => (DetailPane::Stdout, input),
+        _ => (app.detail_focus, input),
+    };
+    let Ok(line) = digits.parse::<usize>() else {
+        app.status_message = Some(format!("Not a line number: {}", input));
+        return;
+    };
+    let Some(tc) = app.current_test() else {
+        return;
+    };
+    let (_, sections) = ui::detail_sections(
+        tc,
+        &app.theme,
+        app.render_ansi,
+        app.line_numbers,
+        app.pretty,
+    );
+    let split = app.output_layout != OutputLayout::Combined;
+    let (start, name) = match pane {
+        DetailPane::Main => (sections.trace, "stack trace"),
+        DetailPane::Stdout => (sections.stdout.map(|s| if split { 0 } else { s }), "stdout"),
+        DetailPane::Stderr => (sections.stderr.map(|s| if split { 0 } else { s }), "stderr"),
+    };
+    match start {
+        Some(start) => app.scroll_detail_to(pane, start + line.saturating_sub(1)),
+        None => app.status_message = Some(format!("This test has no {}", name)),
+    }
+}
+
+/// Scrolls to the next (or previous) section heading of the detail view.
+/// When the output is split off, its panes are the last sections.
+fn jump_section(app: &mut App, forward: bool) {
+    let Some(tc) = app.current_test() else {
+        return;
+    };
+    let targets: Vec<(DetailPane, usize)> = if app.output_layout == OutputLayout::Combined {
+        let (_, sections) = ui::detail_sections(
+            tc,
+            &app.theme,
+            app.render_ansi,
+            app.line_numbers,
+            app.pretty,
+        );
+        sections
+            .headings
+            .into_iter()
+            .map(|row| (DetailPane::Main, row))
+            .collect()
+    } else {
+        let (_, sections) = ui::summary_lines(tc, &app.theme, app.line_numbers, app.pretty);
+        let has_output =
+            |output: &Option<String>| output.as_deref().is_some_and(|o| !o.trim().is_empty());
+        let mut targets: Vec<_> = sections
+            .headings
+            .into_iter()
+            .map(|row| (DetailPane::Main, row))
+            .collect();
+        if has_output(&tc.system_out) {
+            targets.push((DetailPane::Stdout, 0));
+        }
+        if has_output(&tc.system_err) {
+            targets.push((DetailPane::Stderr, 0));
+        }
+        targets
+    };
 
-        KeyCode::Char('j') | KeyCode::Down => app.select_next(),
-        KeyCode::Char('k') | KeyCode::Up => app.select_prev(),
+    let order = |pane: DetailPane| pane as usize;
+    let current = (order(app.detail_focus), app.detail_scroll() as usize);
+    let target = if forward {
+        targets
+            .into_iter()
+            .find(|&(pane, row)| (order(pane), row) > current)
+    } else {
+        targets
+            .into_iter()
+            .rev()
+            .find(|&(pane, row)| (order(pane), row) < current)
+    };
+    match target {
+        Some((pane, row)) => app.scroll_detail_to(pane, row),
+        None => {
+            app.status_message = Some(
+                if forward {
+                    "No more sections below"
+                } else {
+                    "No more sections above"
+                }
+                .into(),
+            )
+        }
+    }
+}
 
-        KeyCode::Char('g') | KeyCode::Home => app.select_first(),
-        KeyCode::Char('G') | KeyCode::End => app.select_last(),
+/// The current test's stack frames, with their rows in the main pane.
+fn detail_frames(app: &App) -> Vec<(usize, editor::Frame)> {
+    app.current_test().map_or_else(Vec::new, |tc| {
+        ui::summary_lines(tc, &app.theme, app.line_numbers, app.pretty)
+            .1
+            .frames
+    })
+}
 
-        KeyCode::PageDown => app.page_down(),
-        KeyCode::PageUp => app.page_up(),
+fn step_frame(app: &mut App, forward: bool) {
+    let Some(current) = app.selected_frame else {
+        return;
+    };
+    let frames = detail_frames(app);
+    let index = if forward {
+        (current + 1).min(frames.len().saturating_sub(1))
+    } else {
+        current.saturating_sub(1)
+    };
+    if let Some(&(row, _)) = frames.get(index) {
+        app.select_frame(index, row);
+    }
+}
 
-        KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => app.enter(),
-        KeyCode::Esc | KeyCode::Char('h') | KeyCode::Left | KeyCode::Backspace => app.go_back(),
+/// Enter in the detail view: the first press picks the first frame whose
+/// file can be found, the next opens the picked frame in the editor.
+fn open_frame(app: &mut App) {
+    let frames = detail_frames(app);
+    let test_file = app.current_test().and_then(|tc| tc.file.clone());
+    let resolve = |frame| editor::resolve(frame, &app.source_roots, test_file.as_deref());
+    match app.selected_frame {
+        None => {
+            if frames.is_empty() {
+                app.status_message = Some("No stack frames in this test".into());
+                return;
+            }
+            let index = frames
+                .iter()
+                .position(|(_, frame)| resolve(frame).is_some())
+                .unwrap_or(0);
+            app.select_frame(index, frames[index].0);
+        }
+        Some(index) => {
+            let Some((_, frame)) = frames.get(index) else {
+                return;
+            };
+            match resolve(frame) {
+                Some(location) => app.pending_edit = Some(location),
+                None => {
+                    app.status_message =
+                        Some(format!("Can't find {} under the source roots", frame.file))
+                }
+            }
+        }
+    }
+}
 
-        KeyCode::Tab => app.next_file(),
-        KeyCode::BackTab => app.prev_file(),
+fn handle_export_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Esc => app.input_mode = InputMode::Normal,
+        KeyCode::Enter => {
+            app.input_mode = InputMode::Normal;
+            if app.export_detail {
+                export_detail(app);
+            } else {
+                export_picked(app);
+            }
+        }
+        KeyCode::Backspace => {
+            app.export_input.pop();
+        }
+        KeyCode::Char(c) => app.export_input.push(c),
+        _ => {}
+    }
+}
 
+fn handle_add_files_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Esc => app.input_mode = InputMode::Normal,
+        KeyCode::Enter => {
+            app.input_mode = InputMode::Normal;
+            app.add_files();
+        }
+        KeyCode::Tab => app.complete_add_input(),
+        KeyCode::Backspace => {
+            app.add_input.pop();
+        }
+        KeyCode::Char(c) => app.add_input.push(c),
         _ => {}
     }
 }
+
+/// `classname.name` for each picked test, one per line.
+fn picked_names(app: &App) -> String {
+    app.picked_tests()
+        .iter()
+        .map(|tc| IdFormat::Dotted.format(tc))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The detail of each picked test, separated by blank lines.
+fn picked_details(app: &App) -> String {
+    app.picked_tests()
+        .iter()
+        .map(|tc| ui::detail_text(tc, &app.theme, app.render_ansi))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn copy_suite_table(app: &mut App) {
+    let suites = &app.current_file().data.suites;
+    let visible = app.visible_suites();
+    let table = summary::suite_table(visible.iter().map(|&i| &suites[i]));
+    let count = visible.len();
+    app.status_message = Some(match app.clipboard.copy(&table) {
+        Ok(()) => format!("Copied a table of {} suites to clipboard", count),
+        Err(e) => format!("Copy failed: {}", e),
+    });
+}
+
+/// Whether an export path asks for Markdown rather than plain text.
+fn is_markdown(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+fn copy_picked_names(app: &mut App) {
+    let count = app.picked.len();
+    let names = picked_names(app);
+    app.status_message = Some(match app.clipboard.copy(&names) {
+        Ok(()) => format!("Copied {} test names to clipboard", count),
+        Err(e) => format!("Copy failed: {}", e),
+    });
+}
+
+fn copy_picked_details(app: &mut App) {
+    let count = app.picked.len();
+    let details = picked_details(app);
+    app.status_message = Some(match app.clipboard.copy(&details) {
+        Ok(()) => format!("Copied {} test details to clipboard", count),
+        Err(e) => format!("Copy failed: {}", e),
+    });
+}
+
+fn export_picked(app: &mut App) {
+    let path = app.export_input.trim().to_string();
+    if path.is_empty() {
+        return;
+    }
+    let count = app.picked.len();
+    let text = if is_markdown(&path) {
+        app.picked_tests()
+            .into_iter()
+            .map(ui::detail_markdown)
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        picked_details(app) + "\n"
+    };
+    app.status_message = Some(match std::fs::write(&path, text) {
+        Ok(()) => format!("Exported {} tests to {}", count, path),
+        Err(e) => format!("Export failed: {}", e),
+    });
+}
+
+fn export_detail(app: &mut App) {
+    let path = app.export_input.trim().to_string();
+    let Some(tc) = app.current_test().filter(|_| !path.is_empty()) else {
+        return;
+    };
+    let text = if is_markdown(&path) {
+        ui::detail_markdown(tc)
+    } else {
+        ui::detail_text(tc, &app.theme, app.render_ansi) + "\n"
+    };
+    app.status_message = Some(match std::fs::write(&path, text) {
+        Ok(()) => format!("Exported {} to {}", tc.name, path),
+        Err(e) => format!("Export failed: {}", e),
+    });
+}
+
+fn copy_failure(app: &mut App) {
+    let Some(text) = app.current_test().and_then(|tc| tc.failure_text()) else {
+        app.status_message = Some("Nothing to copy: test has no failure or error".into());
+        return;
+    };
+    app.status_message = Some(match app.clipboard.copy(&text) {
+        Ok(()) => "Copied failure to clipboard".into(),
+        Err(e) => format!("Copy failed: {}", e),
+    });
+}
+
+fn copy_detail(app: &mut App) {
+    let Some(text) = app
+        .current_test()
+        .map(|tc| ui::detail_text(tc, &app.theme, app.render_ansi))
+    else {
+        return;
+    };
+    app.status_message = Some(match app.clipboard.copy(&text) {
+        Ok(()) => "Copied test detail to clipboard".into(),
+        Err(e) => format!("Copy failed: {}", e),
+    });
+}
+
+fn request_edit(app: &mut App) {
+    match app.cursor_test().and_then(editor::source_location) {
+        Some(location) => app.pending_edit = Some(location),
+        None => app.status_message = Some("No source file recorded for this test".into()),
+    }
+}
+
+fn handle_help_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('j') | KeyCode::Down => app.help_scroll = app.help_scroll.saturating_add(1),
+        KeyCode::Char('k') | KeyCode::Up => app.help_scroll = app.help_scroll.saturating_sub(1),
+        KeyCode::Char('?') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => app.close_help(),
+        _ => {}
+    }
+}
+
+fn handle_properties_key(app: &mut App, key: KeyEvent) {
+    let scroll = &mut app.properties_scroll;
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('j') | KeyCode::Down => *scroll = scroll.saturating_add(1),
+        KeyCode::Char('k') | KeyCode::Up => *scroll = scroll.saturating_sub(1),
+        KeyCode::Char('p') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+            app.close_properties()
+        }
+        _ => {}
+    }
+}
+
+fn handle_suite_output_key(app: &mut App, key: KeyEvent) {
+    let scroll = &mut app.suite_output_scroll;
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('j') | KeyCode::Down => *scroll = scroll.saturating_add(1),
+        KeyCode::Char('k') | KeyCode::Up => *scroll = scroll.saturating_sub(1),
+        KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+        KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+        KeyCode::Char('O') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+            app.close_suite_output()
+        }
+        _ => {}
+    }
+}
+
+fn handle_suite_stats_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('i') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+            app.close_suite_stats()
+        }
+        _ => {}
+    }
+}
+
+fn handle_suite_compare_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('A') | KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+            app.close_suite_compare()
+        }
+        _ => {}
+    }
+}
+
+fn handle_copy_id_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.should_quit = true;
+        }
+        KeyCode::Char('j') | KeyCode::Down => app.step_id_format(true),
+        KeyCode::Char('k') | KeyCode::Up => app.step_id_format(false),
+        KeyCode::Char(c @ '1'..='9') => {
+            let index = c as usize - '1' as usize;
+            if let Some(&format) = IdFormat::ALL.get(index) {
+                app.id_format = format;
+                copy_test_id(app);
+            }
+        }
+        KeyCode::Enter => copy_test_id(app),
+        KeyCode::Char('q') | KeyCode::Esc => app.input_mode = InputMode::Normal,
+        _ => {}
+    }
+}
+
+/// Copies the test under the cursor's identifier in the chosen format.
+fn copy_test_id(app: &mut App) {
+    app.input_mode = InputMode::Normal;
+    let Some(id) = app.cursor_test().map(|tc| app.id_format.format(tc)) else {
+        return;
+    };
+    app.status_message = Some(match app.clipboard.copy(&id) {
+        Ok(()) => format!("Copied {}", id),
+        Err(e) => format!("Copy failed: {}", e),
+    });
+}
+
+fn handle_finder_key(app: &mut App, key: KeyEvent) {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    match key.code {
+        KeyCode::Char('c') if ctrl => app.should_quit = true,
+        KeyCode::Char('n') if ctrl => app.finder_next(),
+        KeyCode::Char('p') if ctrl => app.finder_prev(),
+        KeyCode::Down => app.finder_next(),
+        KeyCode::Up => app.finder_prev(),
+        KeyCode::Esc => app.close_finder(),
+        KeyCode::Enter => app.finder_accept(),
+        KeyCode::Backspace => app.finder_pop(),
+        KeyCode::Char(c) => app.finder_push(c),
+        _ => {}
+    }
+}
+
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent, screen: Rect) {
+    if app.input_mode != InputMode::Normal {
+        return;
+    }
+
+    let areas = ui::areas(screen, app);
+    let position = Position::new(mouse.column, mouse.row);
+
+    match mouse.kind {
+        MouseEventKind::ScrollDown => {
+            for _ in 0..scroll_step(app) {
+                app.select_next();
+            }
+        }
+        MouseEventKind::ScrollUp => {
+            for _ in 0..scroll_step(app) {
+                app.select_prev();
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(sidebar) = areas.sidebar.filter(|a| a.contains(position)) {
+                if let Some(row) = clicked_row(sidebar, position, app.sidebar_selected()) {
+                    app.select_sidebar(row);
+                }
+                return;
+            }
+
+            if !areas.content.contains(position) || app.view == View::TestDetail {
+                return;
+            }
+            let mut list = ui::list_area(areas.content, app);
+            if app.view == View::TestList {
+                // The table's header row sits between the border and the
+                // first test.
+                if position.y == list.y + 1 {
+                    if let Some(sort) = ui::test_sort_at(list, position.x, app) {
+                        app.sort_tests_by(sort);
+                    }
+                    return;
+                }
+                list.y += 1;
+                list.height = list.height.saturating_sub(1);
+            }
+            let Some(row) = clicked_row(list, position, app.selected_row()) else {
+                return;
+            };
+            if row >= app.row_count() {
+                return;
+            }
+
+            let now = Instant::now();
+            let double = app
+                .last_click
+                .is_some_and(|(at, last_row)| last_row == row && now - at < DOUBLE_CLICK);
+            app.select_row(row);
+            if double {
+                app.last_click = None;
+                app.enter();
+            } else {
+                app.last_click = Some((now, row));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scroll_step(app: &App) -> usize {
+    if app.view == View::TestDetail {
+        3
+    } else {
+        1
+    }
+}
+
+/// Maps a click inside a bordered list to an item index. Lists are
+/// rendered with a fresh state each frame, so ratatui scrolls just far
+/// enough to keep `selected` on the last visible row.
+fn clicked_row(area: Rect, position: Position, selected: usize) -> Option<usize> {
+    let top = area.y + 1;
+    let height = area.height.saturating_sub(2) as usize;
+    if position.y < top || position.y >= top + height as u16 {
+        return None;
+    }
+    let offset = selected.saturating_sub(height.saturating_sub(1));
+    Some(offset + (position.y - top) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::FileReport;
+    use crate::keymap::KeyMap;
+    use crate::theme::Theme;
+
+    fn report(filename: &str, n: usize) -> FileReport {
+        let cases: String = (0..n)
+            .map(|i| format!(r#"<testcase classname="C" name="t{:02}"/>"#, i))
+            .collect();
+        let xml = format!(r#"<testsuite name="s" tests="{}">{}</testsuite>"#, n, cases);
+        FileReport::from_xml(filename, &xml)
+    }
+
+    fn app(files: Vec<FileReport>) -> App {
+        let keymap = KeyMap::new(&Default::default()).unwrap();
+        App::new(files, Theme::no_color(), keymap)
+    }
+
+    fn app_with_tests(n: usize) -> App {
+        app(vec![report("r.xml", n)])
+    }
+
+    fn press(app: &mut App, c: char) {
+        handle_key(app, KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn count_prefix_moves_in_test_list() {
+        let mut app = app_with_tests(10);
+        app.enter();
+        assert_eq!(app.view, View::TestList);
+        let start = app.selected_test;
+        press(&mut app, '5');
+        press(&mut app, 'j');
+        assert_eq!(app.selected_test, start + 5);
+        assert_eq!(app.pending_count, None);
+    }
+
+    #[test]
+    fn digits_pick_files_in_suite_list() {
+        let mut app = app(vec![report("a.xml", 3), report("b.xml", 3)]);
+        assert_eq!(app.view, View::SuiteList);
+        press(&mut app, '2');
+        assert_eq!(app.current_file().filename, "b.xml");
+        press(&mut app, '1');
+        assert_eq!(app.current_file().filename, "a.xml");
+        assert_eq!(app.pending_count, None);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::app::FileReport;
+use crate::editor;
+use crate::test_id::{self, IdFormat};
+use anyhow::Result;
+use junit_parser::{TestCase, TestStatus};
+use serde::Serialize;
+
+/// One entry of a GitLab code quality report, in the Code Climate format
+/// GitLab reads from `artifacts:reports:codequality`.
+#[derive(Serialize)]
+struct Issue {
+    description: String,
+    check_name: &'static str,
+    fingerprint: String,
+    severity: &'static str,
+    location: Location,
+}
+
+#[derive(Serialize)]
+struct Location {
+    path: String,
+    lines: Lines,
+}
+
+#[derive(Serialize)]
+struct Lines {
+    begin: u32,
+}
+
+/// A GitLab code quality report with an issue for every failed or
+/// errored test, so merge requests show them inline. Each issue points at
+/// the test's source when a stack frame or its `file` attribute names a
+/// file that exists under the working directory, and at the report
+/// otherwise.
+pub fn code_quality(files: &[FileReport]) -> Result<String> {
+    let issues: Vec<Issue> = files
+        .iter()
+        .flat_map(|f| f.data.all_cases().map(move |(_, tc)| (f, tc)))
+        .filter(|(_, tc)| matches!(tc.status(), TestStatus::Failed | TestStatus::Errored))
+        .map(|(file, tc)| issue(file, tc))
+        .collect();
+    Ok(serde_json::to_string_pretty(&issues)?)
+}
+
+fn issue(file: &FileReport, tc: &TestCase) -> Issue {
+    let name = IdFormat::Dotted.format(tc);
+    let message = tc.failure_line();
+    let outcome = match tc.status() {
+        TestStatus::Errored => "errored",
+        _ => "failed",
+    };
+    let description = match message {
+        Some(message) => format!("{} {}: {}", name, outcome, message),
+        None => format!("{} {}", name, outcome),
+    };
+    let (path, line) =
+        editor::failure_location(tc).unwrap_or_else(|| (editor::repo_relative(&file.path), 1));
+    Issue {
+        description,
+        check_name: match tc.status() {
+            TestStatus::Errored => "test-error",
+            _ => "test-failure",
+        },
+        // Stable across runs, so GitLab can tell fixed failures from new
+        // ones between pipelines.
+        fingerprint: test_id::fingerprint(&file.filename, tc),
+        severity: "major",
+        location: Location {
+            path,
+            lines: Lines { begin: line },
+        },
+    }
+}
@@ -0,0 +1,182 @@
+use crate::theme::Theme;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+
+/// Package prefixes of frames that belong to the runtime or test framework
+/// rather than the code under test; these are dimmed.
+const LIBRARY_FRAMES: &[&str] = &[
+    "java.",
+    "javax.",
+    "jdk.",
+    "sun.",
+    "kotlin.",
+    "scala.",
+    "org.junit.",
+    "junit.",
+    "org.testng.",
+    "org.apache.maven.",
+    "org.gradle.",
+];
+
+/// Styles one line of a failure or error body, recognising Java/Kotlin
+/// frames (`at com.foo.Bar.baz(Bar.java:42)`), Python frames
+/// (`File "x.py", line 3, in f`), exception headers and `path:line:`
+/// locations as printed by C/C++ test frameworks. Anything else is
+/// returned unstyled. Two spaces of indent are prepended to match the
+/// rest of the detail view.
+pub fn trace_line(line: &str, theme: &Theme) -> Line<'static> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let mut spans = vec![Span::raw(format!("  {}", indent))];
+
+    if let Some(frame) = trimmed.strip_prefix("at ") {
+        spans.extend(java_frame(frame, theme));
+    } else if let Some(frame) = trimmed.strip_prefix("File \"") {
+        spans.extend(python_frame(frame, theme));
+    } else if let Some(rest) = trimmed.strip_prefix("Caused by: ") {
+        spans.push(Span::styled(
+            "Caused by: ",
+            Style::default().fg(theme.muted),
+        ));
+        spans.extend(
+            exception_header(rest, theme).unwrap_or_else(|| vec![Span::raw(rest.to_string())]),
+        );
+    } else if let Some(header) = exception_header(trimmed, theme) {
+        spans.extend(header);
+    } else if let Some(location) = path_location(trimmed, theme) {
+        spans.extend(location);
+    } else {
+        spans.push(Span::raw(trimmed.to_string()));
+    }
+
+    Line::from(spans)
+}
+
+fn java_frame(frame: &str, theme: &Theme) -> Vec<Span<'static>> {
+    let prefix = Span::styled("at ", Style::default().fg(theme.muted));
+    let Some(open) = frame.find('(') else {
+        return vec![
+            prefix,
+            Span::styled(frame.to_string(), Style::default().fg(theme.symbol)),
+        ];
+    };
+
+    let method = &frame[..open];
+    // Module-qualified frames look like `java.base/jdk.internal...`.
+    let qualified = method.rsplit('/').next().unwrap_or(method);
+    if LIBRARY_FRAMES.iter().any(|p| qualified.starts_with(p)) {
+        return vec![
+            prefix,
+            Span::styled(frame.to_string(), Style::default().fg(theme.muted)),
+        ];
+    }
+
+    let location = &frame[open + 1..];
+    let location = location.strip_suffix(')').unwrap_or(location);
+    vec![
+        prefix,
+        Span::styled(method.to_string(), Style::default().fg(theme.symbol)),
+        Span::raw("("),
+        Span::styled(location.to_string(), Style::default().fg(theme.location)),
+        Span::raw(")"),
+    ]
+}
+
+fn python_frame(frame: &str, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = vec![Span::styled("File \"", Style::default().fg(theme.muted))];
+    let Some(close) = frame.find('"') else {
+        spans.push(Span::raw(frame.to_string()));
+        return spans;
+    };
+
+    spans.push(Span::styled(
+        frame[..close].to_string(),
+        Style::default().fg(theme.location),
+    ));
+    let rest = &frame[close..];
+
+    // rest looks like `", line 12, in func`
+    match rest.split_once("line ") {
+        Some((before, after)) => {
+            let digits_end = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            spans.push(Span::styled(
+                format!("{}line ", before),
+                Style::default().fg(theme.muted),
+            ));
+            spans.push(Span::styled(
+                after[..digits_end].to_string(),
+                Style::default().fg(theme.line_number),
+            ));
+            let tail = &after[digits_end..];
+            match tail.split_once("in ") {
+                Some((sep, func)) => {
+                    spans.push(Span::styled(
+                        format!("{}in ", sep),
+                        Style::default().fg(theme.muted),
+                    ));
+                    spans.push(Span::styled(
+                        func.to_string(),
+                        Style::default().fg(theme.symbol),
+                    ));
+                }
+                None => spans.push(Span::raw(tail.to_string())),
+            }
+        }
+        None => spans.push(Span::raw(rest.to_string())),
+    }
+    spans
+}
+
+/// `com.foo.SomeException: message` or a bare `ValueError`.
+fn exception_header(text: &str, theme: &Theme) -> Option<Vec<Span<'static>>> {
+    let end = text
+        .find(|c: char| c == ':' || c.is_whitespace())
+        .unwrap_or(text.len());
+    let name = &text[..end];
+    if !is_exception_name(name) {
+        return None;
+    }
+    Some(vec![
+        Span::styled(name.to_string(), Style::default().fg(theme.failed).bold()),
+        Span::raw(text[end..].to_string()),
+    ])
+}
+
+fn is_exception_name(name: &str) -> bool {
+    const SUFFIXES: &[&str] = &["Error", "Exception", "Throwable", "Failure", "Fault"];
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '$'));
+    let last = name.rsplit('.').next().unwrap_or(name);
+    valid_chars
+        && last.starts_with(|c: char| c.is_uppercase())
+        && SUFFIXES.iter().any(|s| last.ends_with(s))
+}
+
+/// `path/to/file.cpp:72: rest` — the path must contain a `.` or `/` so
+/// plain `word:` prefixes are left alone.
+fn path_location(text: &str, theme: &Theme) -> Option<Vec<Span<'static>>> {
+    let colon = text.find(':')?;
+    let path = &text[..colon];
+    if path.is_empty() || path.contains(char::is_whitespace) || !path.contains(['.', '/']) {
+        return None;
+    }
+    let after = &text[colon + 1..];
+    let digits_end = after
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(after.len());
+    if digits_end == 0 {
+        return None;
+    }
+    Some(vec![
+        Span::styled(path.to_string(), Style::default().fg(theme.location)),
+        Span::raw(":"),
+        Span::styled(
+            after[..digits_end].to_string(),
+            Style::default().fg(theme.line_number),
+        ),
+        Span::raw(after[digits_end..].to_string()),
+    ])
+}
@@ -0,0 +1,99 @@
+use crate::app::FileReport;
+use std::collections::HashMap;
+
+/// Totals for one run (one report file).
+pub struct RunPoint {
+    pub filename: String,
+    pub timestamp: Option<String>,
+    pub passed: u64,
+    /// Failures plus errors.
+    pub failed: u64,
+    pub skipped: u64,
+    pub time: f64,
+}
+
+/// One suite's results across runs, aligned with [`History::runs`];
+/// `None` where the suite is missing from a run.
+pub struct SuiteTrend {
+    pub name: String,
+    pub points: Vec<Option<SuitePoint>>,
+}
+
+#[derive(Clone, Copy)]
+pub struct SuitePoint {
+    pub passed: u64,
+    pub failed: u64,
+    pub time: f64,
+}
+
+pub struct History {
+    pub runs: Vec<RunPoint>,
+    pub suites: Vec<SuiteTrend>,
+}
+
+/// Treats each file as one run and orders them by timestamp. Files without
+/// a timestamp keep their filename order after the timestamped ones.
+pub fn build(files: &[FileReport]) -> History {
+    let mut order: Vec<&FileReport> = files.iter().collect();
+    order.sort_by(|a, b| match (a.data.timestamp(), b.data.timestamp()) {
+        (Some(x), Some(y)) => x.cmp(y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.filename.cmp(&b.filename),
+    });
+
+    let runs = order
+        .iter()
+        .map(|f| RunPoint {
+            filename: f.filename.clone(),
+            timestamp: f.data.timestamp().map(str::to_string),
+            passed: f.data.total_passed(),
+            failed: f.data.total_failures() + f.data.total_errors(),
+            skipped: f.data.total_skipped(),
+            time: f.data.suites.iter().map(suite_time).sum(),
+        })
+        .collect();
+
+    let mut suites: Vec<SuiteTrend> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (run, file) in order.iter().enumerate() {
+        for suite in &file.data.suites {
+            let i = *index.entry(&suite.name).or_insert_with(|| {
+                suites.push(SuiteTrend {
+                    name: suite.name.clone(),
+                    points: vec![None; order.len()],
+                });
+                suites.len() - 1
+            });
+            suites[i].points[run] = Some(SuitePoint {
+                passed: suite.passed(),
+                failed: suite.failures + suite.errors,
+                time: suite_time(suite),
+            });
+        }
+    }
+
+    History { runs, suites }
+}
+
+/// The suite's `time` attribute, or the sum of its cases' times.
+fn suite_time(suite: &junit_parser::TestSuite) -> f64 {
+    suite
+        .time
+        .unwrap_or_else(|| suite.test_cases.iter().filter_map(|tc| tc.time).sum())
+}
+
+/// Renders values as a row of block characters scaled to the largest one,
+/// with a space for missing points.
+pub fn sparkline(values: &[Option<f64>]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().flatten().cloned().fold(0.0, f64::max);
+    values
+        .iter()
+        .map(|v| match v {
+            None => ' ',
+            Some(_) if max <= 0.0 => BARS[0],
+            Some(v) => BARS[((v / max) * (BARS.len() - 1) as f64).round() as usize],
+        })
+        .collect()
+}
@@ -0,0 +1,208 @@
+use crate::app::FileReport;
+use crate::theme::Theme;
+use crate::{ansi, highlight};
+use junit_parser::{TestCase, TestStatus, TestSuite};
+use ratatui::style::{Color, Modifier};
+
+/// A self-contained HTML page of `files` for `ratunit export --html`: the
+/// totals, then every suite as a `<details>` block, opened when it has
+/// failures, with the failing tests' traces highlighted as in the detail
+/// view.
+pub fn report(files: &[FileReport]) -> String {
+    let tests: u64 = files.iter().map(|f| f.data.total_tests()).sum();
+    let passed: u64 = files.iter().map(|f| f.data.total_passed()).sum();
+    let failed: u64 = files.iter().map(|f| f.data.total_failures()).sum();
+    let errors: u64 = files.iter().map(|f| f.data.total_errors()).sum();
+    let skipped: u64 = files.iter().map(|f| f.data.total_skipped()).sum();
+    let title = match files {
+        [file] => file.filename.clone(),
+        _ => format!("{} reports", files.len()),
+    };
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{} — ratunit</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape(&title),
+        STYLE,
+        escape(&title)
+    );
+    html.push_str("<p class=\"totals\">");
+    html.push_str(&format!("<span>{} tests</span>", tests));
+    for (n, label, class) in [
+        (passed, "passed", "passed"),
+        (failed, "failed", "failed"),
+        (errors, "errors", "errored"),
+        (skipped, "skipped", "skipped"),
+    ] {
+        html.push_str(&format!(
+            "<span class=\"{}\">{} {}</span>",
+            if n > 0 { class } else { "zero" },
+            n,
+            label
+        ));
+    }
+    html.push_str("</p>\n");
+
+    for file in files {
+        if files.len() > 1 {
+            html.push_str(&format!("<h2>{}</h2>\n", escape(&file.filename)));
+        }
+        for suite in &file.data.suites {
+            push_suite(&mut html, suite);
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn push_suite(html: &mut String, suite: &TestSuite) {
+    let failing = suite.failures + suite.errors;
+    html.push_str(&format!(
+        "<details class=\"suite\"{}>\n<summary><span class=\"{}\">{}</span> {} \
+         <span class=\"counts\">{} tests, {} passed, {} failed, {} skipped{}</span></summary>\n",
+        if failing > 0 { " open" } else { "" },
+        if failing > 0 { "failed" } else { "passed" },
+        if failing > 0 { "✗" } else { "✓" },
+        escape(&suite.name),
+        suite.tests,
+        suite.passed(),
+        failing,
+        suite.skipped.unwrap_or(0),
+        suite
+            .time
+            .map(|t| format!(", {:.2}s", t))
+            .unwrap_or_default()
+    ));
+    html.push_str("<table>\n");
+    for tc in &suite.test_cases {
+        push_case(html, tc);
+    }
+    html.push_str("</table>\n</details>\n");
+}
+
+fn push_case(html: &mut String, tc: &TestCase) {
+    let (badge, class) = match tc.status() {
+        TestStatus::Passed => ("PASS", "passed"),
+        TestStatus::Failed => ("FAIL", "failed"),
+        TestStatus::Errored => ("ERR", "errored"),
+        TestStatus::Skipped => ("SKIP", "skipped"),
+    };
+    let name = match &tc.classname {
+        Some(class) => format!("{}.<b>{}</b>", escape(class), escape(&tc.name)),
+        None => format!("<b>{}</b>", escape(&tc.name)),
+    };
+    html.push_str(&format!(
+        "<tr><td class=\"{}\">{}</td><td>{}</td><td class=\"time\">{}</td></tr>\n",
+        class,
+        badge,
+        name,
+        tc.time.map(|t| format!("{:.3}s", t)).unwrap_or_default()
+    ));
+
+    let mut details = String::new();
+    if let Some(text) = tc.failure_text() {
+        details.push_str("<pre class=\"trace\">");
+        let theme = Theme::light();
+        for line in ansi::strip(&text).lines() {
+            push_trace_line(&mut details, line, &theme);
+        }
+        details.push_str("</pre>\n");
+    }
+    if let Some(reason) = tc.skipped.as_ref().and_then(|s| s.reason()) {
+        details.push_str(&format!("<p class=\"reason\">{}</p>\n", escape(reason)));
+    }
+    for (label, output) in [
+        ("System out", &tc.system_out),
+        ("System err", &tc.system_err),
+    ] {
+        let Some(output) = output.as_deref().filter(|o| !o.trim().is_empty()) else {
+            continue;
+        };
+        details.push_str(&format!(
+            "<details><summary>{}</summary><pre>{}</pre></details>\n",
+            label,
+            escape(&ansi::strip(output))
+        ));
+    }
+    if !details.is_empty() {
+        html.push_str(&format!(
+            "<tr class=\"detail\"><td></td><td colspan=\"2\">{}</td></tr>\n",
+            details
+        ));
+    }
+}
+
+/// One trace line as [`highlight::trace_line`] styles it, with its colors
+/// as inline CSS.
+fn push_trace_line(html: &mut String, line: &str, theme: &Theme) {
+    let styled = highlight::trace_line(line, theme);
+    for (i, span) in styled.spans.iter().enumerate() {
+        // trace_line indents for the detail view; the page doesn't need it.
+        let content = match i {
+            0 => span.content.strip_prefix("  ").unwrap_or(&span.content),
+            _ => &span.content,
+        };
+        let mut css = Vec::new();
+        if let Some(color) = span.style.fg.and_then(css_color) {
+            css.push(format!("color:{}", color));
+        }
+        if span.style.add_modifier.contains(Modifier::BOLD) {
+            css.push("font-weight:bold".to_string());
+        }
+        if css.is_empty() {
+            html.push_str(&escape(content));
+        } else {
+            html.push_str(&format!(
+                "<span style=\"{}\">{}</span>",
+                css.join(";"),
+                escape(content)
+            ));
+        }
+    }
+    html.push('\n');
+}
+
+/// `color` as a CSS color, for the ones the light theme uses.
+fn css_color(color: Color) -> Option<String> {
+    let hex = match color {
+        Color::Rgb(r, g, b) => return Some(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+        Color::Black => "#000000",
+        Color::Red => "#c62828",
+        Color::Green => "#2e7d32",
+        Color::Yellow => "#b58900",
+        Color::Blue => "#1565c0",
+        Color::Magenta => "#8e24aa",
+        Color::Cyan => "#00838f",
+        Color::Gray | Color::DarkGray => "#757575",
+        _ => return None,
+    };
+    Some(hex.to_string())
+}
+
+/// `text` with the characters HTML reads as markup escaped.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 70em; padding: 0 1em; color: #222; }
+h1 { font-size: 1.4em; }
+h2 { font-size: 1.1em; margin-top: 2em; }
+.totals span { margin-right: 1em; font-weight: bold; }
+.passed { color: #2e7d32; }
+.failed { color: #c62828; }
+.errored { color: #8e24aa; }
+.skipped { color: #b58900; }
+.zero, .counts, .time, .reason { color: #757575; }
+details.suite { border: 1px solid #ddd; border-radius: 4px; margin: 0.5em 0; padding: 0.3em 0.8em; }
+summary { cursor: pointer; }
+.counts { font-size: 0.9em; margin-left: 0.5em; }
+table { border-collapse: collapse; width: 100%; margin: 0.5em 0; }
+td { padding: 0.15em 0.5em; vertical-align: top; }
+td:first-child { font-family: monospace; font-weight: bold; width: 3em; }
+.time { text-align: right; white-space: nowrap; }
+pre { background: #f6f6f6; padding: 0.6em; overflow-x: auto; font-size: 0.85em; }
+";
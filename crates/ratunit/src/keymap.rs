@@ -0,0 +1,622 @@
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Everything a key can be bound to in normal mode. Ctrl+C always quits
+/// and is not remappable, so a broken config can't lock the user in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Down,
+    Up,
+    First,
+    Last,
+    PageDown,
+    PageUp,
+    /// Go back, or scroll left in the detail view when wrapping is off.
+    Left,
+    /// Open the selection, or scroll right in the detail view when
+    /// wrapping is off.
+    Right,
+    Open,
+    Back,
+    Finder,
+    CopyFailure,
+    CopyDetail,
+    CopyId,
+    Edit,
+    Help,
+    Search,
+    Filter,
+    Sort,
+    Tree,
+    Expand,
+    ToggleWrap,
+    NextMatch,
+    PrevMatch,
+    NextFile,
+    PrevFile,
+    ToggleWatch,
+    Mark,
+    Baseline,
+    Diff,
+    History,
+    Slowest,
+    Groups,
+    Skips,
+    Packages,
+    Durations,
+    Properties,
+    NextTab,
+    PrevTab,
+    GrowSidebar,
+    ShrinkSidebar,
+    ToggleSidebar,
+    FilterFiles,
+    Pager,
+    OutputLayout,
+    FocusPane,
+    LineNumbers,
+    GoToLine,
+    NextTest,
+    PrevTest,
+    PickRange,
+    Export,
+    SuiteStats,
+    HidePassed,
+    MinTime,
+    ClassFilter,
+    Quarantine,
+    SuiteOutput,
+    CompareSuite,
+    Columns,
+    SortFiles,
+    DropFile,
+    AddFiles,
+    RawXml,
+    NextSection,
+    PrevSection,
+    Pretty,
+}
+
+impl Action {
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Down => &["j", "down"],
+            Action::Up => &["k", "up"],
+            Action::First => &["g", "home"],
+            Action::Last => &["G", "end"],
+            Action::PageDown => &["pagedown"],
+            Action::PageUp => &["pageup"],
+            Action::Left => &["h", "left"],
+            Action::Right => &["l", "right"],
+            Action::Open => &["enter"],
+            Action::Back => &["esc", "backspace"],
+            Action::Finder => &["ctrl-p"],
+            Action::CopyFailure => &["y"],
+            Action::CopyDetail => &["Y"],
+            Action::CopyId => &["ctrl-y"],
+            Action::Edit => &["e"],
+            Action::Help => &["?"],
+            Action::Search => &["/"],
+            Action::Filter => &["f"],
+            Action::Sort => &["s"],
+            Action::Tree => &["t"],
+            Action::Expand => &["space"],
+            Action::ToggleWrap => &["w"],
+            Action::NextMatch => &["n"],
+            Action::PrevMatch => &["N"],
+            Action::NextFile => &["tab"],
+            Action::PrevFile => &["backtab"],
+            Action::ToggleWatch => &["W"],
+            Action::Mark => &["m"],
+            Action::Baseline => &["B"],
+            Action::Diff => &["d"],
+            Action::History => &["H"],
+            Action::Slowest => &["T"],
+            Action::Groups => &["F"],
+            Action::Skips => &["I"],
+            Action::Packages => &["C"],
+            Action::Durations => &["D"],
+            Action::Properties => &["p"],
+            Action::NextTab => &["]"],
+            Action::PrevTab => &["["],
+            Action::GrowSidebar => &[">"],
+            Action::ShrinkSidebar => &["<"],
+            Action::ToggleSidebar => &["b"],
+            Action::FilterFiles => &["ctrl-f"],
+            Action::Pager => &["|"],
+            Action::OutputLayout => &["o"],
+            Action::FocusPane => &["ctrl-w"],
+            Action::LineNumbers => &["#"],
+            Action::GoToLine => &[":"],
+            Action::NextTest => &["J"],
+            Action::PrevTest => &["K"],
+            Action::PickRange => &["V"],
+            Action::Export => &["x"],
+            Action::SuiteStats => &["i"],
+            Action::HidePassed => &["P"],
+            Action::MinTime => &["M"],
+            Action::ClassFilter => &["L"],
+            Action::Quarantine => &["Q"],
+            Action::SuiteOutput => &["O"],
+            Action::CompareSuite => &["A"],
+            Action::Columns => &["c"],
+            Action::SortFiles => &["S"],
+            Action::DropFile => &["X"],
+            Action::AddFiles => &["a"],
+            Action::RawXml => &["r"],
+            Action::NextSection => &["}"],
+            Action::PrevSection => &["{"],
+            Action::Pretty => &["="],
+        }
+    }
+
+    /// What the action does, for the help overlay.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Down => "Move down",
+            Action::Up => "Move up",
+            Action::First => "Jump to first",
+            Action::Last => "Jump to last",
+            Action::PageDown => "Move ten rows down",
+            Action::PageUp => "Move ten rows up",
+            Action::Left => "Go back / scroll left",
+            Action::Right => "Open / scroll right",
+            Action::Open => "Open suite or test",
+            Action::Back => "Go back",
+            Action::Finder => "Fuzzy-find any test",
+            Action::CopyFailure => "Copy failure",
+            Action::CopyDetail => "Copy the whole detail",
+            Action::CopyId => "Copy the test's identifier for a runner",
+            Action::Edit => "Open source in $EDITOR",
+            Action::Help => "Toggle help",
+            Action::Search => "Search",
+            Action::Filter => "Cycle status filter",
+            Action::Sort => "Cycle sort order",
+            Action::Tree => "Toggle tree view",
+            Action::Expand => "Expand / collapse suite",
+            Action::ToggleWrap => "Toggle line wrapping",
+            Action::NextMatch => "Next match",
+            Action::PrevMatch => "Previous match",
+            Action::NextFile => "Next file",
+            Action::PrevFile => "Previous file",
+            Action::ToggleWatch => "Toggle watching for changes",
+            Action::Mark => "Mark file for comparison",
+            Action::Baseline => "Use the file as the failure baseline",
+            Action::Diff => "Compare marked files",
+            Action::History => "Show trends across runs",
+            Action::Slowest => "Show the slowest tests",
+            Action::Groups => "Group failures by message or exception",
+            Action::Skips => "Group skipped tests by reason",
+            Action::Packages => "Browse tests by package",
+            Action::Durations => "Show a duration histogram per suite",
+            Action::Properties => "Show suite properties",
+            Action::NextTab => "Next status tab",
+            Action::PrevTab => "Previous status tab",
+            Action::GrowSidebar => "Widen the file sidebar",
+            Action::ShrinkSidebar => "Narrow the file sidebar",
+            Action::ToggleSidebar => "Show / hide the file sidebar",
+            Action::FilterFiles => "Filter the file sidebar",
+            Action::Pager => "Open the detail in $PAGER",
+            Action::OutputLayout => "Cycle stdout/stderr layout",
+            Action::FocusPane => "Scroll the next detail pane",
+            Action::LineNumbers => "Toggle line numbers in the detail",
+            Action::GoToLine => "Go to a line of the output",
+            Action::NextTest => "Next test's detail",
+            Action::PrevTest => "Previous test's detail",
+            Action::PickRange => "Pick tests up to the cursor",
+            Action::Export => "Export picked tests or the open test",
+            Action::SuiteStats => "Show suite statistics",
+            Action::HidePassed => "Hide / show passed tests",
+            Action::MinTime => "Cycle the minimum test duration shown",
+            Action::ClassFilter => "Limit the lists to a class pattern",
+            Action::Quarantine => "Count / ignore quarantined failures",
+            Action::SuiteOutput => "Show the suite's own output",
+            Action::CompareSuite => "Compare the suite across files",
+            Action::Columns => "Show / hide the class and file columns",
+            Action::SortFiles => "Cycle file sidebar order",
+            Action::DropFile => "Remove the file from this session",
+            Action::AddFiles => "Load another report or directory",
+            Action::RawXml => "Show the test's raw XML",
+            Action::NextSection => "Jump to the next detail section",
+            Action::PrevSection => "Jump to the previous detail section",
+            Action::Pretty => "Pretty-print JSON and XML in the detail",
+        }
+    }
+
+    const ALL: [Action; 68] = [
+        Action::Quit,
+        Action::Down,
+        Action::Up,
+        Action::First,
+        Action::Last,
+        Action::PageDown,
+        Action::PageUp,
+        Action::Left,
+        Action::Right,
+        Action::Open,
+        Action::Back,
+        Action::Finder,
+        Action::CopyFailure,
+        Action::CopyDetail,
+        Action::CopyId,
+        Action::Edit,
+        Action::Help,
+        Action::Search,
+        Action::Filter,
+        Action::Sort,
+        Action::Tree,
+        Action::Expand,
+        Action::ToggleWrap,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::NextFile,
+        Action::PrevFile,
+        Action::ToggleWatch,
+        Action::Mark,
+        Action::Baseline,
+        Action::Diff,
+        Action::History,
+        Action::Slowest,
+        Action::Groups,
+        Action::Skips,
+        Action::Packages,
+        Action::Durations,
+        Action::Properties,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::GrowSidebar,
+        Action::ShrinkSidebar,
+        Action::ToggleSidebar,
+        Action::FilterFiles,
+        Action::Pager,
+        Action::OutputLayout,
+        Action::FocusPane,
+        Action::LineNumbers,
+        Action::GoToLine,
+        Action::NextTest,
+        Action::PrevTest,
+        Action::PickRange,
+        Action::Export,
+        Action::SuiteStats,
+        Action::HidePassed,
+        Action::MinTime,
+        Action::ClassFilter,
+        Action::Quarantine,
+        Action::SuiteOutput,
+        Action::CompareSuite,
+        Action::Columns,
+        Action::SortFiles,
+        Action::DropFile,
+        Action::AddFiles,
+        Action::RawXml,
+        Action::NextSection,
+        Action::PrevSection,
+        Action::Pretty,
+    ];
+}
+
+/// A key with its modifiers, as written in the config file: `q`, `G`,
+/// `ctrl-p`, `alt-j`, `enter`, `space`, `f5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    fn from_event(event: KeyEvent) -> Self {
+        // Terminals report `G` as Shift+G; the case already says so.
+        let modifiers = match event.code {
+            KeyCode::Char(_) | KeyCode::BackTab => event.modifiers - KeyModifiers::SHIFT,
+            _ => event.modifiers,
+        };
+        Self {
+            code: event.code,
+            modifiers: modifiers
+                & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        // A lone `-` is a key, not a separator.
+        while let Some((prefix, tail)) = rest.split_once('-').filter(|(_, t)| !t.is_empty()) {
+            modifiers |= match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => bail!("Unknown modifier '{}' in key '{}'", prefix, text),
+            };
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "insert" => KeyCode::Insert,
+                "delete" | "del" => KeyCode::Delete,
+                f if f.starts_with('f') => match f[1..].parse() {
+                    Ok(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => bail!("Unknown key '{}'", text),
+                },
+                _ => bail!("Unknown key '{}'", text),
+            },
+        };
+
+        // `shift-tab` is what terminals send as BackTab; `shift-g` is `G`.
+        let (code, modifiers) = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                (KeyCode::BackTab, modifiers - KeyModifiers::SHIFT)
+            }
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => (
+                KeyCode::Char(c.to_ascii_uppercase()),
+                modifiers - KeyModifiers::SHIFT,
+            ),
+            _ => (code, modifiers),
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// One key or a list of keys for an action in the `[keys]` table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        let keys = match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys.as_slice(),
+        };
+        keys.iter().map(String::as_str)
+    }
+}
+
+/// Maps keys to actions for normal mode.
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: HashMap<Key, Action>,
+    /// Actions the config rebound, with their new keys, for the help overlay.
+    custom: BTreeMap<Action, Vec<Key>>,
+}
+
+impl KeyMap {
+    /// Builds the key map from the `[keys]` config table. Listing an action
+    /// replaces its default keys; a key taken by a user binding is removed
+    /// from whichever action had it by default. An empty list unbinds the
+    /// action.
+    pub fn new(overrides: &BTreeMap<Action, KeyList>) -> Result<Self> {
+        let mut custom: BTreeMap<Action, Vec<Key>> = BTreeMap::new();
+        let mut taken: HashMap<Key, Action> = HashMap::new();
+        for (&action, keys) in overrides {
+            let mut parsed = Vec::new();
+            for text in keys.iter() {
+                let key = Key::parse(text)?;
+                if let Some(other) = taken.insert(key, action).filter(|&a| a != action) {
+                    bail!(
+                        "Key '{}' is bound to both '{}' and '{}'",
+                        text,
+                        other.describe(),
+                        action.describe()
+                    );
+                }
+                parsed.push(key);
+            }
+            custom.insert(action, parsed);
+        }
+
+        let mut bindings = HashMap::new();
+        for action in Action::ALL.into_iter().filter(|a| !custom.contains_key(a)) {
+            for text in action.default_keys() {
+                bindings.insert(Key::parse(text)?, action);
+            }
+        }
+        bindings.extend(taken);
+        Ok(Self { bindings, custom })
+    }
+
+    pub fn action(&self, event: KeyEvent) -> Option<Action> {
+        self.bindings.get(&Key::from_event(event)).copied()
+    }
+
+    /// Rebound actions and their keys, in a stable order.
+    pub fn custom(&self) -> impl Iterator<Item = (Action, &[Key])> {
+        self.custom.iter().map(|(&a, keys)| (a, keys.as_slice()))
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).expect("default key bindings are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        Key { code, modifiers }
+    }
+
+    fn press(keymap: &KeyMap, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        keymap.action(KeyEvent::new(code, modifiers))
+    }
+
+    fn overrides(bindings: &[(Action, &[&str])]) -> BTreeMap<Action, KeyList> {
+        bindings
+            .iter()
+            .map(|&(action, keys)| {
+                let keys = keys.iter().map(|k| k.to_string()).collect();
+                (action, KeyList::Many(keys))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_modifiers() {
+        let ctrl = KeyModifiers::CONTROL;
+        let alt = KeyModifiers::ALT;
+        assert_eq!(Key::parse("ctrl-p").unwrap(), key(KeyCode::Char('p'), ctrl));
+        assert_eq!(Key::parse("Alt-j").unwrap(), key(KeyCode::Char('j'), alt));
+        assert_eq!(
+            Key::parse("ctrl-alt-x").unwrap(),
+            key(KeyCode::Char('x'), ctrl | alt)
+        );
+        assert_eq!(
+            Key::parse("shift-g").unwrap(),
+            key(KeyCode::Char('G'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            Key::parse("shift-tab").unwrap(),
+            key(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+        assert_eq!(
+            Key::parse("-").unwrap(),
+            key(KeyCode::Char('-'), KeyModifiers::NONE)
+        );
+        assert_eq!(Key::parse("ctrl--").unwrap(), key(KeyCode::Char('-'), ctrl));
+    }
+
+    #[test]
+    fn parses_named_keys() {
+        let none = KeyModifiers::NONE;
+        assert_eq!(Key::parse("enter").unwrap(), key(KeyCode::Enter, none));
+        assert_eq!(Key::parse("Esc").unwrap(), key(KeyCode::Esc, none));
+        assert_eq!(Key::parse("space").unwrap(), key(KeyCode::Char(' '), none));
+        assert_eq!(Key::parse("pgdn").unwrap(), key(KeyCode::PageDown, none));
+        assert_eq!(Key::parse("f5").unwrap(), key(KeyCode::F(5), none));
+        assert_eq!(Key::parse("G").unwrap(), key(KeyCode::Char('G'), none));
+    }
+
+    #[test]
+    fn rejects_bad_keys() {
+        for text in ["", "f13", "f0", "hyper-x", "enterr", "ctrl-"] {
+            assert!(Key::parse(text).is_err(), "{:?} parsed", text);
+        }
+    }
+
+    #[test]
+    fn default_keys_are_distinct() {
+        let mut seen = HashMap::new();
+        for action in Action::ALL {
+            for text in action.default_keys() {
+                let key = Key::parse(text).unwrap();
+                if let Some(other) = seen.insert(key, action) {
+                    panic!("'{}' is bound to {:?} and {:?}", text, other, action);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn override_replaces_default() {
+        let keymap = KeyMap::new(&overrides(&[(Action::Down, &["ctrl-n"])])).unwrap();
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            press(&keymap, KeyCode::Char('n'), KeyModifiers::CONTROL),
+            Some(Action::Down)
+        );
+        assert_eq!(press(&keymap, KeyCode::Char('j'), none), None);
+        assert_eq!(press(&keymap, KeyCode::Down, none), None);
+        assert_eq!(press(&keymap, KeyCode::Char('k'), none), Some(Action::Up));
+        let custom: Vec<_> = keymap.custom().collect();
+        assert_eq!(
+            custom,
+            [(
+                Action::Down,
+                &[key(KeyCode::Char('n'), KeyModifiers::CONTROL)][..]
+            )]
+        );
+    }
+
+    #[test]
+    fn override_takes_key_from_default() {
+        let keymap = KeyMap::new(&overrides(&[(Action::Quit, &["x"])])).unwrap();
+        let none = KeyModifiers::NONE;
+        assert_eq!(press(&keymap, KeyCode::Char('x'), none), Some(Action::Quit));
+        assert_eq!(press(&keymap, KeyCode::Char('q'), none), None);
+    }
+
+    #[test]
+    fn empty_override_unbinds() {
+        let keymap = KeyMap::new(&overrides(&[(Action::Help, &[])])).unwrap();
+        assert_eq!(press(&keymap, KeyCode::Char('?'), KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn rejects_conflicting_bindings() {
+        let both = overrides(&[(Action::Down, &["ctrl-n"]), (Action::Up, &["ctrl-n"])]);
+        let err = KeyMap::new(&both).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Key 'ctrl-n' is bound to both 'Move down' and 'Move up'"
+        );
+    }
+
+    #[test]
+    fn shifted_letters_match_their_binding() {
+        let keymap = KeyMap::default();
+        assert_eq!(
+            press(&keymap, KeyCode::Char('G'), KeyModifiers::SHIFT),
+            Some(Action::Last)
+        );
+    }
+}
@@ -1,86 +1,747 @@
+mod ansi;
 mod app;
+mod ascii;
+mod assertion;
+mod browser;
+mod clipboard;
+mod comment;
+mod complete;
+mod config;
+mod csv;
+mod desktop;
+mod editor;
 mod event;
+mod gitlab;
+mod highlight;
+mod history;
+mod html;
+mod keymap;
+mod pager;
+mod prefilter;
+mod pretty;
+mod quarantine;
+mod sarif;
+mod search;
+mod state;
+mod store;
+mod summary;
+mod test_id;
+mod theme;
 mod ui;
+mod watch;
+mod webhook;
 
-use crate::app::{App, FileReport};
+use crate::app::{App, Baseline, FileReport};
+use crate::keymap::KeyMap;
+use crate::prefilter::Prefilter;
+use crate::quarantine::Quarantine;
+use crate::state::State;
+use crate::store::Store;
+use crate::theme::Theme;
+use crate::watch::Watcher;
 use anyhow::{bail, Context, Result};
-use clap::Parser;
-use crossterm::event::{self as ct_event, Event, KeyEventKind};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use crossterm::event::{
+    self as ct_event, DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind,
+};
 use crossterm::execute;
 use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
 };
 use ratatui::backend::CrosstermBackend;
+use ratatui::layout::Rect;
 use ratatui::Terminal;
-use std::io;
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(
     name = "ratunit",
-    about = "A rat-powered TUI viewer for JUnit XML test reports"
+    about = "A rat-powered TUI viewer for JUnit XML test reports",
+    args_conflicts_with_subcommands = true
 )]
 struct Cli {
-    /// Path to a JUnit XML file or a directory containing XML files
-    path: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// JUnit XML files or directories containing XML files, all loaded into
+    /// one session. Quoted glob patterns such as '**/surefire-reports/*.xml'
+    /// are expanded, and `-` reads a report from standard input. Without
+    /// one, or given a single directory with no XML files, a browser opens
+    /// to pick one.
+    paths: Vec<PathBuf>,
+
+    /// Color theme: dark, light, solarized, or one defined in the config file
+    #[arg(long)]
+    theme: Option<String>,
+
+    /// Draw with ASCII characters only, for consoles that mangle Unicode
+    #[arg(long)]
+    ascii: bool,
+
+    /// Use the terminal's default colors only (also set by NO_COLOR)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Config file to use instead of ~/.config/ratunit/config.toml
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Reload reports when they change on disk (toggle at runtime with W)
+    #[arg(long)]
+    watch: bool,
+
+    /// Add reports that appear in the directory while running, for tests
+    /// still writing results elsewhere
+    #[arg(long)]
+    tail: bool,
+
+    /// Print the totals and failing tests after quitting, so they stay in
+    /// the scrollback
+    #[arg(long)]
+    summary: bool,
+
+    /// Open the viewer even when standard output isn't a terminal
+    #[arg(long, conflicts_with = "no_tui")]
+    tui: bool,
+
+    /// Print the totals and failing tests instead of opening the viewer,
+    /// as happens when standard output is piped or a CI log
+    #[arg(long)]
+    no_tui: bool,
+
+    /// Report whose failures count as known: `f` can then list only new
+    /// failures (pick one at runtime with B)
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+
+    /// Load only failed and errored tests
+    #[arg(long)]
+    only_failed: bool,
+
+    /// Load only tests whose classname.name matches this glob; may be
+    /// repeated
+    #[arg(long, value_name = "PATTERN")]
+    include: Vec<String>,
+
+    /// Leave out tests whose classname.name matches this glob; may be
+    /// repeated
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Print a table of each report's suites with pass/fail/skip counts
+    /// and exit, for CI logs and terminals without the TUI
+    Summary {
+        /// JUnit XML files or directories, as for the viewer
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Output format: a table, the parsed reports as JSON, or a
+        /// Markdown report for CI job summaries and PR comments
+        #[arg(long, value_enum, default_value_t = SummaryFormat::Table)]
+        format: SummaryFormat,
+        /// Exit 1 when fewer than this percentage of the tests that ran
+        /// passed. Quarantined tests don't count
+        #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+        min_pass_rate: Option<f64>,
+        /// Also list the N slowest tests with their share of the total
+        /// test time
+        #[arg(long, value_name = "N")]
+        slowest: Option<usize>,
+    },
+    /// Exit 1 when the reports have more failures or errors than allowed,
+    /// listing the failing tests, to gate CI pipelines; 2 when they can't
+    /// be read. Quarantined tests don't count, nor do a baseline's failures
+    Check {
+        /// JUnit XML files or directories, as for the viewer
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Failed tests to allow
+        #[arg(long, default_value_t = 0)]
+        max_failures: u64,
+        /// Errored tests to allow
+        #[arg(long, default_value_t = 0)]
+        max_errors: u64,
+        /// Lowest percentage of the tests that ran that must pass
+        #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+        min_pass_rate: Option<f64>,
+        /// Report from an earlier run, such as main's: tests already
+        /// failing there don't count, so only regressions fail the check
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+    },
+    /// Combine reports, such as the shards of a parallel run, into one
+    /// JUnit XML file with its counts recomputed
+    Merge {
+        /// JUnit XML files or directories, as for the viewer
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// File to write instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Compare two reports: new failures, fixed tests, added and removed
+    /// tests, and tests that got slower
+    Diff {
+        /// Report to compare against, such as the last run on main. A
+        /// directory's reports are merged into one
+        old: PathBuf,
+        /// Report to compare
+        new: PathBuf,
+        /// Output format: text, JSON, Markdown for PR comments, or the
+        /// viewer opened on the comparison
+        #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+        format: DiffFormat,
+    },
+    /// Print a Markdown comment summarising the reports, ready to post on
+    /// a pull request
+    Comment {
+        /// JUnit XML files or directories, as for the viewer
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Report whose failures are already known, such as the last run on
+        /// main: only failures new since it are listed
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+        /// How many of the slowest tests to list
+        #[arg(long, default_value_t = 5)]
+        slowest: usize,
+        /// Largest comment to print, in bytes; failures past it are left
+        /// out with a note. GitHub allows 65536 characters
+        #[arg(long, default_value_t = 65000)]
+        max_size: usize,
+    },
+    /// Post a summary of the reports to a webhook, such as a Slack
+    /// channel's, with the totals and the first failing tests
+    Notify {
+        /// JUnit XML files or directories, as for the viewer
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// URL to POST the summary to
+        #[arg(long, value_name = "URL")]
+        webhook: String,
+        /// Payload: Slack blocks, or a plain JSON summary for other
+        /// services
+        #[arg(long, value_enum, default_value_t = WebhookFormat::Slack)]
+        format: WebhookFormat,
+        /// How many failing tests to list
+        #[arg(long, default_value_t = 5)]
+        top: usize,
+    },
+    /// Write the reports as a self-contained HTML page, to share with
+    /// people who won't run a terminal viewer
+    Export {
+        /// JUnit XML files or directories, as for the viewer
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// HTML file to write
+        #[arg(
+            long,
+            value_name = "FILE",
+            required_unless_present_any = ["codequality", "sarif", "csv"]
+        )]
+        html: Option<PathBuf>,
+        /// GitLab code quality report to write, listing each failing test
+        /// for `artifacts:reports:codequality`
+        #[arg(long, value_name = "FILE")]
+        codequality: Option<PathBuf>,
+        /// SARIF log to write, with a result for each failing test, for
+        /// code scanning and editors
+        #[arg(long, value_name = "FILE")]
+        sarif: Option<PathBuf>,
+        /// CSV file to write, one row per test case, for spreadsheets
+        #[arg(long, value_name = "FILE")]
+        csv: Option<PathBuf>,
+    },
+    /// Record runs in a local SQLite database, and show a test's streaks,
+    /// duration trend and flakiness across them
+    History {
+        /// Database to use instead of
+        /// ~/.local/state/ratunit/history.sqlite
+        #[arg(long, global = true, value_name = "FILE")]
+        db: Option<PathBuf>,
+        #[command(subcommand)]
+        action: HistoryCommand,
+    },
+    /// Print a completion script for subcommands and flags, to source from
+    /// the shell's startup file
+    Completions {
+        /// Shell to complete for
+        shell: Shell,
+    },
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+    /// Record reports as one run, such as at the end of each CI build
+    Record {
+        /// JUnit XML files or directories, as for the viewer
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+    /// Show a test's results across the recorded runs
+    Show {
+        /// The test's classname.name, or its name alone
+        test: String,
+        /// Recent runs to list
+        #[arg(long, default_value_t = 10)]
+        last: usize,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SummaryFormat {
+    Table,
+    Json,
+    Markdown,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum WebhookFormat {
+    Slack,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum DiffFormat {
+    Text,
+    Json,
+    Markdown,
+    Tui,
+}
+
+/// `ratunit check`'s exit status when the reports are over the limits.
+const CHECK_FAILED: i32 = 1;
+/// `ratunit check`'s exit status when the reports can't be read, kept
+/// apart from [`CHECK_FAILED`] so CI can allow one and not the other.
+const CHECK_ERROR: i32 = 2;
+
+/// How often to check for file changes while watching.
+const WATCH_POLL: Duration = Duration::from_millis(250);
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let path = &cli.path;
+    // https://no-color.org: any non-empty value disables color.
+    let no_color = cli.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
 
-    if !path.exists() {
-        bail!("Path does not exist: {}", path.display());
-    }
-
-    let files = if path.is_dir() {
-        let parsed = junit_parser::parse_directory(path)
-            .with_context(|| format!("Failed to parse directory: {}", path.display()))?;
-        if parsed.is_empty() {
-            bail!("No XML files found in: {}", path.display());
+    // Subcommands print and exit, except a diff asked for with
+    // `--format tui`, whose pair goes on to open in the viewer.
+    let compare = match &cli.command {
+        None => None,
+        Some(Command::Completions { shell }) => {
+            clap_complete::generate(*shell, &mut Cli::command(), "ratunit", &mut io::stdout());
+            return Ok(());
+        }
+        Some(Command::Summary {
+            paths,
+            format,
+            min_pass_rate,
+            slowest,
+        }) => {
+            if slowest.is_some() && matches!(format, SummaryFormat::Json) {
+                bail!("--slowest only applies to the table and Markdown formats");
+            }
+            let files = FileReport::load_all(&expand_globs(paths)?)?;
+            match format {
+                SummaryFormat::Table => {
+                    summary::print_table(&files, !no_color);
+                    if let Some(n) = slowest {
+                        summary::print_slowest(&files, *n, !no_color);
+                    }
+                }
+                SummaryFormat::Json => summary::print_json(&files)?,
+                SummaryFormat::Markdown => summary::print_markdown(&files, *slowest),
+            }
+            if let Some(min) = min_pass_rate {
+                let config = config::load(cli.config.as_deref())?;
+                let rate = summary::pass_rate(&files, &Quarantine::load(&config)?);
+                if rate < *min {
+                    eprintln!(
+                        "ratunit: {:.2}% of tests passed, below the minimum of {}%",
+                        rate, min
+                    );
+                    std::process::exit(CHECK_FAILED);
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Merge { paths, output }) => {
+            let files = FileReport::load_all(&expand_globs(paths)?)?;
+            let merged = junit_parser::TestSuites::merge(files.iter().map(|f| &f.data));
+            let xml = junit_parser::write_xml(&merged);
+            match output {
+                Some(path) => std::fs::write(path, xml)
+                    .with_context(|| format!("Failed to write {}", path.display()))?,
+                None => print!("{}", xml),
+            }
+            return Ok(());
+        }
+        Some(Command::History { db, action }) => {
+            let path = match db {
+                Some(path) => path.clone(),
+                None => store::default_path().context("No home directory for the history")?,
+            };
+            let mut store = Store::open(&path)?;
+            match action {
+                HistoryCommand::Record { paths } => {
+                    let files = FileReport::load_all(&expand_globs(paths)?)?;
+                    let merged = junit_parser::TestSuites::merge(files.iter().map(|f| &f.data));
+                    let source: Vec<&str> = files.iter().map(|f| f.filename.as_str()).collect();
+                    let run = store.record(&merged, &source.join(", "))?;
+                    println!(
+                        "Recorded run {}: {} tests in {}",
+                        run,
+                        merged.all_cases().count(),
+                        path.display()
+                    );
+                }
+                HistoryCommand::Show { test, last } => {
+                    let records = store.test_records(test)?;
+                    if records.is_empty() {
+                        bail!("No recorded runs of {}", test);
+                    }
+                    store::print_records(&records, *last, !no_color);
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Comment {
+            paths,
+            baseline,
+            slowest,
+            max_size,
+        }) => {
+            let files = FileReport::load_all(&expand_globs(paths)?)?;
+            let baseline = baseline.as_deref().map(load_baseline).transpose()?;
+            let config = config::load(cli.config.as_deref())?;
+            let quarantine = Quarantine::load(&config)?;
+            print!(
+                "{}",
+                comment::comment(&files, baseline.as_ref(), &quarantine, *slowest, *max_size)
+            );
+            return Ok(());
+        }
+        Some(Command::Notify {
+            paths,
+            webhook,
+            format,
+            top,
+        }) => {
+            let files = FileReport::load_all(&expand_globs(paths)?)?;
+            let payload = match format {
+                WebhookFormat::Slack => webhook::slack(&files, *top),
+                WebhookFormat::Json => webhook::generic(&files, *top),
+            };
+            webhook::post(webhook, &payload)?;
+            return Ok(());
+        }
+        Some(Command::Export {
+            paths,
+            html,
+            codequality,
+            sarif,
+            csv,
+        }) => {
+            let files = FileReport::load_all(&expand_globs(paths)?)?;
+            let write = |path: &Path, content: String| {
+                std::fs::write(path, content)
+                    .with_context(|| format!("Failed to write {}", path.display()))
+            };
+            // Only the outputs asked for are built.
+            if let Some(path) = html {
+                write(path, html::report(&files))?;
+            }
+            if let Some(path) = codequality {
+                write(path, gitlab::code_quality(&files)?)?;
+            }
+            if let Some(path) = sarif {
+                write(path, sarif::log(&files)?)?;
+            }
+            if let Some(path) = csv {
+                write(path, csv::table(&files))?;
+            }
+            return Ok(());
+        }
+        Some(Command::Diff { old, new, format }) => {
+            let base = FileReport::load_merged(old)?;
+            let mut head = FileReport::load_merged(new)?;
+            head.distinguish(std::slice::from_ref(&base));
+            if head.filename == base.filename {
+                bail!("Both paths name the same report: {}", old.display());
+            }
+            let diff = junit_parser::diff(&base.data, &head.data);
+            match format {
+                DiffFormat::Text => summary::print_diff(&base, &head, &diff, !no_color),
+                DiffFormat::Json => summary::print_diff_json(&base, &head, &diff)?,
+                DiffFormat::Markdown => summary::print_diff_markdown(&base, &head, &diff),
+                DiffFormat::Tui => {}
+            }
+            if !matches!(format, DiffFormat::Tui) {
+                return Ok(());
+            }
+            Some((base, head))
+        }
+        Some(Command::Check {
+            paths,
+            max_failures,
+            max_errors,
+            min_pass_rate,
+            baseline,
+        }) => {
+            let checked = (|| {
+                let config = config::load(cli.config.as_deref())?;
+                let files = FileReport::load_all(&expand_globs(paths)?)?;
+                let quarantine = Quarantine::load(&config)?;
+                let baseline = baseline.as_deref().map(load_baseline).transpose()?;
+                Ok::<_, anyhow::Error>(summary::print_check(
+                    &files,
+                    &quarantine,
+                    baseline.as_ref(),
+                    *max_failures,
+                    *max_errors,
+                    *min_pass_rate,
+                    !no_color,
+                ))
+            })();
+            std::process::exit(match checked {
+                Ok(true) => 0,
+                Ok(false) => CHECK_FAILED,
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);
+                    CHECK_ERROR
+                }
+            });
         }
-        parsed
-            .into_iter()
-            .map(|(name, data)| FileReport {
-                filename: name,
-                data,
-            })
-            .collect()
+    };
+
+    let config = config::load(cli.config.as_deref())?;
+    let theme = if no_color {
+        Theme::no_color()
     } else {
-        let data = junit_parser::parse_file(path)
-            .with_context(|| format!("Failed to parse file: {}", path.display()))?;
-        let filename = path
-            .file_name()
-            .map(|n| n.to_string_lossy().into_owned())
-            .unwrap_or_else(|| path.display().to_string());
-        vec![FileReport { filename, data }]
+        Theme::resolve(cli.theme.as_deref(), &config)?
     };
+    let colorblind = cli.theme.as_deref().or(config.theme.as_deref()) == Some("colorblind");
+    let keymap = KeyMap::new(&config.keys).context("Invalid [keys] in config")?;
+    let quarantine = Quarantine::load(&config)?;
+    let baseline = cli.baseline.as_deref().map(load_baseline).transpose()?;
 
-    let app = App::new(files);
+    // Piped output or a CI log gets the exit summary rather than the
+    // alternate screen's escape sequences. A diff asked for as a TUI
+    // always gets one.
+    let tty = io::stdout().is_terminal();
+    let tui = compare.is_some() || cli.tui || (tty && !cli.no_tui);
 
     install_panic_hook();
 
+    let (paths, mut files) = match compare {
+        Some((base, head)) => (vec![base.path.clone(), head.path.clone()], vec![base, head]),
+        None => {
+            let paths = expand_globs(&cli.paths)?;
+            let paths = match paths.as_slice() {
+                [] if !tui => bail!("No report given: pass a path, or --tui to browse for one"),
+                [] => browse(PathBuf::from("."), &theme, cli.ascii)?,
+                [path] if tui && path.is_dir() && !contains_reports(path) => {
+                    browse(path.clone(), &theme, cli.ascii)?
+                }
+                paths => Some(paths.to_vec()),
+            };
+            let Some(paths) = paths else {
+                return Ok(());
+            };
+            let files = FileReport::load_all(&paths)?;
+            (paths, files)
+        }
+    };
+    let prefilter = Prefilter {
+        only_failed: cli.only_failed,
+        include: cli.include.clone(),
+        exclude: cli.exclude.clone(),
+    };
+    for file in &mut files {
+        prefilter.apply(&mut file.data);
+    }
+
+    let saved = state::load();
+    let report_key = paths
+        .iter()
+        .map(|path| {
+            std::fs::canonicalize(path)
+                .unwrap_or_else(|_| path.clone())
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    let compare_names: Vec<String> = match &cli.command {
+        Some(Command::Diff { .. }) => files.iter().map(|f| f.filename.clone()).collect(),
+        _ => Vec::new(),
+    };
+    let mut app = App::new(files, theme, keymap);
+    app.watching = cli.watch;
+    app.tailing = cli.tail && paths.iter().any(|path| path.is_dir());
+    app.quarantine = quarantine;
+    app.prefilter = prefilter;
+    if let Some(baseline) = baseline {
+        app.set_baseline(baseline);
+    }
+    app.notifications = config.notifications.unwrap_or(true);
+    app.render_ansi = config.ansi.unwrap_or(true) && !no_color;
+    app.ascii = cli.ascii;
+    app.status_markers = config.markers.unwrap_or(no_color || colorblind);
+    app.source_roots = config
+        .source_roots
+        .iter()
+        .map(|root| complete::expand_home(root))
+        .collect();
+    if let Some(width) = saved.sidebar_width {
+        app.set_sidebar_width(width);
+    }
+    app.hide_passed = saved.hide_passed;
+    app.hide_columns = saved.hide_columns;
+    app.file_sort = saved.file_sort;
+    app.id_format = saved.id_format;
+    if let Some(position) = saved.positions.get(&report_key) {
+        app.restore_position(position);
+    }
+    if compare_names.len() == 2 {
+        app.marked = compare_names;
+        app.open_diff();
+    }
+    if !tui {
+        summary::print_exit(&app, !no_color && tty);
+        return Ok(());
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    // Save the window title on the terminal's title stack (XTWINOPS) so it
+    // can be put back on exit.
+    write!(stdout, "\x1b[22;0t")?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = run_loop(&mut terminal, app);
+    // A report piped in has nothing on disk to watch.
+    let watched: Vec<PathBuf> = paths
+        .iter()
+        .filter(|path| path.as_path() != Path::new(app::STDIN))
+        .cloned()
+        .collect();
+    let result = run_loop(&mut terminal, &mut app, &watched);
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    write!(terminal.backend_mut(), "\x1b[23;0t")?;
+    terminal.backend_mut().flush()?;
+    if cli.summary {
+        summary::print_exit(&app, !no_color);
+    }
+
+    let mut state = State {
+        sidebar_width: Some(app.sidebar_width).filter(|&w| w != app::SIDEBAR_WIDTH),
+        hide_passed: app.hide_passed,
+        hide_columns: app.hide_columns,
+        file_sort: app.file_sort,
+        id_format: app.id_format,
+        ..saved.clone()
+    };
+    state.positions.insert(report_key, app.position());
+    if state != saved {
+        if let Err(e) = state::save(&state) {
+            eprintln!("Warning: {:#}", e);
+        }
+    }
 
     result
 }
 
-fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mut app: App) -> Result<()> {
+fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    paths: &[PathBuf],
+) -> Result<()> {
+    let mut watcher: Option<Watcher> = None;
+    let mut first = true;
+    let mut title = String::new();
     loop {
-        terminal.draw(|frame| ui::render(frame, &app))?;
+        let wants_watcher = app.watching || app.tailing;
+        if wants_watcher && watcher.is_none() {
+            match Watcher::new(paths) {
+                Ok(w) => {
+                    // Catch up on anything that changed while not watching.
+                    if !first && app.watching {
+                        let reports = all_reports(app, paths);
+                        reload(app, reports);
+                    }
+                    watcher = Some(w);
+                }
+                Err(e) => {
+                    app.watching = false;
+                    app.tailing = false;
+                    app.status_message = Some(format!("{:#}", e));
+                }
+            }
+        } else if !wants_watcher {
+            watcher = None;
+        }
+        first = false;
 
-        if let Event::Key(key) = ct_event::read()? {
-            if key.kind == KeyEventKind::Press {
-                event::handle_key(&mut app, key);
+        terminal.draw(|frame| ui::render(frame, app))?;
+        let summary = window_title(app);
+        if summary != title {
+            execute!(terminal.backend_mut(), SetTitle(&summary))?;
+            title = summary;
+        }
+
+        let ready = watcher.is_none() || ct_event::poll(WATCH_POLL)?;
+        if ready {
+            match ct_event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    event::handle_key(app, key);
+                }
+                Event::Mouse(mouse) => {
+                    let size = terminal.size()?;
+                    let screen = Rect::new(0, 0, size.width, size.height);
+                    event::handle_mouse(app, mouse, screen);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(mut changed) = watcher.as_ref().map(Watcher::changed) {
+            if !app.watching {
+                // Only tailing: leave the reports already loaded alone.
+                changed.retain(|p| !app.files.iter().any(|f| f.path == *p));
+            }
+            if !changed.is_empty() {
+                reload(app, changed);
+            }
+        }
+
+        if let Some(location) = app.pending_edit.take() {
+            suspend_terminal(terminal)?;
+            let result = editor::open(&location);
+            resume_terminal(terminal)?;
+            if let Err(e) = result {
+                app.status_message = Some(format!("{:#}", e));
+            }
+        }
+
+        if let Some(text) = app.pending_pager.take() {
+            suspend_terminal(terminal)?;
+            let result = pager::show(&text);
+            resume_terminal(terminal)?;
+            if let Err(e) = result {
+                app.status_message = Some(format!("{:#}", e));
             }
         }
 
@@ -90,11 +751,317 @@ fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, mut app: App)
     }
 }
 
+/// Re-parses the given report files, dropping those that were deleted and
+/// adding new ones. A file that fails to parse (often because it is still
+/// being written) keeps its previous contents, or stays out until a later
+/// write if it's new.
+fn reload(app: &mut App, paths: BTreeSet<PathBuf>) {
+    let mut reloaded = Vec::new();
+    let mut added = Vec::new();
+    let mut failed = Vec::new();
+    for path in paths {
+        if app
+            .dropped
+            .iter()
+            .any(|dropped| watch::canonical(dropped) == watch::canonical(&path))
+        {
+            continue;
+        }
+        if !path.exists() {
+            app.remove_file(&path);
+            continue;
+        }
+        // Reports are matched by path: a filename can be shared by reports
+        // in different directories.
+        let previous = app.files.iter().find(|f| f.is_at(&path));
+        let is_new = previous.is_none();
+        let was_failing = previous.map(|f| f.data.total_failures() + f.data.total_errors());
+        let (filename, path) = match previous {
+            Some(f) => (f.filename.clone(), f.path.clone()),
+            None => {
+                let filename = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.display().to_string());
+                (filename, path)
+            }
+        };
+        match junit_parser::parse_file_with_spans(&path) {
+            Ok(mut data) => {
+                app.prefilter.apply(&mut data);
+                let failing = data.total_failures() + data.total_errors();
+                let mut report = FileReport {
+                    filename,
+                    metadata: std::fs::metadata(&path).ok(),
+                    path,
+                    data,
+                    source: None,
+                };
+                if is_new {
+                    report.distinguish(&app.files);
+                }
+                let filename = report.filename.clone();
+                if app.notifications {
+                    notify_status_change(&filename, was_failing, failing);
+                }
+                app.update_file(report);
+                if is_new {
+                    added.push(match failing {
+                        0 => filename,
+                        n => format!("{} ({} failing)", filename, n),
+                    });
+                } else {
+                    reloaded.push(filename);
+                }
+            }
+            Err(_) if is_new => {}
+            Err(e) => failed.push(format!("Failed to reload {}: {:#}", filename, e)),
+        }
+    }
+    let mut messages = failed;
+    if !added.is_empty() {
+        messages.push(format!("New report: {}", added.join(", ")));
+    }
+    if !reloaded.is_empty() {
+        messages.push(format!("Reloaded {}", reloaded.join(", ")));
+    }
+    if !messages.is_empty() {
+        app.status_message = Some(messages.join("; "));
+    }
+}
+
+/// `ratunit: 3 failed / 240 passed`, for the terminal's tab bar.
+fn window_title(app: &App) -> String {
+    let failed = app.aggregate_failures() + app.aggregate_errors();
+    let passed = app.aggregate_passed();
+    if failed > 0 {
+        format!("ratunit: {} failed / {} passed", failed, passed)
+    } else {
+        format!("ratunit: {} passed", passed)
+    }
+}
+
+/// Sends a desktop notification when a reloaded report starts or stops
+/// failing.
+fn notify_status_change(filename: &str, was_failing: Option<u64>, failing: u64) {
+    match (was_failing, failing) {
+        (Some(0), n) if n > 0 => desktop::notify(
+            format!("{} is failing", filename),
+            format!("{} {} failing", n, if n == 1 { "test" } else { "tests" }),
+        ),
+        (Some(n), 0) if n > 0 => desktop::notify(
+            format!("{} is passing", filename),
+            "All tests pass again".to_string(),
+        ),
+        _ => {}
+    }
+}
+
+/// Whether `dir` holds any XML files to open.
+fn contains_reports(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|e| e.path().extension().is_some_and(|ext| ext == "xml"))
+    })
+}
+
+/// Every report currently loaded plus any XML file now in the
+/// directories given.
+fn all_reports(app: &App, paths: &[PathBuf]) -> BTreeSet<PathBuf> {
+    let mut reports: BTreeSet<PathBuf> = app
+        .files
+        .iter()
+        .map(|f| f.path.clone())
+        .filter(|path| path.as_path() != Path::new(app::STDIN))
+        .collect();
+    for dir in paths.iter().filter(|path| path.is_dir()) {
+        if let Ok(entries) = std::fs::read_dir(dir) {
+            reports.extend(
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|ext| ext == "xml")),
+            );
+        }
+    }
+    reports
+}
+
+/// Loads a report whose failures count as known, as for
+/// [`FileReport::load_merged`]: a file, a directory of reports or `-`.
+fn load_baseline(path: &Path) -> Result<Baseline> {
+    let report = FileReport::load_merged(path)
+        .with_context(|| format!("Failed to load baseline {}", path.display()))?;
+    Ok(Baseline::new(report.filename, &report.data))
+}
+
+/// Replaces each argument that isn't an existing path but contains glob
+/// characters with the paths it matches, for shells (and CI steps) that
+/// leave `**` unexpanded.
+fn expand_globs(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+    for path in paths {
+        let pattern = path.to_string_lossy();
+        if path.exists() || !pattern.contains(['*', '?', '[']) {
+            expanded.push(path.clone());
+            continue;
+        }
+        let matches = glob::glob(&pattern)
+            .with_context(|| format!("Invalid glob pattern: {}", pattern))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if matches.is_empty() {
+            bail!("No files match: {}", pattern);
+        }
+        expanded.extend(matches);
+    }
+    Ok(expanded)
+}
+
+/// Parses a `--min-pass-rate` percentage between 0 and 100.
+fn parse_percent(arg: &str) -> Result<f64, String> {
+    match arg.trim_end_matches('%').parse::<f64>() {
+        Ok(p) if (0.0..=100.0).contains(&p) => Ok(p),
+        _ => Err("expected a percentage between 0 and 100".to_string()),
+    }
+}
+
+/// Opens the directory browser at `start`; `None` when it's left without
+/// picking anything.
+fn browse(start: PathBuf, theme: &Theme, ascii: bool) -> Result<Option<Vec<PathBuf>>> {
+    Ok(browser::pick(&start, theme, ascii)?.map(|path| vec![path]))
+}
+
+/// Hands the terminal back to the shell so a child process can use it.
+fn suspend_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    Ok(())
+}
+
+fn resume_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+    Ok(())
+}
+
 fn install_panic_hook() {
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
         original_hook(panic_info);
     }));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSING: &str = r#"<testsuite name="s"><testcase classname="C" name="t"/></testsuite>"#;
+    const FAILING: &str = r#"<testsuite name="s" tests="1" failures="1">
+        <testcase classname="C" name="t"><failure message="boom"/></testcase>
+    </testsuite>"#;
+
+    /// An empty directory of its own under the system temp directory.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ratunit-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn app(files: Vec<FileReport>) -> App {
+        let keymap = KeyMap::new(&Default::default()).unwrap();
+        App::new(files, Theme::no_color(), keymap)
+    }
+
+    #[test]
+    fn reload_matches_same_named_reports_by_path() {
+        let dir = temp_dir("reload");
+        for sub in ["a", "b"] {
+            std::fs::create_dir(dir.join(sub)).unwrap();
+            std::fs::write(dir.join(sub).join("results.xml"), PASSING).unwrap();
+        }
+        let a = dir.join("a").join("results.xml");
+        let b = dir.join("b").join("results.xml");
+        let files = FileReport::load_all(&[a.clone(), b.clone()]).unwrap();
+        let mut app = app(files);
+
+        std::fs::write(&b, FAILING).unwrap();
+        reload(&mut app, BTreeSet::from([b.clone()]));
+        let failures = |app: &App, path: &Path| {
+            let file = app.files.iter().find(|f| f.path == path).unwrap();
+            file.data.total_failures()
+        };
+        assert_eq!(app.files.len(), 2);
+        assert_eq!(failures(&app, &a), 0);
+        assert_eq!(failures(&app, &b), 1);
+
+        // A report that fails to parse is kept as it was; the rest of the
+        // batch still reloads.
+        std::fs::write(&a, "not xml").unwrap();
+        std::fs::write(&b, PASSING).unwrap();
+        reload(&mut app, BTreeSet::from([a.clone(), b.clone()]));
+        assert_eq!(app.files.len(), 2);
+        assert_eq!(failures(&app, &b), 0);
+        let message = app.status_message.clone().unwrap();
+        assert!(message.starts_with("Failed to reload "), "{}", message);
+        assert!(message.contains("; Reloaded "), "{}", message);
+
+        std::fs::remove_file(&a).unwrap();
+        reload(&mut app, BTreeSet::from([a.clone()]));
+        assert_eq!(app.files.len(), 1);
+        assert!(app.files[0].is_at(&b));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parses_percentages() {
+        assert_eq!(parse_percent("90"), Ok(90.0));
+        assert_eq!(parse_percent("90%"), Ok(90.0));
+        assert!(parse_percent("101").is_err());
+        assert!(parse_percent("abc").is_err());
+    }
+
+    #[test]
+    fn literal_paths_pass_through_globbing() {
+        let paths = [PathBuf::from("-"), PathBuf::from("missing/results.xml")];
+        assert_eq!(expand_globs(&paths).unwrap(), paths);
+    }
+
+    #[test]
+    fn globs_expand_and_must_match() {
+        let dir = temp_dir("globs");
+        std::fs::write(dir.join("one.xml"), PASSING).unwrap();
+        std::fs::write(dir.join("two.xml"), PASSING).unwrap();
+
+        let found = expand_globs(&[dir.join("*.xml")]).unwrap();
+        assert_eq!(found, [dir.join("one.xml"), dir.join("two.xml")]);
+
+        let err = expand_globs(&[dir.join("*.json")]).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(err.to_string().starts_with("No files match"));
+    }
+
+    #[test]
+    fn paths_named_twice_load_once() {
+        let dir = temp_dir("twice");
+        let path = dir.join("results.xml");
+        std::fs::write(&path, PASSING).unwrap();
+        let again = dir.join(".").join("results.xml");
+        let files = FileReport::load_all(&[path.clone(), again, path]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(files.len(), 1);
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Pipes `text` into `$PAGER` (default `less`) and waits for it to exit.
+/// The caller is responsible for handing over the terminal.
+pub fn show(text: &str) -> Result<()> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "less".to_string());
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        bail!("$PAGER is empty");
+    };
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to launch pager: {}", pager))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A pager quit before reading everything closes the pipe early;
+        // that is not an error.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let status = child.wait().context("Failed to wait for pager")?;
+    if !status.success() {
+        bail!("Pager exited with {}", status);
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::search;
+use crate::test_id::IdFormat;
+use junit_parser::{TestCase, TestStatus, TestSuites};
+
+/// Test cases left out of every report as it is loaded, from the
+/// `--only-failed`, `--include` and `--exclude` flags. Patterns are globs
+/// matched against the test's `classname.name`, as in
+/// [`search::glob_match`].
+#[derive(Debug, Default)]
+pub struct Prefilter {
+    pub only_failed: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl Prefilter {
+    pub fn is_empty(&self) -> bool {
+        !self.only_failed && self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether `tc` is kept: failed or errored when only failures are
+    /// wanted, matching an include pattern if there are any, and matching
+    /// no exclude pattern.
+    pub fn keeps(&self, tc: &TestCase) -> bool {
+        if self.only_failed && !matches!(tc.status(), TestStatus::Failed | TestStatus::Errored) {
+            return false;
+        }
+        let id = IdFormat::Dotted.format(tc);
+        let matches = |patterns: &[String]| patterns.iter().any(|p| search::glob_match(p, &id));
+        (self.include.is_empty() || matches(&self.include)) && !matches(&self.exclude)
+    }
+
+    /// Drops the cases `data` doesn't keep. The counts are then those of
+    /// the cases left; a kept case's span still points into its report, so
+    /// nothing should index back into the report by position.
+    pub fn apply(&self, data: &mut TestSuites) {
+        if !self.is_empty() {
+            data.retain_cases(|tc| self.keeps(tc));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML: &str = r#"<testsuite name="s" tests="3" failures="1">
+    <testcase classname="C" name="first"/>
+    <testcase classname="C" name="second"><failure message="boom"/></testcase>
+    <testcase classname="C" name="third"/>
+</testsuite>"#;
+
+    #[test]
+    fn kept_cases_keep_their_spans() {
+        let mut data = junit_parser::parse_str_with_spans(XML).unwrap();
+        let prefilter = Prefilter {
+            exclude: vec!["C.first".into()],
+            ..Default::default()
+        };
+        prefilter.apply(&mut data);
+        let cases = &data.suites[0].test_cases;
+        assert_eq!(cases.len(), 2);
+        for tc in cases {
+            let markup = &XML[tc.span.clone().unwrap()];
+            assert!(markup.starts_with(&format!(r#"<testcase classname="C" name="{}""#, tc.name)));
+        }
+        assert_eq!(data.total_tests(), 2);
+    }
+}
@@ -0,0 +1,389 @@
+use crate::theme::Theme;
+use quick_xml::events::{BytesStart, Event};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+
+/// Indentation of the outermost level, matching the rest of the detail view.
+const BASE_INDENT: &str = "  ";
+
+/// A run of text, or an embedded JSON or XML document found in it.
+pub enum Segment<'a> {
+    Text(&'a str),
+    Blob(Vec<Line<'static>>),
+}
+
+/// Splits `text` around every embedded JSON object or array and XML
+/// element, formatting and highlighting each one. Only structured
+/// documents are picked out: a JSON array of plain values or an XML
+/// element with no child elements is left as text.
+pub fn segments<'a>(text: &'a str, theme: &Theme) -> Vec<Segment<'a>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    let mut from = 0;
+    while let Some(i) = rest[from..].find(['{', '[', '<']).map(|i| from + i) {
+        let found = match rest.as_bytes()[i] {
+            b'<' => xml_blob(&rest[i..], theme),
+            _ => json_blob(&rest[i..], theme),
+        };
+        let Some((len, lines)) = found else {
+            from = i + 1;
+            continue;
+        };
+        let before = rest[..i].trim_end_matches([' ', '\t']);
+        if !before.is_empty() {
+            segments.push(Segment::Text(before));
+        }
+        segments.push(Segment::Blob(lines));
+        let after = rest[i + len..].trim_start_matches([' ', '\t']);
+        rest = after.strip_prefix('\n').unwrap_or(after);
+        from = 0;
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// Whether [`segments`] would find anything to format in `text`.
+pub fn has_blob(text: &str, theme: &Theme) -> bool {
+    segments(text, theme)
+        .iter()
+        .any(|segment| matches!(segment, Segment::Blob(_)))
+}
+
+enum Json<'a> {
+    /// A string, number or literal, as written.
+    Scalar(&'a str, ScalarKind),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(&'a str, Json<'a>)>),
+}
+
+#[derive(Clone, Copy)]
+enum ScalarKind {
+    String,
+    Number,
+    Literal,
+}
+
+/// A JSON document at the start of `text`: its length and formatted lines.
+fn json_blob(text: &str, theme: &Theme) -> Option<(usize, Vec<Line<'static>>)> {
+    let mut parser = JsonParser { text, pos: 0 };
+    let value = parser.value()?;
+    let structured = match &value {
+        Json::Object(members) => !members.is_empty(),
+        Json::Array(items) => items
+            .iter()
+            .any(|item| matches!(item, Json::Array(_) | Json::Object(_))),
+        Json::Scalar(..) => false,
+    };
+    if !structured {
+        return None;
+    }
+    let mut lines = Vec::new();
+    json_lines(&value, 0, Vec::new(), false, theme, &mut lines);
+    Some((parser.pos, lines))
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let matched = self.peek() == Some(byte);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn value(&mut self) -> Option<Json<'a>> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'{' => self.object(),
+            b'[' => self.array(),
+            b'"' => Some(Json::Scalar(self.string()?, ScalarKind::String)),
+            b'-' | b'0'..=b'9' => Some(Json::Scalar(self.number()?, ScalarKind::Number)),
+            _ => ["true", "false", "null"].into_iter().find_map(|literal| {
+                self.text[self.pos..].starts_with(literal).then(|| {
+                    self.pos += literal.len();
+                    Json::Scalar(literal, ScalarKind::Literal)
+                })
+            }),
+        }
+    }
+
+    fn object(&mut self) -> Option<Json<'a>> {
+        self.pos += 1;
+        let mut members = Vec::new();
+        if self.eat(b'}') {
+            return Some(Json::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek()? != b'"' {
+                return None;
+            }
+            let key = self.string()?;
+            if !self.eat(b':') {
+                return None;
+            }
+            members.push((key, self.value()?));
+            if self.eat(b'}') {
+                return Some(Json::Object(members));
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<Json<'a>> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        if self.eat(b']') {
+            return Some(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            if self.eat(b']') {
+                return Some(Json::Array(items));
+            }
+            if !self.eat(b',') {
+                return None;
+            }
+        }
+    }
+
+    /// A string including its quotes, escapes left as written.
+    fn string(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        self.pos += 1;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => self.pos += 2,
+                b'\n' => return None,
+                _ => self.pos += 1,
+            }
+        }
+        self.pos += 1;
+        Some(&self.text[start..self.pos])
+    }
+
+    fn number(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'))
+        {
+            self.pos += 1;
+        }
+        let number = &self.text[start..self.pos];
+        number.parse::<f64>().is_ok().then_some(number)
+    }
+}
+
+fn json_lines(
+    value: &Json,
+    depth: usize,
+    mut prefix: Vec<Span<'static>>,
+    comma: bool,
+    theme: &Theme,
+    lines: &mut Vec<Line<'static>>,
+) {
+    let punct = |text: &'static str| Span::styled(text, Style::default().fg(theme.muted));
+    let indent = format!("{}{}", BASE_INDENT, "  ".repeat(depth));
+    prefix.insert(0, Span::raw(indent.clone()));
+    let close = |mut spans: Vec<Span<'static>>| {
+        if comma {
+            spans.push(punct(","));
+        }
+        Line::from(spans)
+    };
+
+    let (open, end, children): (_, _, Vec<(Option<&str>, &Json)>) = match value {
+        Json::Scalar(text, kind) => {
+            let color = match kind {
+                ScalarKind::String => theme.passed,
+                ScalarKind::Number => theme.symbol,
+                ScalarKind::Literal => theme.skipped,
+            };
+            prefix.push(Span::styled(text.to_string(), Style::default().fg(color)));
+            lines.push(close(prefix));
+            return;
+        }
+        Json::Array(items) => ("[", "]", items.iter().map(|item| (None, item)).collect()),
+        Json::Object(members) => (
+            "{",
+            "}",
+            members
+                .iter()
+                .map(|(key, value)| (Some(*key), value))
+                .collect(),
+        ),
+    };
+    if children.is_empty() {
+        prefix.extend([punct(open), punct(end)]);
+        lines.push(close(prefix));
+        return;
+    }
+    prefix.push(punct(open));
+    lines.push(Line::from(prefix));
+    let last = children.len() - 1;
+    for (i, (key, child)) in children.into_iter().enumerate() {
+        let key = key.map_or_else(Vec::new, |key| {
+            vec![
+                Span::styled(key.to_string(), Style::default().fg(theme.accent)),
+                punct(": "),
+            ]
+        });
+        json_lines(child, depth + 1, key, i < last, theme, lines);
+    }
+    lines.push(close(vec![Span::raw(indent), punct(end)]));
+}
+
+/// One piece of an XML document, in the order it appears.
+enum XmlToken {
+    Open(Vec<Span<'static>>),
+    Close(Vec<Span<'static>>),
+    /// A self-closing element, declaration or comment: a line of its own.
+    Whole(Vec<Span<'static>>),
+    Text(String),
+}
+
+/// An XML element at the start of `text`, with any declaration before it:
+/// its length and formatted lines.
+fn xml_blob(text: &str, theme: &Theme) -> Option<(usize, Vec<Line<'static>>)> {
+    let punct = |text: String| Span::styled(text, Style::default().fg(theme.muted));
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+
+    let mut tokens = Vec::new();
+    let (mut depth, mut max_depth) = (0, 0);
+    loop {
+        match reader.read_event().ok()? {
+            Event::Decl(decl) if tokens.is_empty() => tokens.push(XmlToken::Whole(vec![punct(
+                format!("<?{}?>", String::from_utf8_lossy(&decl)),
+            )])),
+            Event::Start(start) => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+                tokens.push(XmlToken::Open(xml_tag(&start, ">", theme)?));
+            }
+            Event::Empty(start) if depth > 0 => {
+                max_depth = max_depth.max(depth + 1);
+                tokens.push(XmlToken::Whole(xml_tag(&start, "/>", theme)?));
+            }
+            Event::End(end) if depth > 0 => {
+                depth -= 1;
+                tokens.push(XmlToken::Close(vec![
+                    punct("</".into()),
+                    Span::styled(
+                        String::from_utf8_lossy(end.name().as_ref()).into_owned(),
+                        Style::default().fg(theme.accent),
+                    ),
+                    punct(">".into()),
+                ]));
+                if depth == 0 {
+                    break;
+                }
+            }
+            Event::Text(text) if depth > 0 => {
+                tokens.push(XmlToken::Text(String::from_utf8_lossy(&text).into_owned()))
+            }
+            Event::CData(data) if depth > 0 => tokens.push(XmlToken::Text(format!(
+                "<![CDATA[{}]]>",
+                String::from_utf8_lossy(&data)
+            ))),
+            Event::Comment(comment) if depth > 0 => tokens.push(XmlToken::Whole(vec![punct(
+                format!("<!--{}-->", String::from_utf8_lossy(&comment)),
+            )])),
+            _ => return None,
+        }
+    }
+    if max_depth < 2 {
+        return None;
+    }
+
+    let mut lines = Vec::new();
+    let mut depth = 0;
+    let mut tokens = tokens.into_iter().peekable();
+    let indent = |depth: usize| Span::raw(format!("{}{}", BASE_INDENT, "  ".repeat(depth)));
+    while let Some(token) = tokens.next() {
+        match token {
+            XmlToken::Open(spans) => {
+                let mut line = vec![indent(depth)];
+                line.extend(spans);
+                // Elements holding only text stay on one line.
+                if let Some(XmlToken::Text(_)) = tokens.peek() {
+                    let Some(XmlToken::Text(text)) = tokens.next() else {
+                        unreachable!()
+                    };
+                    line.push(Span::styled(text, Style::default().fg(theme.text)));
+                }
+                if let Some(XmlToken::Close(_)) = tokens.peek() {
+                    let Some(XmlToken::Close(close)) = tokens.next() else {
+                        unreachable!()
+                    };
+                    line.extend(close);
+                } else {
+                    depth += 1;
+                }
+                lines.push(Line::from(line));
+            }
+            XmlToken::Close(spans) => {
+                depth -= 1;
+                lines.push(Line::from([vec![indent(depth)], spans].concat()));
+            }
+            XmlToken::Whole(spans) => {
+                lines.push(Line::from([vec![indent(depth)], spans].concat()));
+            }
+            XmlToken::Text(text) => lines.push(Line::from(vec![
+                indent(depth),
+                Span::styled(text, Style::default().fg(theme.text)),
+            ])),
+        }
+    }
+    Some((reader.buffer_position() as usize, lines))
+}
+
+/// An opening or self-closing tag: name, then each attribute.
+fn xml_tag(start: &BytesStart, end: &'static str, theme: &Theme) -> Option<Vec<Span<'static>>> {
+    let punct = |text: &'static str| Span::styled(text, Style::default().fg(theme.muted));
+    let mut spans = vec![
+        punct("<"),
+        Span::styled(
+            String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+            Style::default().fg(theme.accent),
+        ),
+    ];
+    for attribute in start.attributes() {
+        let attribute = attribute.ok()?;
+        spans.extend([
+            Span::styled(
+                format!(" {}", String::from_utf8_lossy(attribute.key.as_ref())),
+                Style::default().fg(theme.symbol),
+            ),
+            punct("="),
+            Span::styled(
+                format!("\"{}\"", String::from_utf8_lossy(&attribute.value)),
+                Style::default().fg(theme.passed),
+            ),
+        ]);
+    }
+    spans.push(punct(end));
+    Some(spans)
+}
@@ -0,0 +1,49 @@
+use crate::config::Config;
+use crate::test_id::IdFormat;
+use crate::{complete, search};
+use anyhow::{Context, Result};
+use junit_parser::{TestCase, TestStatus};
+
+/// Patterns naming tests known to fail, from the config's `quarantine`
+/// list and `quarantine_file`. Each is a glob matched against the test's
+/// `classname.name`, as in [`search::glob_match`].
+#[derive(Debug, Default)]
+pub struct Quarantine {
+    patterns: Vec<String>,
+}
+
+impl Quarantine {
+    /// Collects the configured patterns. The file has one pattern per line;
+    /// blank lines and lines starting with `#` are ignored.
+    pub fn load(config: &Config) -> Result<Self> {
+        let mut patterns = config.quarantine.clone();
+        if let Some(file) = &config.quarantine_file {
+            let path = complete::expand_home(file);
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read quarantine file: {}", path.display()))?;
+            patterns.extend(
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(String::from),
+            );
+        }
+        Ok(Quarantine { patterns })
+    }
+
+    /// Whether `tc` failed or errored and matches a pattern.
+    pub fn contains(&self, tc: &TestCase) -> bool {
+        if !matches!(tc.status(), TestStatus::Failed | TestStatus::Errored) {
+            return false;
+        }
+        let id = IdFormat::Dotted.format(tc);
+        self.patterns
+            .iter()
+            .any(|pattern| search::glob_match(pattern, &id))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
@@ -0,0 +1,69 @@
+use crate::app::FileReport;
+use crate::{editor, test_id};
+use anyhow::Result;
+use junit_parser::{TestCase, TestStatus};
+use serde_json::{json, Value};
+
+/// A SARIF 2.1.0 log with a result for every failed or errored test, so
+/// code scanning and editors that read SARIF show failures at the code.
+/// Results are placed as for [`editor::failure_location`], falling back to
+/// the report file.
+pub fn log(files: &[FileReport]) -> Result<String> {
+    let results: Vec<Value> = files
+        .iter()
+        .flat_map(|f| f.data.all_cases().map(move |(_, tc)| (f, tc)))
+        .filter(|(_, tc)| matches!(tc.status(), TestStatus::Failed | TestStatus::Errored))
+        .map(|(file, tc)| result(file, tc))
+        .collect();
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "ratunit",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": [
+                        {
+                            "id": "test-failure",
+                            "shortDescription": {"text": "A test failed an assertion"},
+                        },
+                        {
+                            "id": "test-error",
+                            "shortDescription": {"text": "A test raised an unexpected error"},
+                        },
+                    ],
+                },
+            },
+            "results": results,
+        }],
+    });
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+fn result(file: &FileReport, tc: &TestCase) -> Value {
+    let (path, line) =
+        editor::failure_location(tc).unwrap_or_else(|| (editor::repo_relative(&file.path), 1));
+    let name = test_id::IdFormat::Dotted.format(tc);
+    let text = match tc.failure_text().filter(|t| !t.is_empty()) {
+        Some(text) => format!("{}\n\n{}", name, text),
+        None => name,
+    };
+    json!({
+        "ruleId": match tc.status() {
+            TestStatus::Errored => "test-error",
+            _ => "test-failure",
+        },
+        "level": "error",
+        "message": {"text": text},
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {"uri": path},
+                "region": {"startLine": line},
+            },
+        }],
+        "partialFingerprints": {
+            "ratunitTest/v1": test_id::fingerprint(&file.filename, tc),
+        },
+    })
+}
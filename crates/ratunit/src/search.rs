@@ -0,0 +1,124 @@
+use std::ops::Range;
+
+/// Case-insensitive (ASCII) substring search returning the byte range of
+/// the first match. An empty needle never matches.
+pub fn find_ignore_case(haystack: &str, needle: &str) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    haystack.char_indices().find_map(|(start, _)| {
+        let rest = &haystack[start..];
+        let mut rest_chars = rest.char_indices();
+        for n in needle.chars() {
+            match rest_chars.next() {
+                Some((_, h)) if h.eq_ignore_ascii_case(&n) => {}
+                _ => return None,
+            }
+        }
+        let len = rest_chars.next().map(|(i, _)| i).unwrap_or(rest.len());
+        Some(start..start + len)
+    })
+}
+
+/// All non-overlapping case-insensitive matches of `needle` in `haystack`.
+pub fn match_ranges(haystack: &str, needle: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+    while let Some(r) = find_ignore_case(&haystack[offset..], needle) {
+        let range = offset + r.start..offset + r.end;
+        offset = range.end;
+        ranges.push(range);
+    }
+    ranges
+}
+
+/// Whether `name` matches the glob `pattern`, where `*` stands for any
+/// run of characters and `?` for one. A pattern without wildcards names a
+/// package or class, so `com.example` matches `com.example` and everything
+/// in it.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return name
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'));
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was and how much of `name` it has taken so far.
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((sp, sn)) => {
+                    star = Some((sp, sn + 1));
+                    p = sp + 1;
+                    n = sn + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Scores `haystack` as a fuzzy (subsequence) match for `needle`, higher
+/// being better. Returns `None` when the characters of `needle` do not all
+/// appear in order. Consecutive runs and matches at word boundaries score
+/// extra and every character skipped between hits costs a point, so `lst`
+/// ranks `LoginServiceTest` above `testLastLogin`.
+///
+/// Every possible starting position is tried and the best score kept, so a
+/// contiguous hit late in the text beats a scattered one near the start.
+pub fn fuzzy_score(haystack: &str, needle: &str) -> Option<i64> {
+    let needle: Vec<char> = needle.chars().filter(|c| !c.is_whitespace()).collect();
+    let hay: Vec<char> = haystack.chars().collect();
+    let Some(first) = needle.first() else {
+        return Some(0);
+    };
+
+    (0..hay.len())
+        .filter(|&start| hay[start].eq_ignore_ascii_case(first))
+        .filter_map(|start| score_from(&hay, &needle, start))
+        .max()
+}
+
+fn score_from(hay: &[char], needle: &[char], start: usize) -> Option<i64> {
+    let mut score = -(start as i64) / 4;
+    let mut next = 0;
+    let mut last_hit = start;
+
+    for i in start..hay.len() {
+        if next == needle.len() {
+            break;
+        }
+        let h = hay[i];
+        if h.eq_ignore_ascii_case(&needle[next]) {
+            next += 1;
+            score += 1;
+            if next > 1 && i == last_hit + 1 {
+                score += 5;
+            } else if next > 1 {
+                score -= (i - last_hit - 1) as i64;
+            }
+            let boundary = match i.checked_sub(1).map(|p| hay[p]) {
+                None => true,
+                Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && h.is_uppercase()),
+            };
+            if boundary {
+                score += 8;
+            }
+            last_hit = i;
+        }
+    }
+
+    (next == needle.len()).then_some(score)
+}
@@ -0,0 +1,92 @@
+use crate::app::FileSort;
+use crate::test_id::IdFormat;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Settings changed at runtime that should stick between runs, kept in
+/// `$XDG_STATE_HOME/ratunit/state.toml`. Unlike the config file this is
+/// written by ratunit itself.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    /// Width of the file sidebar, in percent of the screen.
+    pub sidebar_width: Option<u16>,
+    /// Passed tests are hidden with `P`.
+    pub hide_passed: bool,
+    /// The test list's class and file columns are hidden with `c`.
+    pub hide_columns: bool,
+    /// Sidebar order picked with `S`.
+    pub file_sort: FileSort,
+    /// Test identifier format last copied in with `ctrl-y`.
+    pub id_format: IdFormat,
+    /// Where the user left off, keyed by the canonical report path given
+    /// on the command line.
+    pub positions: BTreeMap<String, Position>,
+}
+
+/// The file, suite, test and view open when ratunit last exited. Names
+/// rather than indices, so they survive the report being regenerated.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Position {
+    /// `None` for the "All files" view.
+    pub file: Option<String>,
+    pub suite: Option<String>,
+    pub test: Option<String>,
+    pub classname: Option<String>,
+    pub view: SavedView,
+    /// The tree view was in use rather than the suite → test drill-down.
+    pub tree: bool,
+}
+
+/// The navigation views worth returning to. Overviews like the diff or
+/// history are saved as the list they were opened from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SavedView {
+    #[default]
+    Suites,
+    Tests,
+    Detail,
+}
+
+/// `$XDG_STATE_HOME/ratunit/state.toml`, defaulting to
+/// `~/.local/state/ratunit/state.toml`.
+pub fn default_path() -> Option<PathBuf> {
+    Some(dir()?.join("state.toml"))
+}
+
+/// `$XDG_STATE_HOME/ratunit`, defaulting to `~/.local/state/ratunit`.
+pub fn dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("state"))
+        })?;
+    Some(base.join("ratunit"))
+}
+
+/// Loads the saved state. A missing or unreadable file yields the
+/// defaults: losing it should never stop ratunit from starting.
+pub fn load() -> State {
+    default_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(state: &State) -> Result<()> {
+    let Some(path) = default_path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let content = toml::to_string(state).context("Failed to serialize state")?;
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write state: {}", path.display()))
+}
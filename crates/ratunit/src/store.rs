@@ -0,0 +1,378 @@
+use crate::{history, state, test_id};
+use anyhow::{bail, Context, Result};
+use crossterm::style::{Color, Stylize};
+use junit_parser::{TestStatus, TestSuites};
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+
+/// One row per run and one per test case in each, created on first use.
+/// Times are Unix seconds.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    source TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS results (
+    run_id INTEGER NOT NULL REFERENCES runs(id),
+    suite TEXT NOT NULL,
+    classname TEXT,
+    name TEXT NOT NULL,
+    status TEXT NOT NULL,
+    time REAL,
+    message TEXT
+);
+CREATE INDEX IF NOT EXISTS results_name ON results(name);
+";
+
+/// `$XDG_STATE_HOME/ratunit/history.sqlite`, beside the saved state.
+pub fn default_path() -> Option<PathBuf> {
+    Some(state::dir()?.join("history.sqlite"))
+}
+
+/// The database of past runs behind `ratunit history`.
+pub struct Store {
+    conn: Connection,
+}
+
+/// One test's results, oldest run first.
+pub struct TestRecord {
+    pub suite: String,
+    pub classname: Option<String>,
+    pub name: String,
+    pub results: Vec<RunResult>,
+}
+
+pub struct RunResult {
+    pub run: i64,
+    /// UTC, as `YYYY-MM-DD HH:MM:SS`.
+    pub recorded_at: String,
+    pub status: TestStatus,
+    pub time: Option<f64>,
+    pub message: Option<String>,
+}
+
+impl Store {
+    /// Opens the database at `path`, creating it and its directory if need
+    /// be.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open history {}", path.display()))?;
+        Store::with_connection(conn)
+            .with_context(|| format!("Failed to set up history {}", path.display()))
+    }
+
+    /// Sets up the tables on `conn` if it doesn't have them yet.
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Store { conn })
+    }
+
+    /// Records every test case in `report` as one new run, and returns its
+    /// number.
+    pub fn record(&mut self, report: &TestSuites, source: &str) -> Result<i64> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO runs (recorded_at, source) VALUES (unixepoch(), ?1)",
+            params![source],
+        )?;
+        let run = tx.last_insert_rowid();
+        {
+            let mut insert = tx.prepare(
+                "INSERT INTO results (run_id, suite, classname, name, status, time, message)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            for (suite, tc) in report.all_cases() {
+                insert.execute(params![
+                    run,
+                    suite.name,
+                    tc.classname,
+                    tc.name,
+                    status_name(tc.status()),
+                    tc.time,
+                    tc.failure_line(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(run)
+    }
+
+    /// The recorded results of every test whose `classname.name`, or
+    /// name alone, is `test`.
+    pub fn test_records(&self, test: &str) -> Result<Vec<TestRecord>> {
+        let mut query = self.conn.prepare(
+            "SELECT r.suite, r.classname, r.name, r.run_id,
+                    datetime(runs.recorded_at, 'unixepoch'), r.status, r.time, r.message
+             FROM results r JOIN runs ON runs.id = r.run_id
+             WHERE r.name = ?1 OR r.classname || '.' || r.name = ?1
+             ORDER BY r.suite, r.classname, r.name, r.run_id",
+        )?;
+        let mut rows = query.query(params![test])?;
+        let mut records: Vec<TestRecord> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let suite: String = row.get(0)?;
+            let classname: Option<String> = row.get(1)?;
+            let name: String = row.get(2)?;
+            let status: String = row.get(5)?;
+            let result = RunResult {
+                run: row.get(3)?,
+                recorded_at: row.get(4)?,
+                status: parse_status(&status)?,
+                time: row.get(6)?,
+                message: row.get(7)?,
+            };
+            match records.last_mut() {
+                Some(last)
+                    if last.suite == suite && last.classname == classname && last.name == name =>
+                {
+                    last.results.push(result)
+                }
+                _ => records.push(TestRecord {
+                    suite,
+                    classname,
+                    name,
+                    results: vec![result],
+                }),
+            }
+        }
+        Ok(records)
+    }
+}
+
+impl TestRecord {
+    /// Results that ran, leaving out skips.
+    fn ran(&self) -> impl DoubleEndedIterator<Item = &RunResult> {
+        self.results
+            .iter()
+            .filter(|r| r.status != TestStatus::Skipped)
+    }
+
+    /// Whether the latest run passed, and how many runs in a row ended the
+    /// same way. Skips neither extend nor break a streak.
+    pub fn streak(&self) -> Option<(bool, usize)> {
+        let mut ran = self.ran().rev().map(|r| r.status == TestStatus::Passed);
+        let passing = ran.next()?;
+        Some((passing, 1 + ran.take_while(|&p| p == passing).count()))
+    }
+
+    /// The most runs in a row that failed or errored.
+    pub fn longest_failing_streak(&self) -> usize {
+        let mut longest = 0;
+        let mut current = 0;
+        for r in self.ran() {
+            if r.status == TestStatus::Passed {
+                current = 0;
+            } else {
+                current += 1;
+                longest = longest.max(current);
+            }
+        }
+        longest
+    }
+
+    /// How often the outcome flipped between passing and failing from one
+    /// run to the next, from 0 (never) to 1 (every run). A test that
+    /// fails steadily isn't flaky, one that alternates is.
+    pub fn flakiness(&self) -> f64 {
+        let passes: Vec<bool> = self.ran().map(|r| r.status == TestStatus::Passed).collect();
+        if passes.len() < 2 {
+            return 0.0;
+        }
+        let flips = passes.windows(2).filter(|w| w[0] != w[1]).count();
+        flips as f64 / (passes.len() - 1) as f64
+    }
+}
+
+/// Prints each test's record for `ratunit history show`: its pass count,
+/// streaks, flakiness and durations over every recorded run, then the
+/// `last` most recent runs.
+pub fn print_records(records: &[TestRecord], last: usize, color: bool) {
+    let paint = |text: String, c: Color| {
+        if color {
+            text.with(c).bold().to_string()
+        } else {
+            text
+        }
+    };
+    for (i, record) in records.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let name = test_id::dotted(record.classname.as_deref(), &record.name);
+        println!("{} ({})", paint(name, Color::Cyan), record.suite);
+
+        let runs = record.results.len();
+        let passed = record
+            .results
+            .iter()
+            .filter(|r| r.status == TestStatus::Passed)
+            .count();
+        let failed = record.ran().count() - passed;
+        let mut line = format!("  {} runs: {} passed, {} failed", runs, passed, failed);
+        if runs > passed + failed {
+            line.push_str(&format!(", {} skipped", runs - passed - failed));
+        }
+        println!("{}", line);
+
+        if let Some((passing, n)) = record.streak() {
+            let streak = if passing {
+                paint(format!("{} passed", n), Color::Green)
+            } else {
+                paint(format!("{} failed", n), Color::Red)
+            };
+            println!(
+                "  Streak: {} in a row; longest failing streak {}",
+                streak,
+                record.longest_failing_streak()
+            );
+        }
+        println!("  Flakiness: {:.2}", record.flakiness());
+
+        let times: Vec<f64> = record.results.iter().filter_map(|r| r.time).collect();
+        if let Some(&latest) = times.last() {
+            let mean = times.iter().sum::<f64>() / times.len() as f64;
+            let min = times.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = times.iter().cloned().fold(0.0, f64::max);
+            println!(
+                "  Time: {:.2}s latest, {:.2}s mean, {:.2}s–{:.2}s",
+                latest, mean, min, max
+            );
+            let trend: Vec<Option<f64>> = record.results.iter().map(|r| r.time).collect();
+            println!("  Trend: {}", history::sparkline(&trend));
+        }
+
+        println!("  Recent runs (UTC):");
+        for r in record.results.iter().rev().take(last) {
+            let badge = match r.status {
+                TestStatus::Passed => paint("PASS".into(), Color::Green),
+                TestStatus::Failed => paint("FAIL".into(), Color::Red),
+                TestStatus::Errored => paint("ERR ".into(), Color::Magenta),
+                TestStatus::Skipped => paint("SKIP".into(), Color::Yellow),
+            };
+            let time = r.time.map(|t| format!("{:.2}s", t)).unwrap_or_default();
+            let mut line = format!("    #{:<4} {}  {} {:>8}", r.run, r.recorded_at, badge, time);
+            if let Some(message) = &r.message {
+                line.push_str(&format!("  {}", message));
+            }
+            println!("{}", line);
+        }
+    }
+}
+
+fn status_name(status: TestStatus) -> &'static str {
+    match status {
+        TestStatus::Passed => "passed",
+        TestStatus::Failed => "failed",
+        TestStatus::Errored => "errored",
+        TestStatus::Skipped => "skipped",
+    }
+}
+
+fn parse_status(name: &str) -> Result<TestStatus> {
+    match TestStatus::ALL
+        .into_iter()
+        .find(|&s| status_name(s) == name)
+    {
+        Some(status) => Ok(status),
+        None => bail!("Unknown test status in history: {}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> Store {
+        Store::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    /// A run of one test, `C.t`, with the given outcome.
+    fn run(outcome: &str) -> TestSuites {
+        let body = match outcome {
+            "pass" => "",
+            "fail" => r#"<failure message="boom&#10;detail"/>"#,
+            "error" => r#"<error message="npe"/>"#,
+            "skip" => "<skipped/>",
+            _ => unreachable!(),
+        };
+        junit_parser::parse_str(&format!(
+            r#"<testsuite name="s"><testcase classname="C" name="t" time="0.5">{}</testcase></testsuite>"#,
+            body
+        ))
+        .unwrap()
+    }
+
+    fn record(outcomes: &[&str]) -> TestRecord {
+        let mut store = store();
+        for outcome in outcomes {
+            store.record(&run(outcome), "r.xml").unwrap();
+        }
+        let mut records = store.test_records("C.t").unwrap();
+        assert_eq!(records.len(), 1);
+        records.remove(0)
+    }
+
+    #[test]
+    fn records_and_reads_back_runs() {
+        let record = record(&["pass", "fail", "error"]);
+        assert_eq!(record.suite, "s");
+        assert_eq!(record.classname.as_deref(), Some("C"));
+        let runs: Vec<i64> = record.results.iter().map(|r| r.run).collect();
+        assert_eq!(runs, vec![1, 2, 3]);
+        assert_eq!(record.results[1].status, TestStatus::Failed);
+        assert_eq!(record.results[1].message.as_deref(), Some("boom"));
+        assert_eq!(record.results[2].status, TestStatus::Errored);
+        assert_eq!(record.results[0].time, Some(0.5));
+    }
+
+    #[test]
+    fn finds_tests_by_full_name_or_name() {
+        let mut store = store();
+        store.record(&run("pass"), "r.xml").unwrap();
+        assert_eq!(store.test_records("t").unwrap().len(), 1);
+        assert_eq!(store.test_records("C.t").unwrap().len(), 1);
+        assert!(store.test_records("D.t").unwrap().is_empty());
+    }
+
+    #[test]
+    fn streak_after_pass_fail_fail() {
+        let record = record(&["pass", "fail", "fail"]);
+        assert_eq!(record.streak(), Some((false, 2)));
+        assert_eq!(record.longest_failing_streak(), 2);
+        assert_eq!(record.flakiness(), 0.5);
+    }
+
+    #[test]
+    fn skips_do_not_break_a_streak() {
+        let record = record(&["fail", "pass", "skip", "pass"]);
+        assert_eq!(record.streak(), Some((true, 2)));
+        assert_eq!(record.longest_failing_streak(), 1);
+    }
+
+    #[test]
+    fn alternating_outcomes_are_fully_flaky() {
+        let record = record(&["pass", "fail", "pass", "error", "pass"]);
+        assert_eq!(record.flakiness(), 1.0);
+        assert_eq!(record.streak(), Some((true, 1)));
+        assert_eq!(record.longest_failing_streak(), 1);
+    }
+
+    #[test]
+    fn empty_history() {
+        assert!(store().test_records("C.t").unwrap().is_empty());
+        let record = TestRecord {
+            suite: "s".into(),
+            classname: Some("C".into()),
+            name: "t".into(),
+            results: Vec::new(),
+        };
+        assert_eq!(record.streak(), None);
+        assert_eq!(record.longest_failing_streak(), 0);
+        assert_eq!(record.flakiness(), 0.0);
+    }
+}
@@ -0,0 +1,651 @@
+use crate::app::{App, Baseline, FileReport};
+use crate::html;
+use crate::quarantine::Quarantine;
+use crate::test_id::IdFormat;
+use crate::ui;
+use anyhow::Result;
+use crossterm::style::{Color, Stylize};
+use junit_parser::{ReportDiff, TestCase, TestStatus, TestSuite, TestSuites};
+use serde::Serialize;
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+/// Failing tests listed by [`print_exit`] before it gives a count of
+/// the rest.
+const SUMMARY_FAILURES: usize = 20;
+
+/// Prints the totals and the failing tests, one per line, for the
+/// scrollback after quitting.
+pub fn print_exit(app: &App, color: bool) {
+    let paint = |text: String, c: Color| paint(text, c, color);
+    let failed = app.aggregate_failures();
+    let errors = app.aggregate_errors();
+    let mut totals = vec![
+        format!("{} tests", app.aggregate_tests()),
+        paint(format!("{} passed", app.aggregate_passed()), Color::Green),
+    ];
+    if failed > 0 {
+        totals.push(paint(format!("{} failed", failed), Color::Red));
+    }
+    if errors > 0 {
+        totals.push(paint(format!("{} errors", errors), Color::Magenta));
+    }
+    let skipped = app.aggregate_skipped();
+    if skipped > 0 {
+        totals.push(paint(format!("{} skipped", skipped), Color::Yellow));
+    }
+    let quarantined = app.aggregate_quarantined();
+    if quarantined > 0 {
+        totals.push(format!("{} quarantined", quarantined));
+    }
+    println!("ratunit: {}", totals.join(", "));
+
+    let failing: Vec<String> = failing_tests(&app.files)
+        .map(|(f, _, tc)| failing_line(f, tc, &app.quarantine, app.multi_file, color))
+        .collect();
+    for line in failing.iter().take(SUMMARY_FAILURES) {
+        println!("{}", line);
+    }
+    if failing.len() > SUMMARY_FAILURES {
+        println!("  … and {} more", failing.len() - SUMMARY_FAILURES);
+    }
+}
+
+/// Prints the failure and error counts against their limits, and the
+/// pass rate against `min_pass_rate` when given, then the failing tests
+/// when over a limit, for `ratunit check`. Quarantined tests don't count,
+/// and with a baseline neither do tests it had failing, so only
+/// regressions fail the check. Returns whether the reports are within the
+/// limits.
+pub fn print_check(
+    files: &[FileReport],
+    quarantine: &Quarantine,
+    baseline: Option<&Baseline>,
+    max_failures: u64,
+    max_errors: u64,
+    min_pass_rate: Option<f64>,
+    color: bool,
+) -> bool {
+    let counted: Vec<_> = counted_failures(files, quarantine, baseline).collect();
+    let errors = counted
+        .iter()
+        .filter(|(_, _, tc)| tc.status() == TestStatus::Errored)
+        .count() as u64;
+    let failures = counted.len() as u64 - errors;
+    let rate = pass_rate(files, quarantine);
+    let ok = failures <= max_failures
+        && errors <= max_errors
+        && min_pass_rate.is_none_or(|min| rate >= min);
+    let verdict = if ok {
+        paint("passed".into(), Color::Green, color)
+    } else {
+        paint("failed".into(), Color::Red, color)
+    };
+    let new = if baseline.is_some() { "new " } else { "" };
+    let mut line = format!(
+        "ratunit check {}: {} {}failures (max {}), {} {}errors (max {})",
+        verdict, failures, new, max_failures, errors, new, max_errors
+    );
+    if let Some(min) = min_pass_rate {
+        line.push_str(&format!(", {:.2}% passed (min {}%)", rate, min));
+    }
+    println!("{}", line);
+    if !ok {
+        for (f, _, tc) in counted {
+            println!(
+                "{}",
+                failing_line(f, tc, quarantine, files.len() > 1, color)
+            );
+        }
+    }
+    ok
+}
+
+/// The percentage of tests that ran which passed, leaving out skipped and
+/// quarantined tests; 100 when none ran.
+pub fn pass_rate(files: &[FileReport], quarantine: &Quarantine) -> f64 {
+    let (mut ran, mut passed) = (0u64, 0u64);
+    for (_, tc) in files.iter().flat_map(|f| f.data.all_cases()) {
+        match tc.status() {
+            _ if quarantine.contains(tc) => {}
+            TestStatus::Skipped => {}
+            TestStatus::Passed => {
+                ran += 1;
+                passed += 1;
+            }
+            TestStatus::Failed | TestStatus::Errored => ran += 1,
+        }
+    }
+    match ran {
+        0 => 100.0,
+        n => passed as f64 * 100.0 / n as f64,
+    }
+}
+
+/// Every failed or errored test with its report and suite, in report
+/// order.
+fn failing_tests(
+    files: &[FileReport],
+) -> impl Iterator<Item = (&FileReport, &TestSuite, &TestCase)> {
+    files
+        .iter()
+        .flat_map(|f| f.data.all_cases().map(move |(s, tc)| (f, s, tc)))
+        .filter(|(_, _, tc)| matches!(tc.status(), TestStatus::Failed | TestStatus::Errored))
+}
+
+/// The failing tests that count against `ratunit check`'s limits: not
+/// quarantined, and not already failing in `baseline`.
+pub fn counted_failures<'a>(
+    files: &'a [FileReport],
+    quarantine: &'a Quarantine,
+    baseline: Option<&'a Baseline>,
+) -> impl Iterator<Item = (&'a FileReport, &'a TestSuite, &'a TestCase)> {
+    failing_tests(files)
+        .filter(|(_, _, tc)| !quarantine.contains(tc))
+        .filter(move |(_, suite, tc)| baseline.is_none_or(|b| !b.is_known(suite, tc)))
+}
+
+/// A failing test's badge and full name, and its report when there are
+/// several.
+fn failing_line(
+    file: &FileReport,
+    tc: &TestCase,
+    quarantine: &Quarantine,
+    multi_file: bool,
+    color: bool,
+) -> String {
+    let badge = if quarantine.contains(tc) {
+        "QUAR".to_string()
+    } else if tc.status() == TestStatus::Errored {
+        paint("ERR ".into(), Color::Magenta, color)
+    } else {
+        paint("FAIL".into(), Color::Red, color)
+    };
+    if multi_file {
+        format!(
+            "  {} {} ({})",
+            badge,
+            IdFormat::Dotted.format(tc),
+            file.filename
+        )
+    } else {
+        format!("  {} {}", badge, IdFormat::Dotted.format(tc))
+    }
+}
+
+/// Widest a suite name gets in [`print_table`] before it is cut short.
+const NAME_WIDTH: usize = 60;
+
+/// Prints each report's suites with their counts and time, and a totals
+/// row, for `ratunit summary`.
+pub fn print_table(files: &[FileReport], color: bool) {
+    let width = files
+        .iter()
+        .flat_map(|f| &f.data.suites)
+        .map(|s| s.name.width().min(NAME_WIDTH))
+        .max()
+        .unwrap_or(0)
+        .max("Total".len());
+    for (i, file) in files.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        if files.len() > 1 {
+            println!("{}", paint(file.filename.clone(), Color::Cyan, color));
+        }
+        println!(
+            "  {:<width$}  {:>6}  {:>6}  {:>6}  {:>6}  {:>8}",
+            "Suite",
+            "Tests",
+            "Pass",
+            "Fail",
+            "Skip",
+            "Time",
+            width = width
+        );
+        let mut total = Counts::default();
+        for suite in &file.data.suites {
+            let counts = Counts::of(suite);
+            print_row(&fit(&suite.name, width), &counts, color);
+            total.add(&counts);
+        }
+        print_row(
+            &format!("{:<width$}", "Total", width = width),
+            &total,
+            color,
+        );
+    }
+}
+
+/// One of the slowest tests, with its share of the total test time and the
+/// running total of shares down to it.
+struct SlowTest<'a> {
+    file: &'a FileReport,
+    suite: &'a TestSuite,
+    tc: &'a TestCase,
+    time: f64,
+    share: f64,
+    cumulative: f64,
+}
+
+/// The `n` slowest tests across every report, slowest first. Shares are
+/// percentages of the summed test case times.
+fn slowest(files: &[FileReport], n: usize) -> Vec<SlowTest<'_>> {
+    let mut timed: Vec<_> = files
+        .iter()
+        .flat_map(|f| f.data.all_cases().map(move |(s, tc)| (f, s, tc)))
+        .filter_map(|(f, s, tc)| Some((f, s, tc, tc.time?)))
+        .collect();
+    let total: f64 = timed.iter().map(|t| t.3).sum();
+    timed.sort_by(|a, b| b.3.total_cmp(&a.3));
+    let mut cumulative = 0.0;
+    timed
+        .into_iter()
+        .take(n)
+        .map(|(file, suite, tc, time)| {
+            let share = if total > 0.0 {
+                time * 100.0 / total
+            } else {
+                0.0
+            };
+            cumulative += share;
+            SlowTest {
+                file,
+                suite,
+                tc,
+                time,
+                share,
+                cumulative,
+            }
+        })
+        .collect()
+}
+
+/// Prints the `n` slowest tests with their suites, each one's share of the
+/// total test time and the cumulative share, after the table of
+/// `ratunit summary --slowest`.
+pub fn print_slowest(files: &[FileReport], n: usize, color: bool) {
+    let slow = slowest(files, n);
+    if slow.is_empty() {
+        return;
+    }
+    let total: f64 = files.iter().map(|f| f.data.total_case_time()).sum();
+    println!();
+    println!(
+        "{}",
+        paint(
+            format!("Slowest {} tests of {:.1}s", slow.len(), total),
+            Color::Cyan,
+            color
+        )
+    );
+    println!("  {:>8}  {:>6}  {:>6}  Test", "Time", "Share", "Cum.");
+    for t in &slow {
+        // The suite is left out when it's just the test's class.
+        let mut context = Vec::new();
+        if t.tc.classname.as_deref() != Some(t.suite.name.as_str()) {
+            context.push(t.suite.name.as_str());
+        }
+        if files.len() > 1 {
+            context.push(t.file.filename.as_str());
+        }
+        let mut line = format!(
+            "  {:>7.2}s  {:>5.1}%  {:>5.1}%  {}",
+            t.time,
+            t.share,
+            t.cumulative,
+            IdFormat::Dotted.format(t.tc)
+        );
+        if !context.is_empty() {
+            line.push_str(&format!(" ({})", context.join(", ")));
+        }
+        println!("{}", line);
+    }
+}
+
+/// The `n` slowest tests as a Markdown section, for
+/// `ratunit summary --format markdown --slowest`.
+fn slowest_markdown(files: &[FileReport], n: usize) -> String {
+    let slow = slowest(files, n);
+    if slow.is_empty() {
+        return String::new();
+    }
+    let mut md = format!(
+        "\n### Slowest {} tests\n\n| Test | Suite | Time | Share | Cumulative |\n|---|---|--:|--:|--:|\n",
+        slow.len()
+    );
+    for t in &slow {
+        md.push_str(&format!(
+            "| `{}` | {} | {:.2}s | {:.1}% | {:.1}% |\n",
+            IdFormat::Dotted.format(t.tc).replace('|', "\\|"),
+            t.suite.name.replace('|', "\\|"),
+            t.time,
+            t.share,
+            t.cumulative
+        ));
+    }
+    md
+}
+
+/// One report in the output of [`print_json`].
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    filename: &'a str,
+    path: &'a Path,
+    tests: u64,
+    passed: u64,
+    failures: u64,
+    errors: u64,
+    skipped: u64,
+    report: &'a TestSuites,
+}
+
+/// Prints every report, parsed, with its totals as a JSON array, for
+/// `ratunit summary --format json`.
+pub fn print_json(files: &[FileReport]) -> Result<()> {
+    let reports: Vec<JsonReport> = files
+        .iter()
+        .map(|f| JsonReport {
+            filename: &f.filename,
+            path: &f.path,
+            tests: f.data.total_tests(),
+            passed: f.data.total_passed(),
+            failures: f.data.total_failures(),
+            errors: f.data.total_errors(),
+            skipped: f.data.total_skipped(),
+            report: &f.data,
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&reports)?);
+    Ok(())
+}
+
+/// Prints a GitHub-flavoured Markdown report for `ratunit summary --format
+/// markdown`: the totals, each report's suite table, the `slowest` slowest
+/// tests when asked for, then every failing test in a collapsed
+/// `<details>` block.
+pub fn print_markdown(files: &[FileReport], slowest: Option<usize>) {
+    let tests: u64 = files.iter().map(|f| f.data.total_tests()).sum();
+    let passed: u64 = files.iter().map(|f| f.data.total_passed()).sum();
+    let failed: u64 = files.iter().map(|f| f.data.total_failures()).sum();
+    let errors: u64 = files.iter().map(|f| f.data.total_errors()).sum();
+    let skipped: u64 = files.iter().map(|f| f.data.total_skipped()).sum();
+    let failing: Vec<_> = failing_tests(files).collect();
+    let icon = if failing.is_empty() { "✅" } else { "❌" };
+    let mut md = format!("## {} {} tests, {} passed", icon, tests, passed);
+    for (n, label) in [(failed, "failed"), (errors, "errors"), (skipped, "skipped")] {
+        if n > 0 {
+            md.push_str(&format!(", {} {}", n, label));
+        }
+    }
+    md.push('\n');
+    for file in files {
+        if files.len() > 1 {
+            md.push_str(&format!("\n### {}\n", file.filename));
+        }
+        md.push('\n');
+        md.push_str(&suite_table(&file.data.suites));
+    }
+    if let Some(n) = slowest {
+        md.push_str(&slowest_markdown(files, n));
+    }
+
+    if !failing.is_empty() {
+        md.push_str("\n### Failures\n");
+    }
+    for (file, _, tc) in failing {
+        let badge = match tc.status() {
+            TestStatus::Errored => "ERR",
+            _ => "FAIL",
+        };
+        let mut summary = format!(
+            "<b>{}</b> <code>{}</code>",
+            badge,
+            html::escape(&IdFormat::Dotted.format(tc))
+        );
+        if files.len() > 1 {
+            summary.push_str(&format!(" ({})", html::escape(&file.filename)));
+        }
+        md.push_str(&format!("\n<details>\n<summary>{}</summary>\n\n", summary));
+        if let Some(block) = tc.failure_text().as_deref().and_then(ui::fenced) {
+            md.push_str(&block);
+            md.push('\n');
+        }
+        md.push_str("</details>\n");
+    }
+    print!("{}", md);
+}
+
+/// `suites` as a Markdown table with a totals row.
+pub fn suite_table<'a>(suites: impl IntoIterator<Item = &'a TestSuite>) -> String {
+    let mut table = String::from(
+        "| Suite | Tests | Passed | Failed | Skipped | Time |\n|---|--:|--:|--:|--:|--:|\n",
+    );
+    let mut total = Counts::default();
+    for suite in suites {
+        let counts = Counts::of(suite);
+        table.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {:.1}s |\n",
+            suite.name.replace('|', "\\|"),
+            counts.tests,
+            counts.passed,
+            counts.failed,
+            counts.skipped,
+            counts.time
+        ));
+        total.add(&counts);
+    }
+    table.push_str(&format!(
+        "| **Total** | **{}** | **{}** | **{}** | **{}** | **{:.1}s** |\n",
+        total.tests, total.passed, total.failed, total.skipped, total.time
+    ));
+    table
+}
+
+#[derive(Default)]
+struct Counts {
+    tests: u64,
+    passed: u64,
+    failed: u64,
+    skipped: u64,
+    time: f64,
+}
+
+impl Counts {
+    fn of(suite: &TestSuite) -> Self {
+        Counts {
+            tests: suite.tests,
+            passed: suite.passed(),
+            failed: suite.failures + suite.errors,
+            skipped: suite.skipped.unwrap_or(0),
+            time: suite.time.unwrap_or(0.0),
+        }
+    }
+
+    fn add(&mut self, other: &Counts) {
+        self.tests += other.tests;
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.skipped += other.skipped;
+        self.time += other.time;
+    }
+}
+
+/// One table row; counts are only colored when non-zero so the failures
+/// stand out.
+fn print_row(name: &str, counts: &Counts, color: bool) {
+    let cell = |n: u64, c: Color| {
+        let text = format!("{:>6}", n);
+        if n > 0 {
+            paint(text, c, color)
+        } else {
+            text
+        }
+    };
+    println!(
+        "  {}  {:>6}  {}  {}  {}  {:>7.1}s",
+        name,
+        counts.tests,
+        cell(counts.passed, Color::Green),
+        cell(counts.failed, Color::Red),
+        cell(counts.skipped, Color::Yellow),
+        counts.time
+    );
+}
+
+/// `text` cut to `width` columns with an ellipsis, then padded to it.
+fn fit(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    if text.width() <= width {
+        out.push_str(text);
+    } else {
+        let mut used = 0;
+        for c in text.chars() {
+            let w = c.to_string().width();
+            if used + w + 1 > width {
+                break;
+            }
+            used += w;
+            out.push(c);
+        }
+        out.push('…');
+    }
+    let pad = width.saturating_sub(out.width());
+    out + &" ".repeat(pad)
+}
+
+/// `text` in bold `c` when color is on.
+fn paint(text: String, c: Color, color: bool) -> String {
+    if color {
+        text.with(c).bold().to_string()
+    } else {
+        text
+    }
+}
+
+/// Prints what changed from `base` to `head` for `ratunit diff`: new
+/// failures with their messages, fixed tests, and tests that got slower.
+pub fn print_diff(base: &FileReport, head: &FileReport, diff: &ReportDiff, color: bool) {
+    println!(
+        "{} → {}: {} new failures, {} fixed, {} still failing, {} slower, +{} / −{} tests",
+        base.filename,
+        head.filename,
+        diff.new_failures.len(),
+        diff.fixed.len(),
+        diff.still_failing,
+        diff.slower.len(),
+        diff.added,
+        diff.removed
+    );
+    if !diff.new_failures.is_empty() {
+        println!("\nNew failures:");
+    }
+    for change in &diff.new_failures {
+        let badge = match change.after {
+            TestStatus::Errored => paint("ERR ".into(), Color::Magenta, color),
+            _ => paint("FAIL".into(), Color::Red, color),
+        };
+        let new = if change.before.is_none() {
+            "  (new)"
+        } else {
+            ""
+        };
+        println!("  {} {}{}", badge, change.test.label(), new);
+        if let Some(first) = change.message.as_deref().and_then(|m| m.lines().next()) {
+            println!("       {}", first);
+        }
+    }
+    if !diff.fixed.is_empty() {
+        println!("\nFixed:");
+    }
+    for change in &diff.fixed {
+        let badge = paint("PASS".into(), Color::Green, color);
+        println!("  {} {}", badge, change.test.label());
+    }
+    if !diff.slower.is_empty() {
+        println!("\nSlower:");
+    }
+    for change in &diff.slower {
+        let delta = paint(format!("{:>+8.2}s", change.delta()), Color::Red, color);
+        println!(
+            "  {}  {:>7.2}s → {:>7.2}s  {}",
+            delta,
+            change.before,
+            change.after,
+            change.test.label()
+        );
+    }
+}
+
+/// Prints the diff as JSON for `ratunit diff --format json`.
+pub fn print_diff_json(base: &FileReport, head: &FileReport, diff: &ReportDiff) -> Result<()> {
+    #[derive(Serialize)]
+    struct JsonDiff<'a> {
+        base: &'a Path,
+        head: &'a Path,
+        #[serde(flatten)]
+        diff: &'a ReportDiff,
+    }
+    let json = JsonDiff {
+        base: &base.path,
+        head: &head.path,
+        diff,
+    };
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+/// Prints the diff as GitHub-flavoured Markdown for `ratunit diff
+/// --format markdown`, ready for a PR comment.
+pub fn print_diff_markdown(base: &FileReport, head: &FileReport, diff: &ReportDiff) {
+    let icon = if diff.new_failures.is_empty() {
+        "✅"
+    } else {
+        "❌"
+    };
+    let mut md = format!(
+        "## {} `{}` → `{}`\n\n{} new failures, {} fixed, {} still failing, {} slower, +{} / −{} tests\n",
+        icon,
+        base.filename,
+        head.filename,
+        diff.new_failures.len(),
+        diff.fixed.len(),
+        diff.still_failing,
+        diff.slower.len(),
+        diff.added,
+        diff.removed
+    );
+    if !diff.new_failures.is_empty() {
+        md.push_str("\n### New failures\n\n");
+    }
+    for change in &diff.new_failures {
+        let new = if change.before.is_none() {
+            " (new)"
+        } else {
+            ""
+        };
+        md.push_str(&format!("- `{}`{}\n", change.test.label(), new));
+        if let Some(first) = change.message.as_deref().and_then(|m| m.lines().next()) {
+            md.push_str(&format!("  > {}\n", first));
+        }
+    }
+    if !diff.fixed.is_empty() {
+        md.push_str("\n### Fixed\n\n");
+    }
+    for change in &diff.fixed {
+        md.push_str(&format!("- `{}`\n", change.test.label()));
+    }
+    if !diff.slower.is_empty() {
+        md.push_str("\n### Slower\n\n| Test | Before | After | Change |\n|---|--:|--:|--:|\n");
+    }
+    for change in &diff.slower {
+        md.push_str(&format!(
+            "| `{}` | {:.2}s | {:.2}s | {:+.2}s |\n",
+            change.test.label().replace('|', "\\|"),
+            change.before,
+            change.after,
+            change.delta()
+        ));
+    }
+    print!("{}", md);
+}
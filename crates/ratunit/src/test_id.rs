@@ -0,0 +1,105 @@
+use junit_parser::TestCase;
+use serde::{Deserialize, Serialize};
+
+/// The ways a test can be named for a test runner's filter flag.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdFormat {
+    /// `com.example.LoginTest.badPassword`
+    #[default]
+    Dotted,
+    /// `com.example.LoginTest#badPassword`, as Maven's `-Dtest` and
+    /// Gradle's `--tests` take.
+    Method,
+    /// `auth::login::bad_password`, for `cargo test` and nextest filters.
+    Rust,
+    /// `tests/test_login.py::TestLogin::test_bad_password`, a pytest node
+    /// ID.
+    Pytest,
+}
+
+impl IdFormat {
+    pub const ALL: [IdFormat; 4] = [
+        IdFormat::Dotted,
+        IdFormat::Method,
+        IdFormat::Rust,
+        IdFormat::Pytest,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            IdFormat::Dotted => "class.name",
+            IdFormat::Method => "class#method",
+            IdFormat::Rust => "module::test",
+            IdFormat::Pytest => "pytest node ID",
+        }
+    }
+
+    /// `tc`'s identifier in this format. Tests without a classname are
+    /// named alone.
+    pub fn format(self, tc: &TestCase) -> String {
+        let Some(class) = tc.classname.as_deref().filter(|c| !c.is_empty()) else {
+            return tc.name.clone();
+        };
+        match self {
+            IdFormat::Dotted => dotted(Some(class), &tc.name),
+            IdFormat::Method => format!("{}#{}", class, tc.name),
+            // Runners that already write module paths into the name
+            // (nextest) put the binary in the classname instead.
+            IdFormat::Rust if tc.name.contains("::") => tc.name.clone(),
+            IdFormat::Rust => format!("{}::{}", class.replace('.', "::"), tc.name),
+            IdFormat::Pytest => pytest_node_id(class, tc),
+        }
+    }
+}
+
+/// `classname.name`, or the name alone without a classname: the
+/// [`IdFormat::Dotted`] form of a test known only by its names.
+pub fn dotted(classname: Option<&str>, name: &str) -> String {
+    match classname.filter(|c| !c.is_empty()) {
+        Some(class) => format!("{}.{}", class, name),
+        None => name.to_string(),
+    }
+}
+
+/// A hash of `tc`'s full name and its report's, the same from run to run,
+/// so services that track findings can tell a new failure from one they
+/// have seen before.
+pub fn fingerprint(report: &str, tc: &TestCase) -> String {
+    let name = IdFormat::Dotted.format(tc);
+    format!("{:016x}", fnv1a(&format!("{}\0{}", report, name)))
+}
+
+/// 64-bit FNV-1a: small, and the same on every platform and release.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// pytest writes `tests.test_login.TestLogin` as the classname: module
+/// path, then any classes. The module is taken from the recorded file when
+/// there is one, and otherwise ends before the first capitalised part.
+fn pytest_node_id(class: &str, tc: &TestCase) -> String {
+    let parts: Vec<&str> = class.split('.').collect();
+    let module_len = match tc.file.as_deref() {
+        Some(file) => file.trim_end_matches(".py").split('/').count(),
+        None => parts
+            .iter()
+            .position(|p| p.starts_with(|c: char| c.is_ascii_uppercase()))
+            .unwrap_or(parts.len()),
+    }
+    .min(parts.len());
+    let module = match tc.file.as_deref() {
+        Some(file) => file.to_string(),
+        None => format!("{}.py", parts[..module_len].join("/")),
+    };
+    let mut id = module;
+    for part in &parts[module_len..] {
+        id.push_str("::");
+        id.push_str(part);
+    }
+    id.push_str("::");
+    id.push_str(&tc.name);
+    id
+}
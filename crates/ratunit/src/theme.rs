@@ -0,0 +1,241 @@
+use crate::config::{Config, ThemeConfig};
+use anyhow::{bail, Result};
+use ratatui::style::Color;
+
+/// Colors for every role in the UI. Widgets look colors up here rather
+/// than naming them directly, so a theme can restyle the whole app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Borders, titles, key hints and field labels.
+    pub accent: Color,
+    pub text: Color,
+    /// Secondary information: times, zero counts, library stack frames.
+    pub muted: Color,
+    pub passed: Color,
+    pub failed: Color,
+    pub errored: Color,
+    pub skipped: Color,
+    pub selection_bg: Color,
+    pub status_bg: Color,
+    pub status_fg: Color,
+    pub match_fg: Color,
+    pub match_bg: Color,
+    pub stdout: Color,
+    pub stderr: Color,
+    /// File paths in stack traces.
+    pub location: Color,
+    /// Line numbers in stack traces.
+    pub line_number: Color,
+    /// Method and function names in stack traces.
+    pub symbol: Color,
+}
+
+pub const BUILTIN_THEMES: &[&str] = &["dark", "light", "solarized", "colorblind"];
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            accent: Color::Cyan,
+            text: Color::White,
+            muted: Color::DarkGray,
+            passed: Color::Green,
+            failed: Color::Red,
+            errored: Color::Magenta,
+            skipped: Color::Yellow,
+            selection_bg: Color::DarkGray,
+            status_bg: Color::DarkGray,
+            status_fg: Color::White,
+            match_fg: Color::Black,
+            match_bg: Color::Yellow,
+            stdout: Color::Blue,
+            stderr: Color::Yellow,
+            location: Color::Blue,
+            line_number: Color::Yellow,
+            symbol: Color::Cyan,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            accent: Color::Blue,
+            text: Color::Black,
+            muted: Color::Gray,
+            passed: Color::Green,
+            failed: Color::Red,
+            errored: Color::Magenta,
+            skipped: Color::Rgb(0xb5, 0x89, 0x00),
+            selection_bg: Color::Rgb(0xdd, 0xdd, 0xdd),
+            status_bg: Color::Rgb(0xdd, 0xdd, 0xdd),
+            status_fg: Color::Black,
+            match_fg: Color::Black,
+            match_bg: Color::LightYellow,
+            stdout: Color::Blue,
+            stderr: Color::Rgb(0xb5, 0x89, 0x00),
+            location: Color::Blue,
+            line_number: Color::Magenta,
+            symbol: Color::Rgb(0x00, 0x80, 0x80),
+        }
+    }
+
+    /// Solarized dark.
+    pub fn solarized() -> Self {
+        let base01 = Color::Rgb(0x58, 0x6e, 0x75);
+        let base02 = Color::Rgb(0x07, 0x36, 0x42);
+        let base1 = Color::Rgb(0x93, 0xa1, 0xa1);
+        let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+        let red = Color::Rgb(0xdc, 0x32, 0x2f);
+        let magenta = Color::Rgb(0xd3, 0x36, 0x82);
+        let blue = Color::Rgb(0x26, 0x8b, 0xd2);
+        let cyan = Color::Rgb(0x2a, 0xa1, 0x98);
+        let green = Color::Rgb(0x85, 0x99, 0x00);
+        Self {
+            accent: blue,
+            text: base1,
+            muted: base01,
+            passed: green,
+            failed: red,
+            errored: magenta,
+            skipped: yellow,
+            selection_bg: base02,
+            status_bg: base02,
+            status_fg: base1,
+            match_fg: base02,
+            match_bg: yellow,
+            stdout: blue,
+            stderr: yellow,
+            location: blue,
+            line_number: yellow,
+            symbol: cyan,
+        }
+    }
+
+    /// The Okabe–Ito palette, distinguishable with the common forms of
+    /// color blindness: blue passes and vermillion failures rather than
+    /// green and red.
+    pub fn colorblind() -> Self {
+        let orange = Color::Rgb(0xe6, 0x9f, 0x00);
+        let sky_blue = Color::Rgb(0x56, 0xb4, 0xe9);
+        let yellow = Color::Rgb(0xf0, 0xe4, 0x42);
+        let blue = Color::Rgb(0x00, 0x72, 0xb2);
+        let vermillion = Color::Rgb(0xd5, 0x5e, 0x00);
+        let purple = Color::Rgb(0xcc, 0x79, 0xa7);
+        Self {
+            accent: sky_blue,
+            text: Color::White,
+            muted: Color::DarkGray,
+            passed: blue,
+            failed: vermillion,
+            errored: purple,
+            skipped: yellow,
+            selection_bg: Color::DarkGray,
+            status_bg: Color::DarkGray,
+            status_fg: Color::White,
+            match_fg: Color::Black,
+            match_bg: yellow,
+            stdout: sky_blue,
+            stderr: orange,
+            location: sky_blue,
+            line_number: yellow,
+            symbol: orange,
+        }
+    }
+
+    /// The terminal's default colors everywhere, for `--no-color` and
+    /// `NO_COLOR`. Emphasis is left to bold text and status markers.
+    pub fn no_color() -> Self {
+        Self {
+            accent: Color::Reset,
+            text: Color::Reset,
+            muted: Color::Reset,
+            passed: Color::Reset,
+            failed: Color::Reset,
+            errored: Color::Reset,
+            skipped: Color::Reset,
+            selection_bg: Color::Reset,
+            status_bg: Color::Reset,
+            status_fg: Color::Reset,
+            match_fg: Color::Reset,
+            match_bg: Color::Reset,
+            stdout: Color::Reset,
+            stderr: Color::Reset,
+            location: Color::Reset,
+            line_number: Color::Reset,
+            symbol: Color::Reset,
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "colorblind" => Some(Self::colorblind()),
+            _ => None,
+        }
+    }
+
+    /// Resolves a theme by name: user-defined themes from the config file
+    /// take precedence over built-ins. With no name, the config's `theme`
+    /// key is used, then `dark`.
+    pub fn resolve(name: Option<&str>, config: &Config) -> Result<Self> {
+        let name = name.or(config.theme.as_deref()).unwrap_or("dark");
+        Self::resolve_named(name, config, 0)
+    }
+
+    fn resolve_named(name: &str, config: &Config, depth: usize) -> Result<Self> {
+        if depth > BUILTIN_THEMES.len() + config.themes.len() {
+            bail!("Theme '{}' inherits from itself", name);
+        }
+        if let Some(custom) = config.themes.get(name) {
+            let base = custom.base.as_deref().unwrap_or("dark");
+            // A custom theme may shadow a built-in and extend it.
+            let mut theme = match Self::builtin(base) {
+                Some(builtin) if base == name => builtin,
+                _ => Self::resolve_named(base, config, depth + 1)?,
+            };
+            theme.apply(custom);
+            return Ok(theme);
+        }
+        match Self::builtin(name) {
+            Some(theme) => Ok(theme),
+            None => bail!(
+                "Unknown theme '{}' (built-in themes: {})",
+                name,
+                BUILTIN_THEMES.join(", ")
+            ),
+        }
+    }
+
+    fn apply(&mut self, overrides: &ThemeConfig) {
+        let fields: [(&mut Color, Option<Color>); 17] = [
+            (&mut self.accent, overrides.accent),
+            (&mut self.text, overrides.text),
+            (&mut self.muted, overrides.muted),
+            (&mut self.passed, overrides.passed),
+            (&mut self.failed, overrides.failed),
+            (&mut self.errored, overrides.errored),
+            (&mut self.skipped, overrides.skipped),
+            (&mut self.selection_bg, overrides.selection_bg),
+            (&mut self.status_bg, overrides.status_bg),
+            (&mut self.status_fg, overrides.status_fg),
+            (&mut self.match_fg, overrides.match_fg),
+            (&mut self.match_bg, overrides.match_bg),
+            (&mut self.stdout, overrides.stdout),
+            (&mut self.stderr, overrides.stderr),
+            (&mut self.location, overrides.location),
+            (&mut self.line_number, overrides.line_number),
+            (&mut self.symbol, overrides.symbol),
+        ];
+        for (field, value) in fields {
+            if let Some(color) = value {
+                *field = color;
+            }
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
@@ -1,34 +1,917 @@
-use crate::app::{App, View};
-use junit_parser::TestStatus;
-use ratatui::layout::{Constraint, Layout, Rect};
+use crate::app::{
+    threshold_label, App, DetailPane, FileDiff, FileReport, FileSort, GroupRow, InputMode,
+    OutputLayout, PackageRow, StatusFilter, SuiteSort, TestRow, TestSort, TreeRow, View,
+};
+use crate::editor;
+use crate::pretty::{self, Segment};
+use crate::test_id::IdFormat;
+use crate::theme::Theme;
+use crate::{ansi, ascii, assertion, highlight, history, search};
+use junit_parser::diff::TestKey;
+use junit_parser::{ParameterizedGroup, SuiteStatus, TestCase, TestStatus, TestSuite};
+use ratatui::layout::{Constraint, Layout, Margin, Rect};
 use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::symbols::Marker;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::widgets::{
+    Axis, Block, BorderType, Borders, Cell, Chart, Clear, Dataset, GraphType, List, ListItem,
+    ListState, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState,
+    Tabs, Wrap,
+};
 use ratatui::Frame;
+use std::time::SystemTime;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Screen regions of the main layout, shared with mouse hit-testing.
+pub struct Areas {
+    /// The breadcrumb line at the top.
+    pub header: Rect,
+    pub sidebar: Option<Rect>,
+    pub content: Rect,
+    pub status: Rect,
+}
+
+pub fn areas(area: Rect, app: &App) -> Areas {
+    let [header_area, main_area, status_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Fill(1),
+        Constraint::Length(2),
+    ])
+    .areas(area);
+
+    if app.show_sidebar() {
+        let [sidebar_area, content_area] = Layout::horizontal([
+            Constraint::Percentage(app.sidebar_width),
+            Constraint::Fill(1),
+        ])
+        .areas(main_area);
+        Areas {
+            header: header_area,
+            sidebar: Some(sidebar_area),
+            content: content_area,
+            status: status_area,
+        }
+    } else {
+        Areas {
+            header: header_area,
+            sidebar: None,
+            content: main_area,
+            status: status_area,
+        }
+    }
+}
+
+/// The part of the content area holding the list: everything below the
+/// status tabs in the test list, the whole area elsewhere.
+pub fn list_area(content: Rect, app: &App) -> Rect {
+    if app.view != View::TestList {
+        return content;
+    }
+    let [_, list] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(content);
+    list
+}
 
 pub fn render(frame: &mut Frame, app: &App) {
-    let [main_area, status_area] =
-        Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(frame.area());
+    let areas = areas(frame.area(), app);
 
-    if app.multi_file {
-        let [sidebar_area, content_area] =
-            Layout::horizontal([Constraint::Percentage(25), Constraint::Percentage(75)])
-                .areas(main_area);
+    render_breadcrumb(frame, areas.header, app);
+    if let Some(sidebar_area) = areas.sidebar {
         render_file_sidebar(frame, sidebar_area, app);
-        render_content(frame, content_area, app);
+    }
+    render_content(frame, areas.content, app);
+    render_status_bar(frame, areas.status, app);
+
+    match app.input_mode {
+        InputMode::Finder => render_finder(frame, app),
+        InputMode::Help => render_help(frame, app),
+        InputMode::Properties => render_properties(frame, app),
+        InputMode::SuiteStats => render_suite_stats(frame, app),
+        InputMode::SuiteOutput => render_suite_output(frame, app),
+        InputMode::SuiteCompare => render_suite_compare(frame, app),
+        InputMode::CopyId => render_copy_id(frame, app),
+        InputMode::Normal
+        | InputMode::Search
+        | InputMode::SidebarFilter
+        | InputMode::GoToLine
+        | InputMode::ClassFilter
+        | InputMode::Export
+        | InputMode::AddFiles => {}
+    }
+
+    if app.ascii {
+        ascii::downgrade(frame.buffer_mut());
+    }
+}
+
+/// Where the user is, outermost first: file, then suite and test as far as
+/// the current view goes.
+fn breadcrumb(app: &App) -> Vec<String> {
+    let file = app.current_file();
+    let mut parts = vec![file.filename.clone()];
+    let suite_name = |si: usize| file.data.suites[si].name.clone();
+    let test_name = |si: usize, ti: usize| file.data.suites[si].test_cases[ti].name.clone();
+
+    match app.view {
+        View::SuiteList => {}
+        View::TestList => parts.extend(app.current_suite().map(|s| s.name.clone())),
+        View::TestDetail => {
+            parts.extend(app.current_suite().map(|s| s.name.clone()));
+            parts.extend(app.current_test().map(|tc| tc.name.clone()));
+        }
+        View::Tree => match app.tree_rows().get(app.tree_selected) {
+            Some(&TreeRow::Suite(si)) => parts.push(suite_name(si)),
+            Some(&TreeRow::Test(si, ti)) => parts.extend([suite_name(si), test_name(si, ti)]),
+            None => {}
+        },
+        View::Slowest => {
+            parts.push("Slowest tests".into());
+            if let Some(&(si, ti)) = app.slowest_rows().get(app.slowest_selected) {
+                parts.extend([suite_name(si), test_name(si, ti)]);
+            }
+        }
+        View::Groups => {
+            parts.push("Failure groups".into());
+            if let Some(&GroupRow::Test { suite, test, .. }) =
+                app.group_rows().get(app.groups_selected)
+            {
+                parts.extend([suite_name(suite), test_name(suite, test)]);
+            }
+        }
+        View::Skips => {
+            parts.push("Skipped tests".into());
+            if let Some(&GroupRow::Test { suite, test, .. }) =
+                app.skip_rows().get(app.skips_selected)
+            {
+                parts.extend([suite_name(suite), test_name(suite, test)]);
+            }
+        }
+        View::Packages => {
+            parts.push("Packages".into());
+            match app.package_rows().get(app.packages_selected) {
+                Some(PackageRow::Node { path, .. }) => parts.push(path.clone()),
+                Some(&PackageRow::Test { suite, test, .. }) => {
+                    parts.extend([suite_name(suite), test_name(suite, test)]);
+                }
+                None => {}
+            }
+        }
+        View::Durations => {
+            parts.push("Durations".into());
+            if let Some(&si) = app.visible_suites().get(app.durations_selected) {
+                parts.push(suite_name(si));
+            }
+        }
+        View::Diff => {
+            parts = match &app.diff {
+                Some(d) => vec![format!("{} → {}", d.base, d.head)],
+                None => Vec::new(),
+            };
+            parts.push("Diff".into());
+        }
+        View::History => parts = vec!["History".into()],
+    }
+    parts
+}
+
+/// Renders the breadcrumb, dropping leading parts when it doesn't fit so
+/// the innermost location stays visible.
+fn render_breadcrumb(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    const SEPARATOR: &str = " ▸ ";
+    let mut parts = breadcrumb(app);
+    let width = |parts: &[String]| {
+        parts.iter().map(|p| p.width()).sum::<usize>()
+            + parts.len().saturating_sub(1) * SEPARATOR.width()
+            + 1
+    };
+    let mut elided = false;
+    while parts.len() > 1 && width(&parts) + 4 > area.width as usize {
+        parts.remove(0);
+        elided = true;
+    }
+
+    let mut spans = vec![Span::raw(" ")];
+    if elided {
+        spans.push(Span::styled("…", Style::default().fg(theme.muted)));
+        spans.push(Span::styled(SEPARATOR, Style::default().fg(theme.muted)));
+    }
+    let last = parts.len().saturating_sub(1);
+    for (i, part) in parts.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(SEPARATOR, Style::default().fg(theme.muted)));
+        }
+        let style = if i == last {
+            Style::default().fg(theme.text).bold()
+        } else {
+            Style::default().fg(theme.accent)
+        };
+        spans.push(Span::styled(part, style));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Keybindings shown in the `?` overlay, grouped by where they apply.
+const HELP: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Global",
+        &[
+            ("q / Ctrl+C", "Quit"),
+            ("?", "Toggle this help"),
+            ("Ctrl+P", "Fuzzy-find any test across all files"),
+            ("Tab / Shift+Tab", "Next / previous file"),
+            (
+                "0–9",
+                "In the suite list or tree, jump to a file (0: All files)",
+            ),
+            ("< / >", "Narrow / widen the file sidebar"),
+            ("b", "Show / hide the file sidebar"),
+            ("S", "Sort files: name / most failures / newest first"),
+            ("X", "Remove the open file from this session"),
+            ("a", "Load another report or directory"),
+            (
+                "Ctrl+F",
+                "Filter files by name; a leading ! keeps failing files",
+            ),
+            ("f", "Cycle status filter"),
+            ("P", "Hide / show passed tests everywhere"),
+            ("M", "Show only tests slower than 100ms / 1s / 10s"),
+            ("L", "Show only classes matching a pattern"),
+            ("Q", "Count / ignore quarantined failures"),
+            ("t", "Toggle tree view"),
+            ("W", "Toggle reloading reports when they change"),
+            ("m", "Mark file for comparison"),
+            ("B", "Use file as baseline for the new failures filter"),
+            ("d", "Diff marked files"),
+            ("H", "Trends across runs"),
+            ("T", "Slowest tests"),
+            ("F", "Failures grouped by message; again for by exception"),
+            ("I", "Skipped tests grouped by reason"),
+            ("C", "Tests by package"),
+            ("D", "Duration histogram per suite"),
+            ("p", "Properties of the selected suite"),
+            ("O", "Output the selected suite logged outside its tests"),
+            ("A", "Compare the selected suite across files"),
+        ],
+    ),
+    (
+        "Lists",
+        &[
+            ("j / k, ↓ / ↑", "Move selection"),
+            ("g / G", "Jump to first / last"),
+            ("PgDn / PgUp", "Move ten rows"),
+            ("5j, 12G, 3Tab", "Repeat a motion / jump to row"),
+            ("Enter / l / →", "Open suite or test"),
+            ("Esc / h / ←", "Go back"),
+            ("/", "Search the current list"),
+            ("n / N", "Next / previous match"),
+            ("Ctrl+Y", "Copy the test's identifier for a runner filter"),
+        ],
+    ),
+    (
+        "Suite list",
+        &[
+            ("s", "Cycle sort: name / failures / duration / timestamp"),
+            ("i", "Pass rate, timings and slowest tests of the suite"),
+            ("y", "Copy the suites as a Markdown table"),
+        ],
+    ),
+    (
+        "Test list",
+        &[
+            ("s", "Cycle sort: duration / name / class / status"),
+            ("c", "Show / hide the class and file columns"),
+            ("Enter / h", "Expand / collapse a parameterized test"),
+            (
+                "Alt+1–5",
+                "Status tab: all / failed / errored / skipped / passed",
+            ),
+            ("[ / ]", "Previous / next status tab"),
+            ("Space", "Pick / unpick test"),
+            ("V", "Pick from the last picked test to here"),
+            ("y / Y", "Copy picked names / details"),
+            ("x", "Export picked details to a file"),
+            ("Esc", "Clear picks"),
+        ],
+    ),
+    (
+        "Failure groups",
+        &[
+            ("Space / Enter", "Expand / collapse group"),
+            ("Enter", "Open member test"),
+            ("Esc / h", "Collapse group, then go back"),
+        ],
+    ),
+    (
+        "Tree view",
+        &[
+            ("Space", "Expand / collapse suite"),
+            ("Enter", "Open test or expand suite"),
+            ("Esc / h", "Collapse suite"),
+            ("s", "Cycle test sort order"),
+        ],
+    ),
+    (
+        "Detail",
+        &[
+            ("j / k", "Scroll"),
+            ("g / G", "Jump to top / bottom"),
+            ("w", "Toggle line wrapping"),
+            ("h / l, ← / →", "Scroll sideways (wrapping off)"),
+            ("e", "Open the test's source file in $EDITOR"),
+            ("y", "Copy failure message and stack trace"),
+            ("Y", "Copy the whole detail"),
+            ("Ctrl+Y", "Copy the test's identifier for a runner filter"),
+            ("x", "Export the detail to a text or Markdown file"),
+            ("|", "Open the whole detail in $PAGER"),
+            ("o", "Cycle stdout/stderr: combined, side by side, stacked"),
+            ("Ctrl+W", "Scroll the next pane when split"),
+            ("J / K", "Next / previous test"),
+            (
+                "Enter",
+                "Pick a stack frame; j / k move, Enter opens it in $EDITOR",
+            ),
+            ("#", "Toggle line numbers"),
+            ("=", "Pretty-print embedded JSON / XML"),
+            ("r", "Toggle the test's raw XML"),
+            (
+                "} / {",
+                "Next / previous section: failure, error, stdout, stderr",
+            ),
+            (
+                ":",
+                "Go to a line of stdout (o), stderr (e) or the trace (t)",
+            ),
+            ("Esc / h", "Back to the list"),
+        ],
+    ),
+    (
+        "Mouse",
+        &[
+            ("Click", "Select row or file"),
+            ("Double-click", "Open row"),
+            ("Wheel", "Scroll"),
+        ],
+    ),
+];
+
+fn render_help(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(60, 80, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines: Vec<Line> = Vec::new();
+    let mut custom = app.keymap.custom().peekable();
+    if custom.peek().is_some() {
+        lines.push(Line::styled(
+            " Remapped in config (replaces the defaults below)",
+            Style::default().fg(theme.accent).bold(),
+        ));
+        for (action, keys) in custom {
+            let keys = match keys {
+                [] => "(unbound)".to_string(),
+                keys => keys
+                    .iter()
+                    .map(|k| k.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(format!("   {:<18}", keys), Style::default().bold()),
+                Span::raw(action.describe()),
+            ]));
+        }
+        lines.push(Line::raw(""));
+    }
+    for (group, bindings) in HELP {
+        lines.push(Line::styled(
+            format!(" {}", group),
+            Style::default().fg(theme.accent).bold(),
+        ));
+        for (keys, action) in *bindings {
+            lines.push(Line::from(vec![
+                Span::styled(format!("   {:<18}", keys), Style::default().bold()),
+                Span::raw(*action),
+            ]));
+        }
+        lines.push(Line::raw(""));
+    }
+
+    let block = Block::default()
+        .title(" Help — ? or Esc to close ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .scroll((app.help_scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// The selected suite's `<properties>` as an aligned name/value table.
+fn render_properties(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(suite) = app.properties_suite() else {
+        return;
+    };
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let properties = suite
+        .properties
+        .as_ref()
+        .map(|p| p.properties.as_slice())
+        .unwrap_or_default();
+    let width = properties
+        .iter()
+        .map(|p| p.name.width())
+        .max()
+        .unwrap_or(0)
+        .min(40);
+    let lines: Vec<Line> = if properties.is_empty() {
+        vec![Line::styled(
+            " No properties recorded for this suite",
+            Style::default().fg(theme.muted),
+        )]
     } else {
-        render_content(frame, main_area, app);
+        properties
+            .iter()
+            .map(|p| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {}  ", fit(&p.name, width)),
+                        Style::default().fg(theme.accent).bold(),
+                    ),
+                    Span::styled(p.value.clone(), Style::default().fg(theme.text)),
+                ])
+            })
+            .collect()
+    };
+
+    let block = Block::default()
+        .title(format!(
+            " Properties — {} — p or Esc to close ",
+            truncate_str(&suite.name, 40)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((app.properties_scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// A suite's own captured output: what fixtures and setup logged outside
+/// any test.
+fn render_suite_output(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(suite) = app.properties_suite() else {
+        return;
+    };
+    let area = centered_rect(80, 70, frame.area());
+    frame.render_widget(Clear, area);
+
+    let mut lines = Vec::new();
+    let stdout = output_lines(
+        suite.system_out.as_deref(),
+        Style::default(),
+        app.render_ansi,
+        theme,
+        false,
+        app.pretty,
+    );
+    if !stdout.is_empty() {
+        lines.push(Line::styled(
+            "── System Out ───────────────────────────────────────",
+            Style::default().fg(theme.stdout).bold(),
+        ));
+        lines.extend(stdout);
+        lines.push(Line::raw(""));
+    }
+    let stderr = output_lines(
+        suite.system_err.as_deref(),
+        Style::default().fg(theme.stderr),
+        app.render_ansi,
+        theme,
+        false,
+        app.pretty,
+    );
+    if !stderr.is_empty() {
+        lines.push(Line::styled(
+            "── System Err ───────────────────────────────────────",
+            Style::default().fg(theme.stderr).bold(),
+        ));
+        lines.extend(stderr);
+    }
+    if lines.is_empty() {
+        lines.push(Line::styled(
+            " No output recorded for this suite",
+            Style::default().fg(theme.muted),
+        ));
     }
 
-    render_status_bar(frame, status_area, app);
+    let block = Block::default()
+        .title(format!(
+            " Suite output — {} — O or Esc to close ",
+            truncate_str(&suite.name, 40)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false })
+        .scroll((app.suite_output_scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+/// Number of tests listed under "Slowest" in the suite stats popup.
+const STATS_SLOWEST: usize = 5;
+
+fn render_suite_stats(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(suite) = app.current_suite() else {
+        return;
+    };
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let times: Vec<f64> = suite.test_cases.iter().filter_map(|tc| tc.time).collect();
+    let total = suite.time.unwrap_or_else(|| times.iter().sum());
+    let average = if times.is_empty() {
+        None
+    } else {
+        Some(times.iter().sum::<f64>() / times.len() as f64)
+    };
+    let max = times.iter().copied().reduce(f64::max);
+    let pass_rate = if suite.tests > 0 {
+        format!(
+            "{:.1}%  ({} of {})",
+            suite.passed() as f64 * 100.0 / suite.tests as f64,
+            suite.passed(),
+            suite.tests
+        )
+    } else {
+        "no tests".to_string()
+    };
+    let seconds = |t: Option<f64>| t.map(|t| format!("{:.3}s", t)).unwrap_or("—".into());
+
+    let field = |label: &'static str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!(" {:<10}", label),
+                Style::default().fg(theme.accent).bold(),
+            ),
+            Span::styled(value, Style::default().fg(theme.text)),
+        ])
+    };
+    let mut lines = vec![
+        field("Pass rate", pass_rate),
+        field(
+            "Results",
+            format!(
+                "{} failed, {} errored, {} skipped",
+                suite.failures,
+                suite.errors,
+                suite.skipped.unwrap_or(0)
+            ),
+        ),
+        field("Total", seconds(Some(total))),
+        field("Average", seconds(average)),
+        field("Max", seconds(max)),
+        field(
+            "Hostname",
+            suite.hostname.clone().unwrap_or_else(|| "—".into()),
+        ),
+        field(
+            "Timestamp",
+            suite.timestamp.clone().unwrap_or_else(|| "—".into()),
+        ),
+        Line::raw(""),
+        Line::styled(" Slowest", Style::default().fg(theme.accent).bold()),
+    ];
+    let slowest = suite
+        .sorted_by_time()
+        .into_iter()
+        .filter(|tc| tc.time.is_some())
+        .take(STATS_SLOWEST);
+    for tc in slowest {
+        let (badge, badge_color) = test_badge(app, suite, tc);
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(" {:>9} ", seconds(tc.time)),
+                Style::default().fg(theme.muted),
+            ),
+            Span::styled(
+                format!("[{}] ", badge),
+                Style::default().fg(badge_color).bold(),
+            ),
+            Span::styled(truncate_str(&tc.name, 60), Style::default().fg(theme.text)),
+        ]));
+    }
+
+    let block = Block::default()
+        .title(format!(
+            " Stats — {} — i or Esc to close ",
+            truncate_str(&suite.name, 40)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Cells in the cross-file comparison's duration bars.
+const COMPARE_BAR: usize = 24;
+
+/// One suite's duration and failures in each file that has it, in run
+/// order, with a bar per file so a slow shard or run stands out.
+/// The selected test's identifier in each [`IdFormat`], to pick one to
+/// copy.
+fn render_copy_id(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(tc) = app.cursor_test() else {
+        return;
+    };
+    let formats = IdFormat::ALL;
+    let area = centered_rect(70, 100, frame.area());
+    let height = (formats.len() as u16 + 2).min(area.height);
+    let area = Rect {
+        y: area.y + (area.height - height) / 2,
+        height,
+        ..area
+    };
+    frame.render_widget(Clear, area);
+
+    let label_width = formats.iter().map(|f| f.label().width()).max().unwrap_or(0);
+    let items: Vec<ListItem> = formats
+        .iter()
+        .enumerate()
+        .map(|(i, format)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", i + 1),
+                    Style::default().fg(theme.accent).bold(),
+                ),
+                Span::styled(
+                    format!("{}  ", fit(format.label(), label_width)),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::styled(format.format(tc), Style::default().fg(theme.text)),
+            ]))
+        })
+        .collect();
+
+    let selected = formats.iter().position(|&f| f == app.id_format);
+    let mut state = ListState::default().with_selected(selected);
+    let block = Block::default()
+        .title(" Copy test identifier — 1–4 or Enter to copy, Esc to cancel ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold());
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_suite_compare(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(name) = app.compared_suite.as_deref() else {
+        return;
+    };
+    let history = history::build(&app.files);
+    let Some(trend) = history.suites.iter().find(|t| t.name == name) else {
+        return;
+    };
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let points: Vec<(&str, history::SuitePoint)> = history
+        .runs
+        .iter()
+        .zip(&trend.points)
+        .filter_map(|(run, point)| Some((run.filename.as_str(), (*point)?)))
+        .collect();
+    let fastest = points
+        .iter()
+        .map(|(_, p)| p.time)
+        .fold(f64::INFINITY, f64::min);
+    let slowest = points.iter().map(|(_, p)| p.time).fold(0.0, f64::max);
+    let name_width = points
+        .iter()
+        .map(|(f, _)| f.width())
+        .max()
+        .unwrap_or(0)
+        .min(30);
+    let current = (!app.show_all).then(|| app.current_file().filename.as_str());
+
+    let mut lines = Vec::new();
+    if fastest > 0.0 {
+        lines.push(Line::from(vec![
+            Span::styled(" Spread    ", Style::default().fg(theme.accent).bold()),
+            Span::styled(
+                format!(
+                    "{:.3}s – {:.3}s ({:.1}× the fastest)",
+                    fastest,
+                    slowest,
+                    slowest / fastest
+                ),
+                Style::default().fg(theme.text),
+            ),
+        ]));
+        lines.push(Line::raw(""));
+    }
+    lines.push(Line::styled(
+        format!(
+            "   {:<w$}  {:<b$} {:>9} {:>7} {:>7}",
+            "File",
+            "",
+            "Time",
+            "Failed",
+            "Passed",
+            w = name_width,
+            b = COMPARE_BAR
+        ),
+        Style::default().fg(theme.muted).bold(),
+    ));
+    for (filename, point) in &points {
+        let bar = if slowest > 0.0 {
+            ((point.time / slowest) * COMPARE_BAR as f64).round() as usize
+        } else {
+            0
+        };
+        let bar_color = if point.time == slowest && points.len() > 1 {
+            theme.failed
+        } else {
+            theme.accent
+        };
+        let marker = if Some(*filename) == current {
+            "▸"
+        } else {
+            " "
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", marker), Style::default().fg(theme.accent)),
+            Span::styled(
+                format!("{}  ", fit(filename, name_width)),
+                Style::default().fg(theme.text),
+            ),
+            Span::styled(
+                format!("{:<w$}", "█".repeat(bar.max(1)), w = COMPARE_BAR),
+                Style::default().fg(bar_color),
+            ),
+            Span::styled(
+                format!(" {:>8.3}s", point.time),
+                Style::default().fg(theme.text),
+            ),
+            Span::styled(
+                format!(" {:>7}", point.failed),
+                Style::default().fg(if point.failed > 0 {
+                    theme.failed
+                } else {
+                    theme.muted
+                }),
+            ),
+            Span::styled(
+                format!(" {:>7}", point.passed),
+                Style::default().fg(theme.passed),
+            ),
+        ]));
+    }
+
+    let block = Block::default()
+        .title(format!(
+            " Across files — {} — A or Esc to close ",
+            truncate_str(name, 40)
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_finder(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let area = centered_rect(70, 60, frame.area());
+    frame.render_widget(Clear, area);
+
+    let block = Block::default()
+        .title(" Find test ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [input_area, results_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+
+    let input = Paragraph::new(Line::from(vec![
+        Span::styled(" > ", Style::default().bold().fg(theme.accent)),
+        Span::styled(app.finder.query.clone(), Style::default().fg(theme.text)),
+        Span::styled("█", Style::default().fg(theme.accent)),
+    ]))
+    .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(input, input_area);
+
+    let results = app.finder_results();
+    let items: Vec<ListItem> = results
+        .iter()
+        .map(|hit| {
+            let suite = &app.files[hit.file].data.suites[hit.suite];
+            let tc = &suite.test_cases[hit.test];
+            let (badge, badge_color) = test_badge(app, suite, tc);
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("[{}] ", badge),
+                    Style::default().fg(badge_color).bold(),
+                ),
+                Span::raw(app.finder_label(hit.file, hit.suite, hit.test)),
+            ]))
+        })
+        .collect();
+
+    let mut state = ListState::default().with_selected(Some(app.finder.selected));
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, results_area, &mut state);
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Percentage((100 - percent_y) / 2),
+        Constraint::Percentage(percent_y),
+        Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .areas(area);
+    let [_, center, _] = Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .areas(middle);
+    center
+}
+
+/// A test's badge: its status, `FLKY` for a pass that needed retries,
+/// `QUAR` for a quarantined failure, and with a baseline `NEW` for a
+/// failure it didn't have or `FIXD` for a pass it had failing.
+fn test_badge(app: &App, suite: &TestSuite, tc: &TestCase) -> (&'static str, Color) {
+    let theme = &app.theme;
+    let status = tc.status();
+    let known = app.baseline.as_ref().map(|b| b.is_known(suite, tc));
+    if tc.is_flaky() {
+        ("FLKY", theme.skipped)
+    } else if app.quarantine.contains(tc) {
+        ("QUAR", theme.muted)
+    } else if known == Some(false) && status == TestStatus::Failed {
+        ("NEW ", theme.failed)
+    } else if known == Some(false) && status == TestStatus::Errored {
+        ("NEW ", theme.errored)
+    } else if known == Some(true) && status == TestStatus::Passed {
+        ("FIXD", theme.passed)
+    } else {
+        status_badge(status, theme)
+    }
+}
+
+/// Test names are dimmed while quarantined.
+fn test_name_style(app: &App, tc: &TestCase) -> Style {
+    let color = if app.quarantine.contains(tc) {
+        app.theme.muted
+    } else {
+        app.theme.text
+    };
+    Style::default().fg(color)
+}
+
+fn status_badge(status: TestStatus, theme: &Theme) -> (&'static str, Color) {
+    match status {
+        TestStatus::Passed => ("PASS", theme.passed),
+        TestStatus::Failed => ("FAIL", theme.failed),
+        TestStatus::Skipped => ("SKIP", theme.skipped),
+        TestStatus::Errored => ("ERR ", theme.errored),
+    }
+}
+
+/// `✓ ` / `✗ ` / `− ` for a suite when status markers are on, so its
+/// status doesn't rely on color alone.
+fn suite_marker(app: &App, status: SuiteStatus) -> &'static str {
+    if !app.status_markers {
+        return "";
+    }
+    match status {
+        SuiteStatus::HasFailures => "✗ ",
+        SuiteStatus::AllSkipped => "− ",
+        SuiteStatus::AllPassed | SuiteStatus::Empty => "✓ ",
+    }
 }
 
 fn render_file_sidebar(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let selected = app.sidebar_selected();
     let items: Vec<ListItem> = app
-        .files
-        .iter()
+        .sidebar_entries()
+        .into_iter()
+        .map(|entry| (entry, app.entry_file(entry)))
         .enumerate()
-        .map(|(i, f)| {
+        .map(|(i, (entry, f))| {
             let passed = f.data.total_passed();
             let failed = f.data.total_failures();
             let total = f.data.total_tests();
@@ -41,15 +924,58 @@ fn render_file_sidebar(frame: &mut Frame, area: Rect, app: &App) {
                 .unwrap_or(&f.filename);
 
             let style = if failed > 0 {
-                Style::default().fg(Color::Red)
+                Style::default().fg(theme.failed)
             } else {
-                Style::default().fg(Color::Green)
+                Style::default().fg(theme.passed)
             };
 
-            let label = format!("{} ({}/{})", short_name, passed, total);
-            let item = ListItem::new(label).style(style);
+            let marker = suite_marker(
+                app,
+                if failed > 0 {
+                    SuiteStatus::HasFailures
+                } else {
+                    SuiteStatus::AllPassed
+                },
+            );
+            // The digit that jumps here: 0 for "All files", then 1–9.
+            let number = match entry {
+                None => "0 ".to_string(),
+                Some(_) => {
+                    let n = if app.all_files.is_some() { i } else { i + 1 };
+                    if n <= 9 {
+                        format!("{} ", n)
+                    } else {
+                        "  ".to_string()
+                    }
+                }
+            };
+            let mut label = format!("{}{} ({}/{})", marker, short_name, passed, total);
+            if let Some(mark) = app.marked.iter().position(|m| *m == f.filename) {
+                label.push_str(&format!(" [{}]", mark + 1));
+            }
+            if entry.is_some() && app.baseline.as_ref().is_some_and(|b| b.name == f.filename) {
+                label.push_str(" [base]");
+            }
+            // Right-align the age and size, shortening the name for them
+            // unless that would leave too little of it.
+            let mut info = match entry {
+                Some(_) => file_info(f),
+                None => String::new(),
+            };
+            let room = name_width(area, number.width() + info.width());
+            if room > MIN_NAME_WIDTH {
+                label = fit(&label, room);
+            } else {
+                info.clear();
+            }
+            let item = ListItem::new(Line::from(vec![
+                Span::styled(number, Style::default().fg(theme.muted)),
+                Span::raw(label),
+                Span::styled(info, Style::default().fg(theme.muted)),
+            ]))
+            .style(style);
 
-            if i == app.selected_file {
+            if i == selected {
                 item.style(style.add_modifier(Modifier::BOLD))
             } else {
                 item
@@ -57,328 +983,2020 @@ fn render_file_sidebar(frame: &mut Frame, area: Rect, app: &App) {
         })
         .collect();
 
+    let mut title = " Files ".to_string();
+    if !app.sidebar_filter.is_empty() {
+        title.push_str(&format!("[{}] ", app.sidebar_filter));
+    }
+    if app.file_sort != FileSort::Name {
+        title.push_str(&format!("[{}] ", app.file_sort.label()));
+    }
     let block = Block::default()
-        .title(" Files ")
+        .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
 
-    let mut state = ListState::default().with_selected(Some(app.selected_file));
+    let count = items.len();
+    let mut state = ListState::default().with_selected(Some(selected));
     let list = List::new(items)
         .block(block)
-        .highlight_style(Style::default().bg(Color::DarkGray).bold())
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
         .highlight_symbol("> ");
 
     frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
 }
 
 fn render_content(frame: &mut Frame, area: Rect, app: &App) {
     match app.view {
         View::SuiteList => render_suite_list(frame, area, app),
+        View::Tree => render_tree(frame, area, app),
         View::TestList => render_test_list(frame, area, app),
         View::TestDetail => render_test_detail(frame, area, app),
+        View::Diff => render_diff(frame, area, app),
+        View::History => render_history(frame, area, app),
+        View::Slowest => render_slowest(frame, area, app),
+        View::Groups => render_groups(frame, area, app),
+        View::Skips => render_skips(frame, area, app),
+        View::Packages => render_packages(frame, area, app),
+        View::Durations => render_durations(frame, area, app),
+    }
+}
+
+/// Cells in the suite list's pass-rate gauge.
+const GAUGE_WIDTH: usize = 8;
+
+/// `██████░░  75% `: passes in the passed color, failures and errors in
+/// the failed color. Skipped tests don't count either way; a suite that
+/// ran nothing gets an empty gauge.
+fn pass_rate_gauge(suite: &TestSuite, theme: &Theme) -> Vec<Span<'static>> {
+    let passed = suite.passed();
+    let ran = passed + suite.failures + suite.errors;
+    if ran == 0 {
+        return vec![Span::styled(
+            format!("{}    — ", "░".repeat(GAUGE_WIDTH)),
+            Style::default().fg(theme.muted),
+        )];
+    }
+    let rate = passed as f64 / ran as f64;
+    // Any failure shows at least one red cell.
+    let mut filled = (rate * GAUGE_WIDTH as f64).round() as usize;
+    if passed < ran {
+        filled = filled.min(GAUGE_WIDTH - 1);
     }
+    vec![
+        Span::styled("█".repeat(filled), Style::default().fg(theme.passed)),
+        Span::styled(
+            "█".repeat(GAUGE_WIDTH - filled),
+            Style::default().fg(theme.failed),
+        ),
+        Span::styled(
+            format!(" {:>3.0}% ", rate * 100.0),
+            Style::default().fg(if passed < ran {
+                theme.failed
+            } else {
+                theme.muted
+            }),
+        ),
+    ]
 }
 
 fn render_suite_list(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let file = app.current_file();
-    let items: Vec<ListItem> = file
-        .data
-        .suites
-        .iter()
-        .map(|suite| {
-            let passed = suite
-                .tests
-                .saturating_sub(suite.failures + suite.errors + suite.skipped.unwrap_or(0));
+    let items: Vec<ListItem> = app
+        .visible_suites()
+        .into_iter()
+        .map(|i| {
+            let suite = &file.data.suites[i];
+            let passed = suite.passed();
             let time_str = suite.time.map(|t| format!("{:.1}s", t)).unwrap_or_default();
 
-            let status_color = if suite.failures > 0 || suite.errors > 0 {
-                Color::Red
-            } else if suite.skipped.unwrap_or(0) > 0 && suite.tests == suite.skipped.unwrap_or(0) {
-                Color::Yellow
-            } else {
-                Color::Green
+            let status_color = match suite.status() {
+                SuiteStatus::HasFailures => theme.failed,
+                SuiteStatus::AllSkipped => theme.skipped,
+                SuiteStatus::AllPassed | SuiteStatus::Empty => theme.passed,
             };
 
-            let line = Line::from(vec![
-                Span::styled(
-                    format!("{:<50} ", truncate_str(&suite.name, 50)),
-                    Style::default().fg(status_color),
-                ),
+            let marker = suite_marker(app, suite.status());
+            let mut tail = pass_rate_gauge(suite, theme);
+            tail.extend([
                 Span::styled(
                     format!("{:>3} tests ", suite.tests),
-                    Style::default().fg(Color::White),
+                    Style::default().fg(theme.text),
                 ),
                 Span::styled(
                     format!("{:>3} pass ", passed),
-                    Style::default().fg(Color::Green),
+                    Style::default().fg(theme.passed),
                 ),
                 Span::styled(
                     format!("{:>3} fail ", suite.failures),
                     if suite.failures > 0 {
-                        Style::default().fg(Color::Red)
+                        Style::default().fg(theme.failed)
                     } else {
-                        Style::default().fg(Color::DarkGray)
+                        Style::default().fg(theme.muted)
                     },
                 ),
                 Span::styled(
                     format!("{:>3} skip ", suite.skipped.unwrap_or(0)),
                     if suite.skipped.unwrap_or(0) > 0 {
-                        Style::default().fg(Color::Yellow)
+                        Style::default().fg(theme.skipped)
                     } else {
-                        Style::default().fg(Color::DarkGray)
+                        Style::default().fg(theme.muted)
                     },
                 ),
+                Span::styled(format!("{:>8}", time_str), Style::default().fg(theme.muted)),
                 Span::styled(
-                    format!("{:>8}", time_str),
-                    Style::default().fg(Color::DarkGray),
+                    format!(
+                        "  {:<19}",
+                        suite
+                            .timestamp
+                            .as_deref()
+                            .map(short_timestamp)
+                            .unwrap_or_default()
+                    ),
+                    Style::default().fg(theme.muted),
                 ),
             ]);
+            let used = marker.width() + 1 + tail.iter().map(Span::width).sum::<usize>();
+
+            let mut spans = vec![Span::styled(marker, Style::default().fg(status_color))];
+            spans.extend(highlight_matches(
+                format!("{} ", fit(&suite.name, name_width(area, used))),
+                &app.search_query,
+                Style::default().fg(status_color),
+                theme,
+            ));
+            spans.extend(tail);
+            if app.show_all {
+                spans.push(Span::styled(
+                    format!("  {}", app.suite_origin(i).filename),
+                    Style::default().fg(theme.muted),
+                ));
+            }
 
-            ListItem::new(line)
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
-    let title = format!(" Test Suites — {} ", file.filename);
+    let title = format!(
+        " Test Suites — {}{}{}{} ",
+        file.filename,
+        shown_suffix(items.len(), file.data.suites.len()),
+        filter_suffix(app),
+        suite_sort_suffix(app)
+    );
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+        .border_style(Style::default().fg(theme.accent));
 
+    let count = items.len();
     let mut state = ListState::default().with_selected(Some(app.selected_suite));
     let list = List::new(items)
         .block(block)
-        .highlight_style(Style::default().bg(Color::DarkGray).bold())
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
         .highlight_symbol("> ");
 
     frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
 }
 
-fn render_test_list(frame: &mut Frame, area: Rect, app: &App) {
+fn render_tree(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let file = app.current_file();
-    let suite = &file.data.suites[app.selected_suite];
+    let items: Vec<ListItem> = app
+        .tree_rows()
+        .into_iter()
+        .map(|row| match row {
+            TreeRow::Suite(si) => {
+                let suite = &file.data.suites[si];
+                let marker = if app.expanded.contains(&si) {
+                    "▾"
+                } else {
+                    "▸"
+                };
+                let status_color = match suite.status() {
+                    SuiteStatus::HasFailures => theme.failed,
+                    SuiteStatus::AllSkipped => theme.skipped,
+                    SuiteStatus::AllPassed | SuiteStatus::Empty => theme.passed,
+                };
+                let mut spans = vec![
+                    Span::styled(format!("{} ", marker), Style::default().fg(theme.accent)),
+                    Span::styled(
+                        suite_marker(app, suite.status()),
+                        Style::default().fg(status_color),
+                    ),
+                ];
+                spans.extend(highlight_matches(
+                    suite.name.clone(),
+                    &app.search_query,
+                    Style::default().fg(status_color).bold(),
+                    theme,
+                ));
+                spans.push(Span::styled(
+                    format!("  {}/{} passed", suite.passed(), suite.tests),
+                    Style::default().fg(theme.muted),
+                ));
+                if app.show_all {
+                    spans.push(Span::styled(
+                        format!("  {}", app.suite_origin(si).filename),
+                        Style::default().fg(theme.muted),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            }
+            TreeRow::Test(si, ti) => {
+                let tc = &file.data.suites[si].test_cases[ti];
+                let (badge, badge_color) = test_badge(app, &file.data.suites[si], tc);
+                let time_str = tc.time.map(|t| format!("{:.2}s", t)).unwrap_or_default();
+                let mut spans = vec![Span::styled(
+                    format!("    [{}] ", badge),
+                    Style::default().fg(badge_color).bold(),
+                )];
+                spans.extend(highlight_matches(
+                    format!("{} ", fit(&tc.name, name_width(area, TREE_TEST_FIXED))),
+                    &app.search_query,
+                    test_name_style(app, tc),
+                    theme,
+                ));
+                spans.push(Span::styled(
+                    format!("{:>8}", time_str),
+                    Style::default().fg(theme.muted),
+                ));
+                ListItem::new(Line::from(spans))
+            }
+        })
+        .collect();
+
+    let title = format!(
+        " Tree — {}{}{}{} ",
+        file.filename,
+        shown_suffix(app.visible_suites().len(), file.data.suites.len()),
+        filter_suffix(app),
+        sort_suffix(app)
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let count = items.len();
+    let mut state = ListState::default().with_selected(Some(app.tree_selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
+}
+
+fn render_test_list(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let Some(suite) = app.current_suite() else {
+        return;
+    };
+    let [tabs_area, _] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+    render_status_tabs(frame, tabs_area, app, suite);
+    let area = list_area(area, app);
+
+    let suite_index = app.current_suite_index().unwrap_or(0);
+    let columns = test_columns(app, suite);
+    let groups = app.param_groups();
+    let rows: Vec<Row> = app
+        .test_rows()
+        .into_iter()
+        .map(|row| {
+            let (cases, name) = match row {
+                TestRow::Test { test, group } => {
+                    let mut name = vec![Span::raw(if group.is_some() { "  " } else { "" })];
+                    name.extend(highlight_matches(
+                        suite.test_cases[test].name.clone(),
+                        &app.search_query,
+                        test_name_style(app, &suite.test_cases[test]),
+                        theme,
+                    ));
+                    (vec![test], name)
+                }
+                TestRow::Params(gi) => {
+                    let group = &groups[gi];
+                    (group.cases.clone(), params_name(app, suite, group))
+                }
+            };
+            let tc = &suite.test_cases[cases[0]];
+            let (badge, badge_color) = cases
+                .iter()
+                .map(|&i| &suite.test_cases[i])
+                .max_by_key(|tc| status_rank(tc.status()))
+                .map_or(("", theme.text), |worst| test_badge(app, suite, worst));
+            let time: Option<f64> = cases.iter().map(|&i| suite.test_cases[i].time).sum();
+            let time_str = time.map(|t| format!("{:.2}s", t)).unwrap_or_default();
+            let pick = if cases
+                .iter()
+                .all(|&i| app.picked.contains(&(suite_index, i)))
+            {
+                Span::styled("●", Style::default().fg(theme.accent).bold())
+            } else {
+                Span::raw(" ")
+            };
+
+            let mut cells = vec![
+                Cell::from(pick),
+                Cell::from(Span::styled(
+                    format!("[{}]", badge),
+                    Style::default().fg(badge_color).bold(),
+                )),
+                Cell::from(Line::from(name)),
+            ];
+            for &(label, _, _) in &columns {
+                let value = match label {
+                    "Class" => &tc.classname,
+                    "File" => &tc.file,
+                    _ => continue,
+                };
+                cells.push(Cell::from(Span::styled(
+                    value.clone().unwrap_or_default(),
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            cells.push(Cell::from(
+                Line::styled(time_str, Style::default().fg(theme.muted)).right_aligned(),
+            ));
+            Row::new(cells)
+        })
+        .collect();
+
+    let picked = if app.picked.is_empty() {
+        String::new()
+    } else {
+        format!(" — {} picked", app.picked.len())
+    };
+    let title = format!(
+        " Tests — {}{}{}{}{} ",
+        truncate_str(&suite.name, area.width as usize / 2),
+        shown_suffix(app.visible_tests().len(), suite.test_cases.len()),
+        test_filter_suffix(app),
+        sort_suffix(app),
+        picked
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let header = Row::new(columns.iter().map(|&(label, sort, _)| {
+        let sorted = sort.is_some() && sort == Some(app.test_sort);
+        let text = if sorted {
+            format!("{} ▾", label)
+        } else {
+            label.to_string()
+        };
+        let style = if sorted {
+            Style::default().fg(theme.accent).bold()
+        } else {
+            Style::default().fg(theme.muted).bold()
+        };
+        let line = Line::styled(text, style);
+        // Keep the header over the time column lined up with the times.
+        Cell::from(if sort == Some(TestSort::Duration) {
+            line.right_aligned()
+        } else {
+            line
+        })
+    }));
+
+    let count = rows.len();
+    let mut state = TableState::default().with_selected(Some(app.selected_test));
+    let table = Table::new(rows, columns.iter().map(|&(_, _, width)| width))
+        .header(header)
+        .block(block)
+        .row_highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol(TABLE_HIGHLIGHT);
+
+    frame.render_stateful_widget(table, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
+}
+
+/// A parameterized test's header: `▸ test_sum (12 cases, 2 failed)`.
+fn params_name(app: &App, suite: &TestSuite, group: &ParameterizedGroup) -> Vec<Span<'static>> {
+    let theme = &app.theme;
+    let marker = if app.expanded_params.contains(&group.name) {
+        "▾"
+    } else {
+        "▸"
+    };
+    let failed = group
+        .cases
+        .iter()
+        .filter(|&&i| {
+            matches!(
+                suite.test_cases[i].status(),
+                TestStatus::Failed | TestStatus::Errored
+            )
+        })
+        .count();
+    let mut spans = vec![Span::styled(
+        format!("{} ", marker),
+        Style::default().fg(theme.accent),
+    )];
+    spans.extend(highlight_matches(
+        group.name.clone(),
+        &app.search_query,
+        Style::default().fg(theme.text).bold(),
+        theme,
+    ));
+    spans.push(Span::styled(
+        format!(" ({} cases", group.cases.len()),
+        Style::default().fg(theme.muted),
+    ));
+    if failed > 0 {
+        spans.extend([
+            Span::styled(", ", Style::default().fg(theme.muted)),
+            Span::styled(
+                format!("{} failed", failed),
+                Style::default().fg(theme.failed),
+            ),
+        ]);
+    }
+    spans.push(Span::styled(")", Style::default().fg(theme.muted)));
+    spans
+}
+
+/// How bad a status is, for picking the badge a parameterized test shows.
+fn status_rank(status: TestStatus) -> u8 {
+    match status {
+        TestStatus::Passed => 0,
+        TestStatus::Skipped => 1,
+        TestStatus::Errored => 2,
+        TestStatus::Failed => 3,
+    }
+}
+
+const TABLE_HIGHLIGHT: &str = "> ";
+
+/// Columns a tree test row uses besides its name: the indented badge and
+/// the time.
+const TREE_TEST_FIXED: usize = 11 + 8;
+
+/// A test list column: header label, the sort a header click picks, and
+/// width.
+type TestColumn = (&'static str, Option<TestSort>, Constraint);
+
+/// The test list's columns. The first holds the pick marker. Class and
+/// file can be hidden with `c`, and file only shows when some test in the
+/// suite has one.
+fn test_columns(app: &App, suite: &TestSuite) -> Vec<TestColumn> {
+    let mut columns = vec![
+        ("", None, Constraint::Length(1)),
+        ("Status", Some(TestSort::Status), Constraint::Length(6)),
+        ("Name", Some(TestSort::Name), Constraint::Fill(3)),
+    ];
+    if !app.hide_columns {
+        columns.push(("Class", Some(TestSort::Class), Constraint::Fill(2)));
+        if suite.test_cases.iter().any(|tc| tc.file.is_some()) {
+            columns.push(("File", None, Constraint::Fill(2)));
+        }
+    }
+    columns.push(("Time", Some(TestSort::Duration), Constraint::Length(9)));
+    columns
+}
 
-    let items: Vec<ListItem> = suite
-        .test_cases
+/// The sort picked by clicking the test list's header at column `x`, laid
+/// out the way `Table` lays out `test_columns` inside the bordered `list`.
+pub fn test_sort_at(list: Rect, x: u16, app: &App) -> Option<TestSort> {
+    let columns = test_columns(app, app.current_suite()?);
+    let inner = list.inner(Margin::new(1, 1));
+    let [_, area] = Layout::horizontal([
+        Constraint::Length(TABLE_HIGHLIGHT.len() as u16),
+        Constraint::Fill(0),
+    ])
+    .areas(inner);
+    let rects = Layout::horizontal(columns.iter().map(|&(_, _, width)| width))
+        .spacing(1)
+        .split(area);
+    let column = rects.iter().position(|r| x >= r.x && x < r.x + r.width)?;
+    columns[column].1
+}
+
+/// `All 24 │ Failed 3 │ Errored 1 │ …` for the current suite, with the
+/// active filter's tabs lit up.
+fn render_status_tabs(frame: &mut Frame, area: Rect, app: &App, suite: &TestSuite) {
+    let theme = &app.theme;
+    let titles: Vec<Line> = StatusFilter::TABS
         .iter()
-        .map(|tc| {
-            let (badge, badge_color) = match tc.status() {
-                TestStatus::Passed => ("PASS", Color::Green),
-                TestStatus::Failed => ("FAIL", Color::Red),
-                TestStatus::Skipped => ("SKIP", Color::Yellow),
-                TestStatus::Errored => ("ERR ", Color::Magenta),
+        .enumerate()
+        .map(|(i, &tab)| {
+            let count = suite.cases_with_status(tab.statuses()).count();
+            let color = match tab {
+                StatusFilter::Failed | StatusFilter::Failing | StatusFilter::NewFailures => {
+                    theme.failed
+                }
+                StatusFilter::Errored => theme.errored,
+                StatusFilter::Skipped => theme.skipped,
+                StatusFilter::Passed => theme.passed,
+                StatusFilter::All => theme.text,
             };
+            let style = if app.status_filter.shows_tab(tab) {
+                Style::default().fg(color).bg(theme.selection_bg).bold()
+            } else {
+                Style::default().fg(color)
+            };
+            let label = tab.label();
+            let mut name = label[..1].to_uppercase();
+            name.push_str(&label[1..]);
+            Line::styled(format!("{} {} {}", i + 1, name, count), style)
+        })
+        .collect();
+    let tabs = Tabs::new(titles)
+        .select(None)
+        .divider(Span::styled("│", Style::default().fg(theme.muted)));
+    frame.render_widget(tabs, area);
+}
+
+/// The slowest tests with their share of the file's total runtime and the
+/// running total, so it's clear how few tests dominate.
+fn render_slowest(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let data = &app.current_file().data;
+    let total = data.total_case_time();
+    let percent = |t: f64| if total > 0.0 { t / total * 100.0 } else { 0.0 };
 
-            let time_str = tc.time.map(|t| format!("{:.2}s", t)).unwrap_or_default();
+    let mut cumulative = 0.0;
+    let items: Vec<ListItem> = app
+        .slowest_rows()
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (si, ti))| {
+            let suite = &data.suites[si];
+            let tc = &suite.test_cases[ti];
+            let time = tc.time.unwrap_or(0.0);
+            cumulative += time;
+            let (badge, badge_color) = test_badge(app, suite, tc);
 
-            let line = Line::from(vec![
+            let mut spans = vec![
                 Span::styled(
-                    format!(" [{}] ", badge),
-                    Style::default().fg(badge_color).bold(),
+                    format!("{:>4}. ", rank + 1),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::styled(format!("{:>8.2}s ", time), Style::default().fg(theme.text)),
+                Span::styled(
+                    format!("{:>5.1}% ", percent(time)),
+                    Style::default().fg(theme.accent),
                 ),
                 Span::styled(
-                    format!("{:<70} ", truncate_str(&tc.name, 70)),
-                    Style::default().fg(Color::White),
+                    format!("Σ{:>5.1}% ", percent(cumulative)),
+                    Style::default().fg(theme.muted),
                 ),
                 Span::styled(
+                    format!("[{}] ", badge),
+                    Style::default().fg(badge_color).bold(),
+                ),
+            ];
+            let used = spans.iter().map(Span::width).sum::<usize>();
+            let (name_width, suite_width) = split_width(name_width(area, used));
+            spans.extend(highlight_matches(
+                format!("{} ", fit(&tc.name, name_width)),
+                &app.search_query,
+                Style::default().fg(theme.text),
+                theme,
+            ));
+            spans.push(Span::styled(
+                truncate_str(&suite.name, suite_width),
+                Style::default().fg(theme.muted),
+            ));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!(
+        " Slowest tests — {} ({:.1}s total){} ",
+        app.current_file().filename,
+        total,
+        filter_suffix(app)
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let count = items.len();
+    let mut state = ListState::default().with_selected(Some(app.slowest_selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
+}
+
+/// Column headings of the duration histogram, one per bucket of
+/// [`junit_parser::DURATION_BUCKETS`].
+const DURATION_LABELS: [&str; junit_parser::DURATION_BUCKETS.len() + 1] =
+    ["<1ms", "1–10ms", "10–100ms", "0.1–1s", "1–10s", ">10s"];
+
+/// Cells in each bucket's bar.
+const DURATION_BAR: usize = 6;
+
+/// Columns each bucket takes: the bar, a space and a four-digit count.
+const DURATION_CELL: usize = DURATION_BAR + 6;
+
+/// One row per suite with a bar per duration bucket, scaled to the suite's
+/// fullest bucket so both outliers and two-humped timings stand out. Bars
+/// warm from the passed color for the fastest bucket to the failed color
+/// for the slowest.
+fn render_durations(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let data = &app.current_file().data;
+    let colors = [
+        theme.passed,
+        theme.passed,
+        theme.accent,
+        theme.skipped,
+        theme.errored,
+        theme.failed,
+    ];
+    let buckets_width = DURATION_LABELS.len() * DURATION_CELL;
+    let name_width = name_width(area, buckets_width);
+
+    let items: Vec<ListItem> = app
+        .visible_suites()
+        .into_iter()
+        .map(|si| {
+            let suite = &data.suites[si];
+            let counts = suite.duration_histogram();
+            let max = counts.iter().copied().max().unwrap_or(0);
+            let mut spans = highlight_matches(
+                format!("{} ", fit(&suite.name, name_width)),
+                &app.search_query,
+                Style::default().fg(theme.text),
+                theme,
+            );
+            for (count, color) in counts.into_iter().zip(colors) {
+                if count == 0 {
+                    spans.push(Span::styled(
+                        format!("{:<w$}", "·", w = DURATION_CELL),
+                        Style::default().fg(theme.muted),
+                    ));
+                    continue;
+                }
+                let bar = (count * DURATION_BAR).div_ceil(max);
+                spans.push(Span::styled(
+                    format!("{:<w$}", "█".repeat(bar), w = DURATION_BAR),
+                    Style::default().fg(color),
+                ));
+                spans.push(Span::styled(
+                    format!(" {:<5}", count),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!(
+        " Durations — {} ({} timed tests) ",
+        app.current_file().filename,
+        data.duration_histogram().iter().sum::<usize>()
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let [header_area, list_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+
+    let mut header = vec![Span::raw(" ".repeat(name_width + 3))];
+    header.extend(DURATION_LABELS.iter().zip(colors).map(|(label, color)| {
+        Span::styled(
+            format!("{:<w$}", label, w = DURATION_CELL),
+            Style::default().fg(color).bold(),
+        )
+    }));
+    frame.render_widget(Paragraph::new(Line::from(header)), header_area);
+
+    let count = items.len();
+    let mut state = ListState::default().with_selected(Some(app.durations_selected));
+    let list = List::new(items)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, list_area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
+}
+
+/// Failed tests clustered by normalised message, biggest cluster first,
+/// so one root cause behind many failures stands out.
+fn render_groups(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let data = &app.current_file().data;
+    let groups = app.failure_groups();
+    let failing: usize = groups.iter().map(|g| g.cases.len()).sum();
+
+    let items: Vec<ListItem> = app
+        .group_rows()
+        .into_iter()
+        .map(|row| match row {
+            GroupRow::Group(gi) => {
+                let group = &groups[gi];
+                let marker = if app.expanded_groups.contains(&group.signature) {
+                    "▾"
+                } else {
+                    "▸"
+                };
+                let mut spans = vec![
+                    Span::styled(format!("{} ", marker), Style::default().fg(theme.accent)),
+                    Span::styled(
+                        format!("{:>4}× ", group.cases.len()),
+                        Style::default().fg(theme.failed).bold(),
+                    ),
+                ];
+                spans.extend(highlight_matches(
+                    group.signature.clone(),
+                    &app.search_query,
+                    Style::default().fg(theme.text),
+                    theme,
+                ));
+                ListItem::new(Line::from(spans))
+            }
+            GroupRow::Test { suite, test, .. } => {
+                let s = &data.suites[suite];
+                let tc = &s.test_cases[test];
+                let (badge, badge_color) = test_badge(app, s, tc);
+                let mut spans = vec![Span::styled(
+                    format!("    [{}] ", badge),
+                    Style::default().fg(badge_color).bold(),
+                )];
+                let used = spans.iter().map(Span::width).sum::<usize>();
+                let (name_width, suite_width) = split_width(name_width(area, used));
+                spans.extend(highlight_matches(
+                    format!("{} ", fit(&tc.name, name_width)),
+                    &app.search_query,
+                    test_name_style(app, tc),
+                    theme,
+                ));
+                spans.push(Span::styled(
+                    truncate_str(&s.name, suite_width),
+                    Style::default().fg(theme.muted),
+                ));
+                ListItem::new(Line::from(spans))
+            }
+        })
+        .collect();
+
+    let items = if items.is_empty() {
+        vec![ListItem::new(Line::styled(
+            " No failures",
+            Style::default().fg(theme.muted),
+        ))]
+    } else {
+        items
+    };
+
+    let title = format!(
+        " Failure groups by {} — {} ({} failing in {} groups) ",
+        if app.group_by_exception {
+            "exception"
+        } else {
+            "message"
+        },
+        app.current_file().filename,
+        failing,
+        groups.len()
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let count = items.len();
+    let mut state = ListState::default().with_selected(Some(app.groups_selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
+}
+
+fn render_packages(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let data = &app.current_file().data;
+    let tree = data.package_tree();
+
+    let items: Vec<ListItem> = app
+        .package_rows()
+        .into_iter()
+        .map(|row| match row {
+            PackageRow::Node { path, depth } => {
+                let Some(node) = tree.find(&path) else {
+                    return ListItem::new(Line::raw(""));
+                };
+                let marker = if app.expanded_packages.contains(&path) {
+                    "▾"
+                } else {
+                    "▸"
+                };
+                let failed = node.failures + node.errors;
+                let name_color = if failed > 0 {
+                    theme.failed
+                } else if node.skipped == node.tests {
+                    theme.skipped
+                } else {
+                    theme.passed
+                };
+                let mut spans = vec![Span::styled(
+                    format!("{}{} ", "  ".repeat(depth), marker),
+                    Style::default().fg(theme.accent),
+                )];
+                spans.extend(highlight_matches(
+                    node.name.clone(),
+                    &app.search_query,
+                    Style::default().fg(name_color).bold(),
+                    theme,
+                ));
+                spans.push(Span::styled(
+                    format!("  {}/{} passed", node.passed, node.tests),
+                    Style::default().fg(theme.muted),
+                ));
+                if failed > 0 {
+                    spans.push(Span::styled(
+                        format!("  {} failed", failed),
+                        Style::default().fg(theme.failed),
+                    ));
+                }
+                if node.skipped > 0 {
+                    spans.push(Span::styled(
+                        format!("  {} skipped", node.skipped),
+                        Style::default().fg(theme.skipped),
+                    ));
+                }
+                spans.push(Span::styled(
+                    format!("  {:.2}s", node.time),
+                    Style::default().fg(theme.muted),
+                ));
+                ListItem::new(Line::from(spans))
+            }
+            PackageRow::Test { suite, test, depth } => {
+                let tc = &data.suites[suite].test_cases[test];
+                let (badge, badge_color) = test_badge(app, &data.suites[suite], tc);
+                let time_str = tc.time.map(|t| format!("{:.2}s", t)).unwrap_or_default();
+                let mut spans = vec![Span::styled(
+                    format!("{}[{}] ", "  ".repeat(depth), badge),
+                    Style::default().fg(badge_color).bold(),
+                )];
+                let used = spans.iter().map(Span::width).sum::<usize>() + 8;
+                spans.extend(highlight_matches(
+                    format!("{} ", fit(&tc.name, name_width(area, used))),
+                    &app.search_query,
+                    test_name_style(app, tc),
+                    theme,
+                ));
+                spans.push(Span::styled(
                     format!("{:>8}", time_str),
-                    Style::default().fg(Color::DarkGray),
+                    Style::default().fg(theme.muted),
+                ));
+                ListItem::new(Line::from(spans))
+            }
+        })
+        .collect();
+
+    let items = if items.is_empty() {
+        vec![ListItem::new(Line::styled(
+            " No tests",
+            Style::default().fg(theme.muted),
+        ))]
+    } else {
+        items
+    };
+
+    let title = format!(
+        " Packages — {} ({}/{} passed) ",
+        app.current_file().filename,
+        tree.passed,
+        tree.tests
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let count = items.len();
+    let mut state = ListState::default().with_selected(Some(app.packages_selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
+}
+
+/// Skipped tests clustered by skip reason, biggest cluster first, with the
+/// tests of expanded reasons listed beneath them.
+fn render_skips(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let data = &app.current_file().data;
+    let groups = app.skip_groups();
+    let skipped: usize = groups.iter().map(|g| g.cases.len()).sum();
+
+    let items: Vec<ListItem> = app
+        .skip_rows()
+        .into_iter()
+        .map(|row| match row {
+            GroupRow::Group(gi) => {
+                let group = &groups[gi];
+                let marker = if app.expanded_skips.contains(&group.reason) {
+                    "▾"
+                } else {
+                    "▸"
+                };
+                let tests = if group.cases.len() == 1 {
+                    "test"
+                } else {
+                    "tests"
+                };
+                let mut spans = vec![Span::styled(
+                    format!("{} ", marker),
+                    Style::default().fg(theme.accent),
+                )];
+                spans.extend(highlight_matches(
+                    group.reason.clone(),
+                    &app.search_query,
+                    Style::default().fg(theme.text),
+                    theme,
+                ));
+                spans.push(Span::styled(
+                    format!(": {} {}", group.cases.len(), tests),
+                    Style::default().fg(theme.skipped).bold(),
+                ));
+                ListItem::new(Line::from(spans))
+            }
+            GroupRow::Test { suite, test, .. } => {
+                let s = &data.suites[suite];
+                let tc = &s.test_cases[test];
+                let (badge, badge_color) = test_badge(app, s, tc);
+                let mut spans = vec![Span::styled(
+                    format!("    [{}] ", badge),
+                    Style::default().fg(badge_color).bold(),
+                )];
+                let used = spans.iter().map(Span::width).sum::<usize>();
+                let (name_width, suite_width) = split_width(name_width(area, used));
+                spans.extend(highlight_matches(
+                    format!("{} ", fit(&tc.name, name_width)),
+                    &app.search_query,
+                    test_name_style(app, tc),
+                    theme,
+                ));
+                spans.push(Span::styled(
+                    truncate_str(&s.name, suite_width),
+                    Style::default().fg(theme.muted),
+                ));
+                ListItem::new(Line::from(spans))
+            }
+        })
+        .collect();
+
+    let items = if items.is_empty() {
+        vec![ListItem::new(Line::styled(
+            " No skipped tests",
+            Style::default().fg(theme.muted),
+        ))]
+    } else {
+        items
+    };
+
+    let title = format!(
+        " Skipped tests — {} ({} skipped for {} reasons) ",
+        app.current_file().filename,
+        skipped,
+        groups.len()
+    );
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent));
+
+    let count = items.len();
+    let mut state = ListState::default().with_selected(Some(app.skips_selected));
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(theme.selection_bg).bold())
+        .highlight_symbol("> ");
+
+    frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, area, count, state.selected().unwrap_or(0));
+}
+
+fn render_test_detail(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let Some(tc) = app.current_test() else {
+        return;
+    };
+
+    let mut title = format!(
+        " Detail — {} ",
+        truncate_str(&tc.name, area.width as usize / 2)
+    );
+    if app.pretty {
+        title.push_str("(pretty) ");
+    } else if has_blob(tc, theme) {
+        title.push_str("— = to pretty-print ");
+    }
+    if app.show_raw_xml {
+        let mut lines: Vec<Line> = match app.raw_xml() {
+            Ok(xml) => xml
+                .lines()
+                .map(|l| Line::styled(l.to_string(), Style::default().fg(theme.text)))
+                .collect(),
+            Err(e) => vec![Line::styled(
+                format!("{:#}", e),
+                Style::default().fg(theme.failed),
+            )],
+        };
+        if app.line_numbers {
+            number_lines(&mut lines, theme);
+        }
+        let title = format!(
+            " Raw XML — {} ",
+            truncate_str(&tc.name, area.width as usize / 2)
+        );
+        render_detail_pane(frame, area, app, title, lines, DetailPane::Main);
+        return;
+    }
+    if app.output_layout == OutputLayout::Combined {
+        let (mut lines, sections) =
+            detail_sections(tc, theme, app.render_ansi, app.line_numbers, app.pretty);
+        mark_selected_frame(&mut lines, &sections, app);
+        render_detail_pane(frame, area, app, title, lines, DetailPane::Main);
+        return;
+    }
+
+    let (main_area, stdout_area, stderr_area) = match app.output_layout {
+        OutputLayout::SideBySide => {
+            let [main_area, output_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+            let [stdout_area, stderr_area] =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(output_area);
+            (main_area, stdout_area, stderr_area)
+        }
+        _ => {
+            let [main_area, stdout_area, stderr_area] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ])
+            .areas(area);
+            (main_area, stdout_area, stderr_area)
+        }
+    };
+
+    let (mut lines, sections) = summary_lines(tc, theme, app.line_numbers, app.pretty);
+    mark_selected_frame(&mut lines, &sections, app);
+    render_detail_pane(frame, main_area, app, title, lines, DetailPane::Main);
+    render_detail_pane(
+        frame,
+        stdout_area,
+        app,
+        " System Out ".to_string(),
+        output_lines(
+            tc.system_out.as_deref(),
+            Style::default(),
+            app.render_ansi,
+            theme,
+            app.line_numbers,
+            app.pretty,
+        ),
+        DetailPane::Stdout,
+    );
+    render_detail_pane(
+        frame,
+        stderr_area,
+        app,
+        " System Err ".to_string(),
+        output_lines(
+            tc.system_err.as_deref(),
+            Style::default().fg(theme.stderr),
+            app.render_ansi,
+            theme,
+            app.line_numbers,
+            app.pretty,
+        ),
+        DetailPane::Stderr,
+    );
+}
+
+/// Highlights the stack frame picked with Enter.
+fn mark_selected_frame(lines: &mut [Line], sections: &Sections, app: &App) {
+    let row = app
+        .selected_frame
+        .and_then(|index| sections.frames.get(index))
+        .map(|&(row, _)| row);
+    if let Some(line) = row.and_then(|row| lines.get_mut(row)) {
+        *line = std::mem::take(line).patch_style(Style::default().bg(app.theme.selection_bg));
+    }
+}
+
+/// Whether any of the test's text holds JSON or XML that `=` would format.
+fn has_blob(tc: &TestCase, theme: &Theme) -> bool {
+    let failure = tc.failure.iter().flat_map(|f| [&f.message, &f.body]);
+    let error = tc.error.iter().flat_map(|e| [&e.message, &e.body]);
+    failure
+        .chain(error)
+        .chain([&tc.system_out, &tc.system_err])
+        .flatten()
+        .any(|text| pretty::has_blob(text, theme))
+}
+
+/// One bordered, independently scrolled pane of the detail view. The pane
+/// that `j` / `k` scroll gets a thick border. Records how far the pane can
+/// scroll and shows where in its content the bottom row is.
+fn render_detail_pane(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    title: String,
+    lines: Vec<Line>,
+    pane: DetailPane,
+) {
+    let theme = &app.theme;
+    let focused = app.output_layout != OutputLayout::Combined
+        && !app.show_raw_xml
+        && app.detail_focus == pane;
+    let scroll = match pane {
+        DetailPane::Main => app.scroll_offset,
+        DetailPane::Stdout => app.stdout_scroll,
+        DetailPane::Stderr => app.stderr_scroll,
+    };
+    let mut paragraph = Paragraph::new(lines);
+    if app.wrap_detail {
+        paragraph = paragraph.wrap(Wrap { trim: false });
+    }
+    let height = area.height.saturating_sub(2) as usize;
+    let content_height = paragraph.line_count(area.width.saturating_sub(2));
+    let max_scroll = content_height.saturating_sub(height).min(u16::MAX as usize) as u16;
+    app.set_max_scroll(pane, max_scroll);
+    if pane == DetailPane::Main {
+        app.detail_height.set(height as u16);
+    }
+    // The terminal may have grown, or the content changed, since the
+    // scroll position was last set.
+    let scroll = scroll.min(max_scroll);
+
+    let mut block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(if focused {
+            BorderType::Thick
+        } else {
+            BorderType::Plain
+        })
+        .border_style(Style::default().fg(theme.accent));
+    if content_height > height {
+        let bottom = (scroll as usize + height).min(content_height);
+        block = block.title_top(
+            Line::styled(
+                format!(
+                    " [{}/{}] {}% ",
+                    bottom,
+                    content_height,
+                    bottom * 100 / content_height
                 ),
-            ]);
+                Style::default().fg(theme.muted),
+            )
+            .right_aligned(),
+        );
+    }
+
+    let paragraph = paragraph.block(block).scroll((scroll, app.h_scroll));
+    frame.render_widget(paragraph, area);
+    render_scrollbar(frame, area, content_height, scroll as usize);
+}
+
+/// Draws a vertical scrollbar on the right border of a bordered pane, but
+/// only when its content doesn't fit.
+fn render_scrollbar(frame: &mut Frame, area: Rect, content_len: usize, position: usize) {
+    let inner = area.inner(Margin {
+        vertical: 1,
+        horizontal: 0,
+    });
+    if content_len <= inner.height as usize {
+        return;
+    }
+    let mut state = ScrollbarState::new(content_len).position(position);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None);
+    frame.render_stateful_widget(scrollbar, inner, &mut state);
+}
 
-            ListItem::new(line)
+/// The detail view's content as plain text, for copying and exporting.
+pub fn detail_text(tc: &TestCase, theme: &Theme, ansi: bool) -> String {
+    detail_lines(tc, theme, ansi)
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
         })
-        .collect();
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    let title = format!(" Tests — {} ", truncate_str(&suite.name, 60));
-    let block = Block::default()
-        .title(title)
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
+/// The detail as Markdown, for bug reports: a heading, the metadata as a
+/// list and the failure, reruns and captured output in fenced blocks.
+pub fn detail_markdown(tc: &TestCase) -> String {
+    let mut md = match &tc.classname {
+        Some(classname) => format!("## {}.{}\n\n", classname, tc.name),
+        None => format!("## {}\n\n", tc.name),
+    };
+    let status = match tc.status() {
+        TestStatus::Passed => "passed",
+        TestStatus::Failed => "failed",
+        TestStatus::Skipped => "skipped",
+        TestStatus::Errored => "error",
+    };
+    md.push_str(&format!("- **Status:** {}\n", status));
+    if let Some(classname) = &tc.classname {
+        md.push_str(&format!("- **Class:** `{}`\n", classname));
+    }
+    if let Some(file) = &tc.file {
+        md.push_str(&format!("- **File:** `{}`\n", file));
+    }
+    if let Some(time) = tc.time {
+        md.push_str(&format!("- **Time:** {:.3}s\n", time));
+    }
+    if let Some(reason) = tc.skipped.as_ref().and_then(|s| s.reason()) {
+        md.push_str(&format!("- **Reason:** {}\n", reason));
+    }
 
-    let mut state = ListState::default().with_selected(Some(app.selected_test));
-    let list = List::new(items)
-        .block(block)
-        .highlight_style(Style::default().bg(Color::DarkGray).bold())
-        .highlight_symbol("> ");
+    let heading = match tc.status() {
+        TestStatus::Errored => "Error",
+        _ => "Failure",
+    };
+    if let Some(text) = tc.failure_text() {
+        push_fenced(&mut md, heading, &text);
+    }
+    for (i, rerun) in tc.reruns().enumerate() {
+        let text: Vec<&str> = [rerun.message.as_deref(), rerun.stack_trace.as_deref()]
+            .into_iter()
+            .flatten()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+        push_fenced(&mut md, &format!("Rerun {}", i + 1), &text.join("\n\n"));
+    }
+    if let Some(out) = &tc.system_out {
+        push_fenced(&mut md, "System Out", out);
+    }
+    if let Some(err) = &tc.system_err {
+        push_fenced(&mut md, "System Err", err);
+    }
+    md
+}
 
-    frame.render_stateful_widget(list, area, &mut state);
+/// A `###` heading and `text` in a code fence.
+fn push_fenced(md: &mut String, heading: &str, text: &str) {
+    if let Some(block) = fenced(text) {
+        md.push_str(&format!("\n### {}\n\n{}", heading, block));
+    }
 }
 
-fn render_test_detail(frame: &mut Frame, area: Rect, app: &App) {
-    let file = app.current_file();
-    let suite = &file.data.suites[app.selected_suite];
-    let tc = &suite.test_cases[app.selected_test];
+/// `text` in a code fence long enough that backticks inside it can't close
+/// it early, or `None` when there is nothing to show.
+pub fn fenced(text: &str) -> Option<String> {
+    let text = ansi::strip(text);
+    let text = text.trim_matches('\n');
+    if text.trim().is_empty() {
+        return None;
+    }
+    let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    Some(format!("{}text\n{}\n{}\n", fence, text, fence))
+}
 
+/// Start of each numbered section among the detail view's lines.
+#[derive(Debug, Default, Clone)]
+pub struct Sections {
+    /// The failure (or, failing that, error) stack trace.
+    pub trace: Option<usize>,
+    /// Each `── Failure ──`, `── Error ──`, `── Reruns ──`, `── System
+    /// Out ──` and `── System Err ──` heading, in order.
+    pub headings: Vec<usize>,
+    pub stdout: Option<usize>,
+    pub stderr: Option<usize>,
+    /// Each stack frame that names a file, with its row.
+    pub frames: Vec<(usize, editor::Frame)>,
+}
+
+/// Name, class, timing and status, followed by any failure or error, and
+/// where the first stack trace and each heading are.
+pub fn summary_lines<'a>(
+    tc: &'a TestCase,
+    theme: &Theme,
+    numbered: bool,
+    pretty: bool,
+) -> (Vec<Line<'a>>, Sections) {
     let (status_text, status_color) = match tc.status() {
-        TestStatus::Passed => ("PASSED", Color::Green),
-        TestStatus::Failed => ("FAILED", Color::Red),
-        TestStatus::Skipped => ("SKIPPED", Color::Yellow),
-        TestStatus::Errored => ("ERROR", Color::Magenta),
+        TestStatus::Passed => ("PASSED", theme.passed),
+        TestStatus::Failed => ("FAILED", theme.failed),
+        TestStatus::Skipped => ("SKIPPED", theme.skipped),
+        TestStatus::Errored => ("ERROR", theme.errored),
     };
 
     let mut lines: Vec<Line> = Vec::new();
+    let mut sections = Sections::default();
 
     lines.push(Line::from(vec![
-        Span::styled("  Name: ", Style::default().bold().fg(Color::Cyan)),
+        Span::styled("  Name: ", Style::default().bold().fg(theme.accent)),
         Span::raw(&tc.name),
     ]));
 
     if let Some(ref classname) = tc.classname {
         lines.push(Line::from(vec![
-            Span::styled(" Class: ", Style::default().bold().fg(Color::Cyan)),
+            Span::styled(" Class: ", Style::default().bold().fg(theme.accent)),
             Span::raw(classname),
         ]));
     }
 
     if let Some(ref file_path) = tc.file {
         lines.push(Line::from(vec![
-            Span::styled("  File: ", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("  File: ", Style::default().bold().fg(theme.accent)),
             Span::raw(file_path),
         ]));
     }
 
     lines.push(Line::from(vec![
-        Span::styled("  Time: ", Style::default().bold().fg(Color::Cyan)),
+        Span::styled("  Time: ", Style::default().bold().fg(theme.accent)),
         Span::raw(tc.time.map(|t| format!("{:.3}s", t)).unwrap_or_default()),
     ]));
 
     lines.push(Line::from(vec![
-        Span::styled("Status: ", Style::default().bold().fg(Color::Cyan)),
+        Span::styled("Status: ", Style::default().bold().fg(theme.accent)),
         Span::styled(status_text, Style::default().fg(status_color).bold()),
     ]));
+    if let Some(reason) = tc.skipped.as_ref().and_then(|s| s.reason()) {
+        lines.push(Line::from(vec![
+            Span::styled("Reason: ", Style::default().bold().fg(theme.accent)),
+            Span::styled(reason, Style::default().fg(theme.skipped)),
+        ]));
+    }
+    let reruns = tc.reruns().count();
+    if reruns > 0 {
+        let attempts = if reruns == 1 { "attempt" } else { "attempts" };
+        lines.push(Line::from(vec![
+            Span::styled("Reruns: ", Style::default().bold().fg(theme.accent)),
+            Span::styled(
+                if tc.is_flaky() {
+                    format!("passed after {} failed {} (flaky)", reruns, attempts)
+                } else {
+                    format!("{} more failed {}", reruns, attempts)
+                },
+                Style::default().fg(theme.skipped),
+            ),
+        ]));
+    }
 
     lines.push(Line::raw(""));
 
     if let Some(ref failure) = tc.failure {
+        sections.headings.push(lines.len());
         lines.push(Line::styled(
             "── Failure ──────────────────────────────────────────",
-            Style::default().fg(Color::Red).bold(),
+            Style::default().fg(theme.failed).bold(),
         ));
         if let Some(ref msg) = failure.message {
-            for l in msg.lines() {
-                lines.push(Line::styled(l.to_string(), Style::default().fg(Color::Red)));
-            }
+            lines.extend(with_blobs(&ansi::strip(msg), pretty, theme, |text| {
+                assertion::message_lines(text, theme.failed, theme)
+            }));
         }
         if let Some(ref body) = failure.body {
             lines.push(Line::raw(""));
-            for l in body.lines() {
-                lines.push(Line::raw(format!("  {}", l)));
-            }
+            sections.trace.get_or_insert(lines.len());
+            push_trace(&mut lines, &mut sections, body, theme, numbered, pretty);
         }
         lines.push(Line::raw(""));
     }
 
     if let Some(ref error) = tc.error {
+        sections.headings.push(lines.len());
         lines.push(Line::styled(
             "── Error ────────────────────────────────────────────",
-            Style::default().fg(Color::Magenta).bold(),
+            Style::default().fg(theme.errored).bold(),
         ));
         if let Some(ref msg) = error.message {
-            for l in msg.lines() {
-                lines.push(Line::styled(
-                    l.to_string(),
-                    Style::default().fg(Color::Magenta),
-                ));
-            }
+            lines.extend(with_blobs(&ansi::strip(msg), pretty, theme, |text| {
+                assertion::message_lines(text, theme.errored, theme)
+            }));
         }
         if let Some(ref body) = error.body {
             lines.push(Line::raw(""));
-            for l in body.lines() {
-                lines.push(Line::raw(format!("  {}", l)));
-            }
+            sections.trace.get_or_insert(lines.len());
+            push_trace(&mut lines, &mut sections, body, theme, numbered, pretty);
         }
         lines.push(Line::raw(""));
     }
 
-    if let Some(ref stdout) = tc.system_out {
-        let trimmed = stdout.trim();
-        if !trimmed.is_empty() {
-            lines.push(Line::styled(
-                "── System Out ───────────────────────────────────────",
-                Style::default().fg(Color::Blue).bold(),
-            ));
-            for l in trimmed.lines() {
-                lines.push(Line::raw(format!("  {}", l)));
+    if reruns > 0 {
+        sections.headings.push(lines.len());
+        lines.push(Line::styled(
+            "── Reruns ───────────────────────────────────────────",
+            Style::default().fg(theme.skipped).bold(),
+        ));
+        for (i, rerun) in tc.reruns().enumerate() {
+            let mut heading = vec![Span::styled(
+                format!("Attempt {}", i + 1),
+                Style::default().fg(theme.skipped).bold(),
+            )];
+            if let Some(kind) = &rerun.kind {
+                heading.push(Span::styled(
+                    format!("  {}", kind),
+                    Style::default().fg(theme.muted),
+                ));
+            }
+            lines.push(Line::from(heading));
+            if let Some(message) = &rerun.message {
+                for l in ansi::strip(message).lines() {
+                    lines.push(Line::styled(
+                        format!("  {}", l),
+                        Style::default().fg(theme.skipped),
+                    ));
+                }
+            }
+            if let Some(stack_trace) = &rerun.stack_trace {
+                push_trace(
+                    &mut lines,
+                    &mut sections,
+                    stack_trace.trim(),
+                    theme,
+                    numbered,
+                    pretty,
+                );
             }
             lines.push(Line::raw(""));
         }
     }
 
-    if let Some(ref stderr) = tc.system_err {
-        let trimmed = stderr.trim();
-        if !trimmed.is_empty() {
-            lines.push(Line::styled(
-                "── System Err ───────────────────────────────────────",
-                Style::default().fg(Color::Yellow).bold(),
-            ));
-            for l in trimmed.lines() {
+    (lines, sections)
+}
+
+/// Appends a stack trace, or a diff when the body holds one, highlighted
+/// and optionally numbered, noting the row of each frame.
+fn push_trace(
+    lines: &mut Vec<Line>,
+    sections: &mut Sections,
+    body: &str,
+    theme: &Theme,
+    numbered: bool,
+    pretty: bool,
+) {
+    let body = ansi::strip(body);
+    let diff = assertion::has_diff(&body);
+    let mut trace = with_blobs(&body, pretty, theme, |text| {
+        text.lines()
+            .map(|l| {
+                if diff {
+                    assertion::diff_line(l, theme)
+                } else {
+                    highlight::trace_line(l, theme)
+                }
+            })
+            .collect()
+    });
+    for (i, line) in trace.iter().enumerate() {
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        if let Some(frame) = editor::parse_frame(&text) {
+            sections.frames.push((lines.len() + i, frame));
+        }
+    }
+    if numbered {
+        number_lines(&mut trace, theme);
+    }
+    lines.extend(trace);
+}
+
+/// Prefixes each line with its 1-based number in a muted gutter.
+fn number_lines(lines: &mut [Line], theme: &Theme) {
+    let width = lines.len().to_string().len();
+    for (i, line) in lines.iter_mut().enumerate() {
+        line.spans.insert(
+            0,
+            Span::styled(
+                format!("{:>width$} │", i + 1),
+                Style::default().fg(theme.muted),
+            ),
+        );
+    }
+}
+
+/// The full detail view: summary and failure, then captured output.
+fn detail_lines<'a>(tc: &'a TestCase, theme: &Theme, ansi: bool) -> Vec<Line<'a>> {
+    detail_sections(tc, theme, ansi, false, false).0
+}
+
+/// The detail view's lines and where each numbered section starts.
+pub fn detail_sections<'a>(
+    tc: &'a TestCase,
+    theme: &Theme,
+    ansi: bool,
+    numbered: bool,
+    pretty: bool,
+) -> (Vec<Line<'a>>, Sections) {
+    let (mut lines, mut sections) = summary_lines(tc, theme, numbered, pretty);
+    let stdout = output_lines(
+        tc.system_out.as_deref(),
+        Style::default(),
+        ansi,
+        theme,
+        numbered,
+        pretty,
+    );
+    if !stdout.is_empty() {
+        sections.headings.push(lines.len());
+        lines.push(Line::styled(
+            "── System Out ───────────────────────────────────────",
+            Style::default().fg(theme.stdout).bold(),
+        ));
+        sections.stdout = Some(lines.len());
+        lines.extend(stdout);
+        lines.push(Line::raw(""));
+    }
+    let stderr = output_lines(
+        tc.system_err.as_deref(),
+        Style::default().fg(theme.stderr),
+        ansi,
+        theme,
+        numbered,
+        pretty,
+    );
+    if !stderr.is_empty() {
+        sections.headings.push(lines.len());
+        lines.push(Line::styled(
+            "── System Err ───────────────────────────────────────",
+            Style::default().fg(theme.stderr).bold(),
+        ));
+        sections.stderr = Some(lines.len());
+        lines.extend(stderr);
+        lines.push(Line::raw(""));
+    }
+    (lines, sections)
+}
+
+/// Captured output, indented, with surrounding blank lines trimmed. ANSI
+/// colors are rendered when `ansi` is set and stripped otherwise.
+fn output_lines(
+    output: Option<&str>,
+    style: Style,
+    ansi: bool,
+    theme: &Theme,
+    numbered: bool,
+    pretty: bool,
+) -> Vec<Line<'static>> {
+    let trimmed = output.unwrap_or_default().trim();
+    let mut lines = with_blobs(trimmed, pretty, theme, |text| {
+        if ansi {
+            ansi::to_lines(text, style, "  ")
+        } else {
+            ansi::strip(text)
+                .lines()
+                .map(|l| Line::styled(format!("  {}", l), style))
+                .collect()
+        }
+    });
+    if numbered {
+        number_lines(&mut lines, theme);
+    }
+    lines
+}
+
+/// `text` rendered by `plain`, except that with `pretty` set any embedded
+/// JSON or XML is pretty-printed on lines of its own.
+fn with_blobs(
+    text: &str,
+    pretty: bool,
+    theme: &Theme,
+    plain: impl Fn(&str) -> Vec<Line<'static>>,
+) -> Vec<Line<'static>> {
+    if !pretty {
+        return plain(text);
+    }
+    pretty::segments(text, theme)
+        .into_iter()
+        .flat_map(|segment| match segment {
+            Segment::Text(text) => plain(text),
+            Segment::Blob(lines) => lines,
+        })
+        .collect()
+}
+
+/// New failures and fixes side by side, with duration regressions below.
+/// All three panes scroll together.
+fn render_diff(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let Some(FileDiff { base, head, diff }) = &app.diff else {
+        app.set_max_scroll(DetailPane::Main, 0);
+        return;
+    };
+
+    let [header_area, changes_area, slower_area] = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Fill(3),
+        Constraint::Fill(2),
+    ])
+    .areas(area);
+    let [failures_area, fixed_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(changes_area);
+
+    let header = Line::from(vec![
+        Span::styled(
+            format!(" {} ", base),
+            Style::default().fg(theme.text).bold(),
+        ),
+        Span::styled("→", Style::default().fg(theme.accent)),
+        Span::styled(
+            format!(" {}  ", head),
+            Style::default().fg(theme.text).bold(),
+        ),
+        Span::styled(
+            format!("{} still failing", diff.still_failing),
+            Style::default().fg(theme.muted),
+        ),
+        Span::styled(
+            format!("  +{} / −{} tests", diff.added, diff.removed),
+            Style::default().fg(theme.muted),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(header), header_area);
+
+    let failures: Vec<Line> = diff
+        .new_failures
+        .iter()
+        .flat_map(|change| {
+            let (label, color) = status_badge(change.after, theme);
+            let mut lines = vec![Line::from(vec![
+                Span::styled(format!(" [{}] ", label), Style::default().fg(color)),
+                Span::raw(short_label(&change.test)),
+                Span::styled(
+                    if change.before.is_none() {
+                        "  (new)"
+                    } else {
+                        ""
+                    },
+                    Style::default().fg(theme.muted),
+                ),
+            ])];
+            if let Some(message) = &change.message {
+                let first = message.lines().next().unwrap_or_default();
                 lines.push(Line::styled(
-                    format!("  {}", l),
-                    Style::default().fg(Color::Yellow),
+                    format!("        {}", first),
+                    Style::default().fg(theme.muted),
                 ));
             }
-            lines.push(Line::raw(""));
-        }
+            lines
+        })
+        .collect();
+    let fixed: Vec<Line> = diff
+        .fixed
+        .iter()
+        .map(|change| {
+            let (label, color) = status_badge(change.after, theme);
+            Line::from(vec![
+                Span::styled(format!(" [{}] ", label), Style::default().fg(color)),
+                Span::raw(short_label(&change.test)),
+            ])
+        })
+        .collect();
+    let slower: Vec<Line> = diff
+        .slower
+        .iter()
+        .map(|change| {
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:>+8.2}s ", change.delta()),
+                    Style::default().fg(theme.failed),
+                ),
+                Span::styled(
+                    format!("{:>7.2}s → {:>7.2}s  ", change.before, change.after),
+                    Style::default().fg(theme.muted),
+                ),
+                Span::raw(short_label(&change.test)),
+            ])
+        })
+        .collect();
+
+    let panes = [
+        (
+            failures_area,
+            format!(" New failures ({}) ", diff.new_failures.len()),
+            failures,
+        ),
+        (fixed_area, format!(" Fixed ({}) ", diff.fixed.len()), fixed),
+        (
+            slower_area,
+            format!(" Slower ({}) ", diff.slower.len()),
+            slower,
+        ),
+    ];
+    // The panes scroll together, as far as the longest needs.
+    let furthest = panes
+        .iter()
+        .map(|(pane, _, lines)| {
+            lines
+                .len()
+                .saturating_sub(pane.height.saturating_sub(2) as usize)
+        })
+        .max()
+        .unwrap_or(0);
+    app.set_max_scroll(DetailPane::Main, furthest.min(u16::MAX as usize) as u16);
+    for (pane, title, lines) in panes {
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent));
+        let len = lines.len();
+        let lines = if len == 0 {
+            vec![Line::styled(" None", Style::default().fg(theme.muted))]
+        } else {
+            lines
+        };
+        let max_scroll = len.saturating_sub(pane.height.saturating_sub(2) as usize);
+        let scroll = (app.scroll_offset as usize).min(max_scroll);
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll as u16, 0));
+        frame.render_widget(paragraph, pane);
+        render_scrollbar(frame, pane, len, scroll);
     }
+}
+
+/// Charts of pass/fail counts and total duration per run, above a table
+/// of per-suite sparklines.
+fn render_history(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let history = history::build(&app.files);
+    let runs = &history.runs;
+
+    let [charts_area, suites_area] =
+        Layout::vertical([Constraint::Length(12), Constraint::Fill(1)]).areas(area);
+    let [counts_area, time_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .areas(charts_area);
+
+    let series = |value: &dyn Fn(&history::RunPoint) -> f64| -> Vec<(f64, f64)> {
+        runs.iter()
+            .enumerate()
+            .map(|(i, run)| (i as f64, value(run)))
+            .collect()
+    };
+    let passed = series(&|r| r.passed as f64);
+    let failed = series(&|r| r.failed as f64);
+    let skipped = series(&|r| r.skipped as f64);
+    let time = series(&|r| r.time);
+
+    // `2026-01-15T09:30:00` → `01-15 09:30`
+    let run_label = |run: &history::RunPoint| match run.timestamp.as_deref() {
+        Some(ts) if ts.len() >= 16 && ts.is_char_boundary(16) => ts[5..16].replace('T', " "),
+        Some(ts) => ts.to_string(),
+        None => run.filename.clone(),
+    };
+    let x_labels = vec![
+        Span::raw(runs.first().map(run_label).unwrap_or_default()),
+        Span::raw(runs.last().map(run_label).unwrap_or_default()),
+    ];
+    let x_axis = || {
+        Axis::default()
+            .bounds([0.0, (runs.len().max(2) - 1) as f64])
+            .labels(x_labels.clone())
+            .style(Style::default().fg(theme.muted))
+    };
+    let y_axis = |max: f64, unit: &str| {
+        let max = if max > 0.0 { max } else { 1.0 };
+        Axis::default()
+            .bounds([0.0, max])
+            .labels(vec![
+                Span::raw("0"),
+                Span::raw(format!("{:.0}{}", max, unit)),
+            ])
+            .style(Style::default().fg(theme.muted))
+    };
+    let dataset = |name: &'static str, data, color| {
+        Dataset::default()
+            .name(name)
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color))
+            .data(data)
+    };
+
+    let max_count = runs
+        .iter()
+        .map(|r| r.passed.max(r.failed).max(r.skipped))
+        .max()
+        .unwrap_or(0) as f64;
+    let counts = Chart::new(vec![
+        dataset("passed", &passed, theme.passed),
+        dataset("failed", &failed, theme.failed),
+        dataset("skipped", &skipped, theme.skipped),
+    ])
+    .block(
+        Block::default()
+            .title(format!(" Results over {} runs ", runs.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent)),
+    )
+    .x_axis(x_axis())
+    .y_axis(y_axis(max_count, ""))
+    .hidden_legend_constraints((Constraint::Ratio(1, 2), Constraint::Ratio(1, 1)));
+    frame.render_widget(counts, counts_area);
+
+    let max_time = runs.iter().map(|r| r.time).fold(0.0, f64::max);
+    let durations = Chart::new(vec![dataset("duration", &time, theme.accent)])
+        .block(
+            Block::default()
+                .title(" Total duration ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent)),
+        )
+        .x_axis(x_axis())
+        .y_axis(y_axis(max_time, "s"));
+    frame.render_widget(durations, time_area);
+
+    // Room left by the two sparklines and the latest run's summary.
+    let available = (suites_area.width as usize)
+        .saturating_sub(2 * runs.len() + 32)
+        .max(MIN_NAME_WIDTH);
+    let name_width = history
+        .suites
+        .iter()
+        .map(|s| s.name.width())
+        .max()
+        .unwrap_or(0)
+        .min(available);
+    let lines: Vec<Line> = history
+        .suites
+        .iter()
+        .map(|suite| {
+            let failures: Vec<Option<f64>> = suite
+                .points
+                .iter()
+                .map(|p| p.map(|p| p.failed as f64))
+                .collect();
+            let times: Vec<Option<f64>> = suite.points.iter().map(|p| p.map(|p| p.time)).collect();
+            let latest = suite.points.iter().rev().flatten().next();
+            let latest_failed = latest.is_some_and(|p| p.failed > 0);
+            Line::from(vec![
+                Span::styled(
+                    format!(" {} ", fit(&suite.name, name_width)),
+                    Style::default().fg(if latest_failed {
+                        theme.failed
+                    } else {
+                        theme.passed
+                    }),
+                ),
+                Span::styled(
+                    history::sparkline(&failures),
+                    Style::default().fg(theme.failed),
+                ),
+                Span::raw("  "),
+                Span::styled(
+                    history::sparkline(&times),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::styled(
+                    latest
+                        .map(|p| {
+                            format!(
+                                "  {}/{} passed  {:.1}s",
+                                p.passed,
+                                p.passed + p.failed,
+                                p.time
+                            )
+                        })
+                        .unwrap_or_default(),
+                    Style::default().fg(theme.muted),
+                ),
+            ])
+        })
+        .collect();
 
-    let title = format!(" Detail — {} ", truncate_str(&tc.name, 50));
     let block = Block::default()
-        .title(title)
+        .title(" Suites — failures, duration, latest run ")
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan));
-
-    let paragraph = Paragraph::new(lines)
-        .block(block)
-        .wrap(Wrap { trim: false })
-        .scroll((app.scroll_offset, 0));
+        .border_style(Style::default().fg(theme.accent));
+    let len = lines.len();
+    let max_scroll = len.saturating_sub(suites_area.height.saturating_sub(2) as usize);
+    app.set_max_scroll(DetailPane::Main, max_scroll.min(u16::MAX as usize) as u16);
+    let scroll = (app.scroll_offset as usize).min(max_scroll);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((scroll as u16, 0)),
+        suites_area,
+    );
+    render_scrollbar(frame, suites_area, len, scroll);
+}
 
-    frame.render_widget(paragraph, area);
+/// `Class.name` without the package, to fit the split panes.
+fn short_label(test: &TestKey) -> String {
+    let class = test.classname.as_deref().unwrap_or(&test.suite);
+    let class = class.rsplit('.').next().unwrap_or(class);
+    format!("{}.{}", class, test.name)
 }
 
 fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
     let [stats_area, keys_area] =
         Layout::vertical([Constraint::Length(1), Constraint::Length(1)]).areas(area);
 
-    let stats_line = Line::from(vec![
+    let mut stats_line = Line::from(vec![
         Span::styled(" Total: ", Style::default().bold()),
         Span::styled(
             format!("{} ", app.aggregate_tests()),
-            Style::default().fg(Color::White).bold(),
+            Style::default().fg(theme.text).bold(),
         ),
         Span::raw("│ "),
-        Span::styled("Passed: ", Style::default().fg(Color::Green)),
+        Span::styled("Passed: ", Style::default().fg(theme.passed)),
         Span::styled(
             format!("{} ", app.aggregate_passed()),
-            Style::default().fg(Color::Green).bold(),
+            Style::default().fg(theme.passed).bold(),
         ),
         Span::raw("│ "),
-        Span::styled("Failed: ", Style::default().fg(Color::Red)),
+        Span::styled("Failed: ", Style::default().fg(theme.failed)),
         Span::styled(
             format!("{} ", app.aggregate_failures()),
-            Style::default().fg(Color::Red).bold(),
+            Style::default().fg(theme.failed).bold(),
         ),
         Span::raw("│ "),
-        Span::styled("Errors: ", Style::default().fg(Color::Magenta)),
+        Span::styled("Errors: ", Style::default().fg(theme.errored)),
         Span::styled(
             format!("{} ", app.aggregate_errors()),
-            Style::default().fg(Color::Magenta).bold(),
+            Style::default().fg(theme.errored).bold(),
         ),
         Span::raw("│ "),
-        Span::styled("Skipped: ", Style::default().fg(Color::Yellow)),
+        Span::styled("Skipped: ", Style::default().fg(theme.skipped)),
         Span::styled(
             format!("{}", app.aggregate_skipped()),
-            Style::default().fg(Color::Yellow).bold(),
+            Style::default().fg(theme.skipped).bold(),
         ),
     ]);
+    let quarantined = app.aggregate_quarantined();
+    if quarantined > 0 {
+        stats_line.push_span(Span::raw(" │ "));
+        stats_line.push_span(Span::styled(
+            format!(
+                "Quarantined: {}{}",
+                quarantined,
+                if app.count_quarantined {
+                    " (counted)"
+                } else {
+                    ""
+                }
+            ),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    if !app.prefilter.is_empty() {
+        stats_line.push_span(Span::raw(" │ "));
+        stats_line.push_span(Span::styled(
+            "pre-filtered",
+            Style::default().fg(theme.muted),
+        ));
+    }
+    if app.watching {
+        stats_line.push_span(Span::raw(" │ "));
+        stats_line.push_span(Span::styled("watching", Style::default().fg(theme.accent)));
+    } else if app.tailing {
+        stats_line.push_span(Span::raw(" │ "));
+        stats_line.push_span(Span::styled("tailing", Style::default().fg(theme.accent)));
+    }
 
     let keys_line = match app.view {
         View::SuiteList => Line::from(vec![
-            Span::styled(" j/k", Style::default().bold().fg(Color::Cyan)),
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
             Span::raw(" navigate  "),
-            Span::styled("Enter", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
             Span::raw(" open  "),
+            Span::styled("/", Style::default().bold().fg(theme.accent)),
+            Span::raw(" search  "),
+            Span::styled("f", Style::default().bold().fg(theme.accent)),
+            Span::raw(" filter  "),
+            Span::styled("s", Style::default().bold().fg(theme.accent)),
+            Span::raw(" sort  "),
+            Span::styled("t", Style::default().bold().fg(theme.accent)),
+            Span::raw(" tree  "),
+            Span::styled("i", Style::default().bold().fg(theme.accent)),
+            Span::raw(" stats  "),
+            Span::styled("p", Style::default().bold().fg(theme.accent)),
+            Span::raw(" properties  "),
+            Span::styled("^P", Style::default().bold().fg(theme.accent)),
+            Span::raw(" find  "),
             if app.multi_file {
-                Span::styled("Tab", Style::default().bold().fg(Color::Cyan))
+                Span::styled("Tab", Style::default().bold().fg(theme.accent))
             } else {
                 Span::raw("")
             },
@@ -387,41 +3005,502 @@ fn render_status_bar(frame: &mut Frame, area: Rect, app: &App) {
             } else {
                 Span::raw("")
             },
-            Span::styled("q", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
+            Span::raw(" quit"),
+        ]),
+        View::Tree => Line::from(vec![
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
+            Span::raw(" navigate  "),
+            Span::styled("Space", Style::default().bold().fg(theme.accent)),
+            Span::raw(" expand  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
+            Span::raw(" open  "),
+            Span::styled("/", Style::default().bold().fg(theme.accent)),
+            Span::raw(" search  "),
+            Span::styled("f", Style::default().bold().fg(theme.accent)),
+            Span::raw(" filter  "),
+            Span::styled("t", Style::default().bold().fg(theme.accent)),
+            Span::raw(" list view  "),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
             Span::raw(" quit"),
         ]),
         View::TestList => Line::from(vec![
-            Span::styled(" j/k", Style::default().bold().fg(Color::Cyan)),
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
+            Span::raw(" navigate  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
+            Span::raw(" detail  "),
+            Span::styled("/", Style::default().bold().fg(theme.accent)),
+            Span::raw(" search  "),
+            Span::styled("Alt+1-5", Style::default().bold().fg(theme.accent)),
+            Span::raw(" tab  "),
+            Span::styled("s", Style::default().bold().fg(theme.accent)),
+            Span::raw(" sort  "),
+            Span::styled("c", Style::default().bold().fg(theme.accent)),
+            Span::raw(" columns  "),
+            Span::styled("Space/V", Style::default().bold().fg(theme.accent)),
+            Span::raw(" pick  "),
+            Span::styled("O", Style::default().bold().fg(theme.accent)),
+            Span::raw(" suite output  "),
+            Span::styled("^P", Style::default().bold().fg(theme.accent)),
+            Span::raw(" find  "),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
+            Span::raw(" back  "),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
+            Span::raw(" quit"),
+        ]),
+        View::Groups | View::Skips => Line::from(vec![
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
+            Span::raw(" navigate  "),
+            Span::styled("Space", Style::default().bold().fg(theme.accent)),
+            Span::raw(" expand  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
+            Span::raw(" open  "),
+            if app.view == View::Groups {
+                Span::styled("F", Style::default().bold().fg(theme.accent))
+            } else {
+                Span::raw("")
+            },
+            match app.view {
+                View::Groups if app.group_by_exception => Span::raw(" by message  "),
+                View::Groups => Span::raw(" by exception  "),
+                _ => Span::raw(""),
+            },
+            Span::styled("/", Style::default().bold().fg(theme.accent)),
+            Span::raw(" search  "),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
+            Span::raw(" back  "),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
+            Span::raw(" quit"),
+        ]),
+        View::Durations => Line::from(vec![
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
+            Span::raw(" navigate  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
+            Span::raw(" open suite  "),
+            Span::styled("/", Style::default().bold().fg(theme.accent)),
+            Span::raw(" search  "),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
+            Span::raw(" back  "),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
+            Span::raw(" quit"),
+        ]),
+        View::Packages => Line::from(vec![
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
             Span::raw(" navigate  "),
-            Span::styled("Enter", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("Space", Style::default().bold().fg(theme.accent)),
+            Span::raw(" expand  "),
+            Span::styled("h", Style::default().bold().fg(theme.accent)),
+            Span::raw(" collapse  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
+            Span::raw(" open  "),
+            Span::styled("f", Style::default().bold().fg(theme.accent)),
+            Span::raw(" filter  "),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
+            Span::raw(" back  "),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
+            Span::raw(" quit"),
+        ]),
+        View::Slowest => Line::from(vec![
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
+            Span::raw(" navigate  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
             Span::raw(" detail  "),
-            Span::styled("Esc", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("/", Style::default().bold().fg(theme.accent)),
+            Span::raw(" search  "),
+            Span::styled("f", Style::default().bold().fg(theme.accent)),
+            Span::raw(" filter  "),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
             Span::raw(" back  "),
-            Span::styled("q", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
             Span::raw(" quit"),
         ]),
+        View::TestDetail if app.selected_frame.is_some() => Line::from(vec![
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
+            Span::raw(" next/prev frame  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
+            Span::raw(" open in editor  "),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
+            Span::raw(" done"),
+        ]),
         View::TestDetail => Line::from(vec![
-            Span::styled(" j/k", Style::default().bold().fg(Color::Cyan)),
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
+            Span::raw(" scroll  "),
+            Span::styled("J/K", Style::default().bold().fg(theme.accent)),
+            Span::raw(" next/prev test  "),
+            Span::styled("y/Y", Style::default().bold().fg(theme.accent)),
+            Span::raw(" copy failure/all  "),
+            Span::styled("e", Style::default().bold().fg(theme.accent)),
+            Span::raw(" edit  "),
+            Span::styled("Enter", Style::default().bold().fg(theme.accent)),
+            Span::raw(" frames  "),
+            Span::styled("|", Style::default().bold().fg(theme.accent)),
+            Span::raw(" pager  "),
+            Span::styled("o", Style::default().bold().fg(theme.accent)),
+            Span::raw(" layout  "),
+            Span::styled("#", Style::default().bold().fg(theme.accent)),
+            Span::raw(" numbers  "),
+            Span::styled(":", Style::default().bold().fg(theme.accent)),
+            Span::raw(" line  "),
+            Span::styled("{/}", Style::default().bold().fg(theme.accent)),
+            Span::raw(" sections  "),
+            Span::styled("r", Style::default().bold().fg(theme.accent)),
+            Span::raw(" raw XML  "),
+            Span::styled("w", Style::default().bold().fg(theme.accent)),
+            Span::raw(if app.wrap_detail {
+                " no-wrap  "
+            } else {
+                " wrap  "
+            }),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
+            Span::raw(" back  "),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
+            Span::raw(" quit"),
+        ]),
+        View::Diff | View::History => Line::from(vec![
+            Span::styled(" j/k", Style::default().bold().fg(theme.accent)),
             Span::raw(" scroll  "),
-            Span::styled("Esc", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
             Span::raw(" back  "),
-            Span::styled("q", Style::default().bold().fg(Color::Cyan)),
+            Span::styled("?", Style::default().bold().fg(theme.accent)),
+            Span::raw(" help  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
             Span::raw(" quit"),
         ]),
     };
 
+    let keys_line = if let Some(ref message) = app.status_message {
+        Line::styled(format!(" {}", message), Style::default().fg(theme.accent))
+    } else if let Some(count) = app.pending_count {
+        Line::styled(
+            format!(" {}", count),
+            Style::default().fg(theme.text).bold(),
+        )
+    } else if app.input_mode == InputMode::Export {
+        Line::from(vec![
+            Span::styled(" export to: ", Style::default().bold().fg(theme.accent)),
+            Span::styled(app.export_input.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+            Span::raw(if app.export_detail {
+                "  (.md for Markdown)".to_string()
+            } else {
+                format!("  {} tests  (.md for Markdown)", app.picked.len())
+            }),
+        ])
+    } else if app.input_mode == InputMode::AddFiles {
+        Line::from(vec![
+            Span::styled(" add: ", Style::default().bold().fg(theme.accent)),
+            Span::styled(app.add_input.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+            Span::styled(
+                if app.add_completions.is_empty() {
+                    "  (Tab completes)".to_string()
+                } else {
+                    format!("  {}", app.add_completions.join("  "))
+                },
+                Style::default().fg(theme.muted),
+            ),
+        ])
+    } else if app.input_mode == InputMode::GoToLine {
+        Line::from(vec![
+            Span::styled(" line: ", Style::default().bold().fg(theme.accent)),
+            Span::styled(app.line_input.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+            Span::raw("  (prefix o stdout, e stderr, t stack trace)"),
+        ])
+    } else if app.input_mode == InputMode::ClassFilter {
+        Line::from(vec![
+            Span::styled(" classes: ", Style::default().bold().fg(theme.accent)),
+            Span::styled(app.class_input.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+            Span::raw(format!(
+                "  {} tests  (* and ? wildcards; empty shows all)",
+                app.class_input_matches()
+            )),
+        ])
+    } else if app.input_mode == InputMode::SidebarFilter {
+        Line::from(vec![
+            Span::styled(" files: ", Style::default().bold().fg(theme.accent)),
+            Span::styled(app.sidebar_filter.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+            Span::raw(format!(
+                "  {} of {} files  (! for failing only)",
+                app.sidebar_entries().iter().flatten().count(),
+                app.files.len()
+            )),
+        ])
+    } else if app.input_mode == InputMode::Search {
+        Line::from(vec![
+            Span::styled(" /", Style::default().bold().fg(theme.accent)),
+            Span::styled(app.search_query.clone(), Style::default().fg(theme.text)),
+            Span::styled("█", Style::default().fg(theme.accent)),
+            Span::raw(format!("  {} matches", app.search_matches().len())),
+        ])
+    } else if app.has_search() {
+        let matches = app.search_matches();
+        let position = app
+            .current_match_position()
+            .map(|i| format!("{}/{}", i + 1, matches.len()))
+            .unwrap_or_else(|| format!("-/{}", matches.len()));
+        Line::from(vec![
+            Span::styled(
+                format!(" /{} ", app.search_query),
+                Style::default().fg(theme.text),
+            ),
+            Span::raw(format!("[{}]  ", position)),
+            Span::styled("n/N", Style::default().bold().fg(theme.accent)),
+            Span::raw(" next/prev  "),
+            Span::styled("Esc", Style::default().bold().fg(theme.accent)),
+            Span::raw(" clear  "),
+            Span::styled("q", Style::default().bold().fg(theme.accent)),
+            Span::raw(" quit"),
+        ])
+    } else {
+        keys_line
+    };
+
     let stats_widget =
-        Paragraph::new(stats_line).style(Style::default().bg(Color::DarkGray).fg(Color::White));
-    let keys_widget = Paragraph::new(keys_line).style(Style::default().fg(Color::DarkGray));
+        Paragraph::new(stats_line).style(Style::default().bg(theme.status_bg).fg(theme.status_fg));
+    let keys_widget = Paragraph::new(keys_line).style(Style::default().fg(theme.muted));
 
     frame.render_widget(stats_widget, stats_area);
     frame.render_widget(keys_widget, keys_area);
 }
 
-fn truncate_str(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
+fn filter_suffix(app: &App) -> String {
+    let mut suffix = String::new();
+    match (app.status_filter, &app.baseline) {
+        (StatusFilter::All, _) => {}
+        (StatusFilter::NewFailures, Some(baseline)) => {
+            suffix.push_str(&format!(" [new failures vs {}]", baseline.name));
+        }
+        (filter, _) => suffix.push_str(&format!(" [{}]", filter.label())),
+    }
+    if app.hide_passed {
+        suffix.push_str(" [passed hidden]");
+    }
+    suffix.push_str(&test_filter_suffix(app));
+    suffix
+}
+
+/// ` [slower than 1s] [com.example.*]` for the duration and class
+/// filters that are set.
+fn test_filter_suffix(app: &App) -> String {
+    let mut suffix = String::new();
+    if let Some(min) = app.min_time {
+        suffix.push_str(&format!(" [slower than {}]", threshold_label(min)));
+    }
+    if let Some(pattern) = &app.class_filter {
+        suffix.push_str(&format!(" [{}]", pattern));
+    }
+    suffix
+}
+
+/// ` (3 of 8)` when some of `total` rows are filtered out.
+fn shown_suffix(shown: usize, total: usize) -> String {
+    if shown == total {
+        String::new()
+    } else {
+        format!(" ({} of {})", shown, total)
+    }
+}
+
+fn suite_sort_suffix(app: &App) -> String {
+    if app.suite_sort == SuiteSort::Report {
+        String::new()
+    } else {
+        format!(" [sort: {}]", app.suite_sort.label())
+    }
+}
+
+/// ` 5m 12K`: how long ago a report was written and its size, so
+/// stale artifacts stand out. Empty for a report piped in.
+fn file_info(file: &FileReport) -> String {
+    let Some(metadata) = &file.metadata else {
+        return String::new();
+    };
+    let age = file
+        .modified()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .map(|d| format!("{} ", short_age(d.as_secs())))
+        .unwrap_or_default();
+    format!(" {}{}", age, human_size(metadata.len()))
+}
+
+/// `42s`, `5m`, `3h`, `12d`.
+fn short_age(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// `512B`, `12K`, `3.4M`.
+fn human_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = 1024 * KB;
+    match bytes {
+        0..KB => format!("{}B", bytes),
+        KB..MB => format!("{}K", bytes / KB),
+        _ => format!("{:.1}M", bytes as f64 / MB as f64),
+    }
+}
+
+/// `2026-01-15T09:30:00.123+01:00` → `2026-01-15 09:30:00`
+fn short_timestamp(timestamp: &str) -> String {
+    timestamp
+        .chars()
+        .take(19)
+        .collect::<String>()
+        .replacen('T', " ", 1)
+}
+
+fn sort_suffix(app: &App) -> String {
+    if app.test_sort == TestSort::Report {
+        String::new()
     } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+        format!(" [sort: {}]", app.test_sort.label())
+    }
+}
+
+/// Splits `text` into spans, styling every case-insensitive occurrence of
+/// `query` so search hits stand out within a row.
+fn highlight_matches(text: String, query: &str, base: Style, theme: &Theme) -> Vec<Span<'static>> {
+    let ranges = search::match_ranges(&text, query);
+    if ranges.is_empty() {
+        return vec![Span::styled(text, base)];
+    }
+
+    let hit = base.bg(theme.match_bg).fg(theme.match_fg);
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for range in ranges {
+        if range.start > last {
+            spans.push(Span::styled(text[last..range.start].to_string(), base));
+        }
+        spans.push(Span::styled(text[range.clone()].to_string(), hit));
+        last = range.end;
+    }
+    if last < text.len() {
+        spans.push(Span::styled(text[last..].to_string(), base));
+    }
+    spans
+}
+
+/// Cuts `s` to at most `max_width` terminal columns, ending in `...` when
+/// anything was dropped. Wide characters (CJK, most emoji) take two
+/// columns and are never split.
+fn truncate_str(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
+        return s.to_string();
+    }
+    let budget = max_width.saturating_sub(3);
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    out.push_str(&"..."[..max_width.min(3)]);
+    out
+}
+
+/// [`truncate_str`], padded with spaces to exactly `width` columns so the
+/// fields after it line up. `format!("{:<n}")` pads by characters, which
+/// is wrong for wide ones.
+fn fit(s: &str, width: usize) -> String {
+    let mut out = truncate_str(s, width);
+    out.push_str(&" ".repeat(width.saturating_sub(out.width())));
+    out
+}
+
+/// Narrowest a name column gets, however little room the other fields
+/// leave.
+const MIN_NAME_WIDTH: usize = 16;
+
+/// Columns left for a name in a bordered, highlighted list once `used`
+/// columns of other fields are accounted for.
+fn name_width(area: Rect, used: usize) -> usize {
+    // Two borders, the `> ` highlight symbol and the space after the name.
+    (area.width as usize)
+        .saturating_sub(used + 5)
+        .max(MIN_NAME_WIDTH)
+}
+
+/// Splits a row's free columns between a test name and its suite name,
+/// favoring the test.
+fn split_width(width: usize) -> (usize, usize) {
+    let name = (width * 3 / 5).max(MIN_NAME_WIDTH);
+    (name, width.saturating_sub(name + 1).max(MIN_NAME_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::FileReport;
+    use crate::keymap::KeyMap;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    const XML: &str = r#"<testsuite name="com.example.Login" tests="2" failures="1">
+    <testcase classname="com.example.Login" name="accepts" time="0.1"/>
+    <testcase classname="com.example.Login" name="rejects" time="3">
+        <failure message="expected 401">at Login.rejects(Login.java:12)</failure>
+    </testcase>
+</testsuite>"#;
+
+    /// The screen after drawing `app`, as rows of text.
+    fn draw(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(160, 40)).unwrap();
+        terminal.draw(|frame| render(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn draws_each_level() {
+        let keymap = KeyMap::new(&Default::default()).unwrap();
+        let report = FileReport::from_xml("r.xml", XML);
+        let mut app = App::new(vec![report], Theme::no_color(), keymap);
+        assert!(draw(&app).contains("com.example.Login"));
+
+        app.enter();
+        let screen = draw(&app);
+        assert!(screen.contains("accepts"));
+        assert!(screen.contains("rejects"));
+
+        app.select_next();
+        app.enter();
+        let screen = draw(&app);
+        assert!(screen.contains("expected 401"));
+        assert!(screen.contains("Login.java:12"));
     }
 }
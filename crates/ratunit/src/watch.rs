@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+/// Watches report files and directories and reports which XML files
+/// changed.
+pub struct Watcher {
+    // Kept alive for as long as we want events.
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    /// Directories whose every XML file is wanted.
+    dirs: Vec<PathBuf>,
+    /// Files watched through their directory; other files there are
+    /// ignored.
+    files: Vec<PathBuf>,
+}
+
+impl Watcher {
+    pub fn new(paths: &[PathBuf]) -> Result<Self> {
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+        let mut watched = BTreeSet::new();
+        for path in paths {
+            // Watch a single file through its directory: test runners and
+            // editors often replace the file rather than writing in place.
+            let dir = if path.is_dir() {
+                dirs.push(canonical(path));
+                path.to_path_buf()
+            } else {
+                files.push(canonical(path));
+                match path.parent() {
+                    Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+                    _ => PathBuf::from("."),
+                }
+            };
+            if watched.insert(canonical(&dir)) {
+                watcher
+                    .watch(&dir, RecursiveMode::NonRecursive)
+                    .with_context(|| format!("Failed to watch: {}", dir.display()))?;
+            }
+        }
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            dirs,
+            files,
+        })
+    }
+
+    /// Drains pending events and returns the XML files they touched.
+    pub fn changed(&self) -> BTreeSet<PathBuf> {
+        let mut changed = BTreeSet::new();
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else { continue };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            changed.extend(event.paths.into_iter().filter(|p| self.wants(p)));
+        }
+        changed
+    }
+
+    fn wants(&self, path: &Path) -> bool {
+        let path = canonical(path);
+        let in_dir = path
+            .parent()
+            .is_some_and(|dir| self.dirs.iter().any(|d| d == dir));
+        self.files.contains(&path) || in_dir && path.extension().is_some_and(|ext| ext == "xml")
+    }
+}
+
+/// `path` made absolute with symlinks resolved, going by its directory
+/// when the file itself is gone.
+pub fn canonical(path: &Path) -> PathBuf {
+    if let Ok(path) = std::fs::canonicalize(path) {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) => {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            std::fs::canonicalize(dir)
+                .map(|dir| dir.join(name))
+                .unwrap_or_else(|_| path.to_path_buf())
+        }
+        _ => path.to_path_buf(),
+    }
+}
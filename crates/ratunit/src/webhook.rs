@@ -0,0 +1,211 @@
+use crate::app::FileReport;
+use crate::test_id::IdFormat;
+use anyhow::{bail, Result};
+use junit_parser::{TestCase, TestStatus};
+use serde_json::{json, Value};
+
+/// The most characters Slack takes in a section or context text.
+const SLACK_TEXT_LIMIT: usize = 3000;
+
+/// Characters of a test name or message kept in a Slack list item.
+const SLACK_LINE_LIMIT: usize = 300;
+
+/// Totals across every report, and the first failing tests.
+struct Summary<'a> {
+    tests: u64,
+    passed: u64,
+    failures: u64,
+    errors: u64,
+    skipped: u64,
+    failing: Vec<(&'a FileReport, &'a TestCase)>,
+    /// Failing tests beyond those listed.
+    more: usize,
+}
+
+impl<'a> Summary<'a> {
+    fn new(files: &'a [FileReport], top: usize) -> Self {
+        let mut failing: Vec<_> = files
+            .iter()
+            .flat_map(|f| f.data.all_cases().map(move |(_, tc)| (f, tc)))
+            .filter(|(_, tc)| matches!(tc.status(), TestStatus::Failed | TestStatus::Errored))
+            .collect();
+        let more = failing.len().saturating_sub(top);
+        failing.truncate(top);
+        let sum = |count: fn(&junit_parser::TestSuites) -> u64| {
+            files.iter().map(|f| count(&f.data)).sum::<u64>()
+        };
+        Summary {
+            tests: sum(|d| d.total_tests()),
+            passed: sum(|d| d.total_passed()),
+            failures: sum(|d| d.total_failures()),
+            errors: sum(|d| d.total_errors()),
+            skipped: sum(|d| d.total_skipped()),
+            failing,
+            more,
+        }
+    }
+
+    fn ok(&self) -> bool {
+        self.failures + self.errors == 0
+    }
+
+    fn headline(&self) -> String {
+        if self.ok() {
+            format!("✅ All {} tests passed", self.passed)
+        } else {
+            format!(
+                "❌ {} of {} tests failing",
+                self.failures + self.errors,
+                self.tests
+            )
+        }
+    }
+}
+
+/// A Slack message with Block Kit blocks: the headline, the totals, the
+/// first `top` failing tests and the reports they came from.
+pub fn slack(files: &[FileReport], top: usize) -> Value {
+    let summary = Summary::new(files, top);
+    let totals = format!(
+        "*{}* passed · *{}* failed · *{}* errors · *{}* skipped",
+        summary.passed, summary.failures, summary.errors, summary.skipped
+    );
+    let mut blocks = vec![
+        json!({"type": "header", "text": {"type": "plain_text", "text": summary.headline()}}),
+        json!({"type": "section", "text": {"type": "mrkdwn", "text": totals}}),
+    ];
+    if !summary.failing.is_empty() {
+        let items: Vec<String> = summary
+            .failing
+            .iter()
+            .map(|(_, tc)| {
+                let name = clip(&IdFormat::Dotted.format(tc), SLACK_LINE_LIMIT);
+                match tc.failure_line() {
+                    Some(message) => format!(
+                        "• `{}` — {}",
+                        name,
+                        slack_escape(&clip(message, SLACK_LINE_LIMIT))
+                    ),
+                    None => format!("• `{}`", name),
+                }
+            })
+            .collect();
+        // Tests are dropped from the end to keep within Slack's limit,
+        // leaving room for the count of the rest.
+        let limit = SLACK_TEXT_LIMIT - 32;
+        let mut lines: Vec<&str> = Vec::new();
+        let mut length = 0;
+        for item in &items {
+            length += item.chars().count() + 1;
+            if length > limit {
+                break;
+            }
+            lines.push(item);
+        }
+        let more = summary.more + items.len() - lines.len();
+        let more_line = format!("…and {} more", more);
+        if more > 0 {
+            lines.push(&more_line);
+        }
+        blocks
+            .push(json!({"type": "section", "text": {"type": "mrkdwn", "text": lines.join("\n")}}));
+    }
+    let names: Vec<&str> = files.iter().map(|f| f.filename.as_str()).collect();
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{
+            "type": "mrkdwn",
+            "text": clip(&slack_escape(&names.join(", ")), SLACK_TEXT_LIMIT),
+        }]
+    }));
+    json!({"text": summary.headline(), "blocks": blocks})
+}
+
+/// A plain JSON summary for webhooks other than Slack's.
+pub fn generic(files: &[FileReport], top: usize) -> Value {
+    let summary = Summary::new(files, top);
+    let failing: Vec<Value> = summary
+        .failing
+        .iter()
+        .map(|(file, tc)| {
+            json!({
+                "name": tc.name,
+                "classname": tc.classname,
+                "status": tc.status(),
+                "message": tc.failure_line(),
+                "report": file.filename,
+            })
+        })
+        .collect();
+    json!({
+        "status": if summary.ok() { "passed" } else { "failed" },
+        "summary": summary.headline(),
+        "tests": summary.tests,
+        "passed": summary.passed,
+        "failures": summary.failures,
+        "errors": summary.errors,
+        "skipped": summary.skipped,
+        "failing": failing,
+        "more_failing": summary.more,
+        "reports": files.iter().map(|f| &f.filename).collect::<Vec<_>>(),
+    })
+}
+
+/// POSTs `payload` as JSON to `url`. Errors leave the URL out: webhook
+/// URLs carry their secret, and CI logs are widely readable.
+pub fn post(url: &str, payload: &Value) -> Result<()> {
+    match ureq::post(url).send_json(payload) {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, _)) => bail!("The webhook answered with HTTP {}", code),
+        Err(ureq::Error::Transport(e)) => bail!("Failed to reach the webhook: {}", e.kind()),
+    }
+}
+
+/// `text` cut to `max` characters, ending in an ellipsis when cut.
+fn clip(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut clipped: String = text.chars().take(max.saturating_sub(1)).collect();
+    clipped.push('…');
+    clipped
+}
+
+/// Escapes the characters Slack's mrkdwn reads as markup.
+fn slack_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slack_sections_stay_within_limit() {
+        let message = "x".repeat(1000);
+        let cases: String = (0..50)
+            .map(|i| {
+                format!(
+                    r#"<testcase classname="C" name="t{}"><failure message="{}"/></testcase>"#,
+                    i, message
+                )
+            })
+            .collect();
+        let xml = format!(
+            r#"<testsuite name="s" tests="50" failures="50">{}</testsuite>"#,
+            cases
+        );
+        let files = [FileReport::from_xml("r.xml", &xml)];
+        let payload = slack(&files, 50);
+        let texts: Vec<&str> = payload["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|b| b["text"]["text"].as_str())
+            .collect();
+        assert!(texts.iter().all(|t| t.chars().count() <= SLACK_TEXT_LIMIT));
+        assert!(texts.last().unwrap().ends_with("more"));
+    }
+}